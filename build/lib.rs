@@ -0,0 +1,184 @@
+//! Build script helpers for exporting `#[gobject::class]` types to C.
+//!
+//! ```no_run
+//! // build.rs
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! gobject_build::generate_headers("src/lib.rs", out_dir).unwrap();
+//! ```
+//!
+//! Only classes declared with `#[gobject::class(ffi)]` produce a header, since the symbols it
+//! declares are emitted by that option. [`generate_gir`] writes introspection data for every class
+//! and interface in the same way.
+
+use gobject_core::{
+    util::Errors, ClassDefinition, ClassOptions, GirNamespace, InterfaceDefinition,
+    InterfaceOptions,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Parses `source` and any out-of-line modules it declares, writing a header for each exported
+/// class into `out_dir`. Returns the paths of the written headers.
+pub fn generate_headers(
+    source: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    std::fs::create_dir_all(out_dir)?;
+    let mut headers = Vec::new();
+    for class in parse_classes(source.as_ref())? {
+        let path = out_dir.join(format!("{}.h", class.0));
+        std::fs::write(&path, class.1)?;
+        headers.push(path);
+    }
+    Ok(headers)
+}
+
+/// Returns the file name stem and header contents for each exported class in `source`.
+pub fn parse_classes(source: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut definitions = Vec::new();
+    visit_file(source, &mut definitions)?;
+    Ok(definitions
+        .into_iter()
+        .filter_map(|def| match def {
+            Definition::Class(class) if class.ffi => {
+                Some((class.c_symbol_prefix(), class.to_c_header()))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Parses `source` like [`generate_headers`], writing `<TypeName>.gir` for each class and
+/// interface into `out_dir`. Returns the paths of the written files.
+pub fn generate_gir(
+    source: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    std::fs::create_dir_all(out_dir)?;
+    let mut files = Vec::new();
+    for (name, gir) in parse_gir(source.as_ref())? {
+        let path = out_dir.join(format!("{}.gir", name));
+        std::fs::write(&path, gir)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// Returns the type name and GIR document for each class and interface in `source`. Namespaces
+/// default to the name and major version of the package being built.
+pub fn parse_gir(source: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut definitions = Vec::new();
+    visit_file(source, &mut definitions)?;
+    Ok(definitions
+        .into_iter()
+        .filter_map(|def| match def {
+            Definition::Class(class) if !class.inner.is_generic() => Some((
+                class.gtype_name(),
+                class.to_gir(&GirNamespace::from_env(class.ns.as_ref())),
+            )),
+            Definition::Class(_) => None,
+            Definition::Interface(iface) => Some((
+                iface.gtype_name(),
+                iface.to_gir(&GirNamespace::from_env(iface.ns.as_ref())),
+            )),
+        })
+        .collect())
+}
+
+enum Definition {
+    Class(Box<ClassDefinition>),
+    Interface(Box<InterfaceDefinition>),
+}
+
+fn visit_file(path: &Path, definitions: &mut Vec<Definition>) -> io::Result<()> {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let contents = std::fs::read_to_string(path)?;
+    let file = syn::parse_file(&contents).map_err(|e| invalid_data(path, e))?;
+    let is_mod_root = matches!(
+        path.file_name().and_then(|n| n.to_str()),
+        Some("lib.rs" | "main.rs" | "mod.rs")
+    );
+    let dir = if is_mod_root {
+        path.parent().map(Path::to_owned).unwrap_or_default()
+    } else {
+        path.with_extension("")
+    };
+    visit_items(path, &dir, file.items, definitions)
+}
+
+fn visit_items(
+    path: &Path,
+    dir: &Path,
+    items: Vec<syn::Item>,
+    definitions: &mut Vec<Definition>,
+) -> io::Result<()> {
+    for item in items {
+        let mut module = match item {
+            syn::Item::Mod(module) => module,
+            _ => continue,
+        };
+        let type_attr = module.attrs.iter().position(|a| {
+            a.path
+                .segments
+                .last()
+                .map(|s| s.ident == "class" || s.ident == "interface")
+                == Some(true)
+        });
+        if let Some(index) = type_attr {
+            let attr = module.attrs.remove(index);
+            definitions.push(parse_definition(path, attr, module)?);
+            continue;
+        }
+        let sub_dir = dir.join(module.ident.to_string());
+        match module.content {
+            Some((_, items)) => visit_items(path, &sub_dir, items, definitions)?,
+            None => {
+                let file = dir.join(format!("{}.rs", module.ident));
+                if file.exists() {
+                    visit_file(&file, definitions)?;
+                } else {
+                    visit_file(&sub_dir.join("mod.rs"), definitions)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_definition(
+    path: &Path,
+    attr: syn::Attribute,
+    module: syn::ItemMod,
+) -> io::Result<Definition> {
+    let is_class = attr.path.segments.last().map(|s| s.ident == "class") == Some(true);
+    let args = match attr.tokens.into_iter().next() {
+        Some(proc_macro2::TokenTree::Group(group)) => group.stream(),
+        _ => Default::default(),
+    };
+    let errors = Errors::new();
+    let go = syn::parse_quote! { gobject };
+    let definition = if is_class {
+        let opts = ClassOptions::parse(args, &errors);
+        Definition::Class(Box::new(ClassDefinition::parse(module, opts, go, &errors)))
+    } else {
+        let opts = InterfaceOptions::parse(args, &errors);
+        Definition::Interface(Box::new(InterfaceDefinition::parse(
+            module, opts, go, &errors,
+        )))
+    };
+    if let Some(e) = errors.into_compile_errors() {
+        return Err(invalid_data(path, e));
+    }
+    Ok(definition)
+}
+
+fn invalid_data(path: &Path, e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), e),
+    )
+}
@@ -0,0 +1,248 @@
+use crate::util::{self, Errors};
+use darling::{util::Flag, FromMeta};
+use heck::ToUpperCamelCase;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse_quote;
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct Attrs {
+    pub name: Option<syn::Ident>,
+    pub ns: Option<syn::Ident>,
+    pub shared: Flag,
+}
+
+#[derive(Debug)]
+pub struct BoxedOptions(Attrs);
+
+impl BoxedOptions {
+    pub fn parse(tokens: TokenStream, errors: &Errors) -> Self {
+        Self(util::parse_list(tokens, errors))
+    }
+}
+
+#[derive(Debug)]
+pub struct BoxedDefinition {
+    pub item: syn::ItemStruct,
+    pub crate_path: syn::Path,
+    pub ns: Option<syn::Ident>,
+    pub name: syn::Ident,
+    pub shared: Option<syn::Type>,
+}
+
+impl BoxedDefinition {
+    pub fn parse(
+        item: syn::ItemStruct,
+        opts: BoxedOptions,
+        crate_path: syn::Path,
+        errors: &Errors,
+    ) -> Self {
+        let attrs = opts.0;
+        if !item.generics.params.is_empty() {
+            errors.push_spanned(&item.generics, "Generic boxed types not supported");
+        }
+        let shared = attrs.shared.is_some().then(|| match &item.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Some(fields.unnamed[0].ty.clone())
+            }
+            fields => {
+                errors.push_spanned(
+                    fields,
+                    "Shared boxed type must be a tuple struct with one reference counted field",
+                );
+                None
+            }
+        });
+        let name = attrs.name.unwrap_or_else(|| item.ident.clone());
+        Self {
+            item,
+            crate_path,
+            ns: attrs.ns,
+            name,
+            shared: shared.flatten(),
+        }
+    }
+    pub fn glib(&self) -> syn::Path {
+        let go = &self.crate_path;
+        parse_quote! { #go::glib }
+    }
+    pub fn gtype_name(&self) -> String {
+        if let Some(ns) = &self.ns {
+            format!("{}{}", ns, self.name)
+        } else {
+            self.name.to_string()
+        }
+        .to_upper_camel_case()
+    }
+    fn static_type_impl(&self) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let gtype_name = self.gtype_name();
+        let once = format_ident!("ONCE", span = Span::mixed_site());
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let (type_impl, register) = if let Some(refcounted) = &self.shared {
+            (
+                quote! {
+                    impl #glib::subclass::shared::SharedType for #ident {
+                        const NAME: &'static ::std::primitive::str = #gtype_name;
+                        type RefCountedType = #refcounted;
+                        #[inline]
+                        fn from_refcounted(this: Self::RefCountedType) -> Self {
+                            Self(this)
+                        }
+                        #[inline]
+                        fn into_refcounted(self) -> Self::RefCountedType {
+                            self.0
+                        }
+                    }
+                },
+                quote! { #glib::subclass::shared::register_shared_type::<#ident>() },
+            )
+        } else {
+            (
+                quote! {
+                    impl #glib::subclass::boxed::BoxedType for #ident {
+                        const NAME: &'static ::std::primitive::str = #gtype_name;
+                    }
+                },
+                quote! { #glib::subclass::boxed::register_boxed_type::<#ident>() },
+            )
+        };
+        quote! {
+            #type_impl
+            impl #glib::StaticType for #ident {
+                fn static_type() -> #glib::Type {
+                    static #once: ::std::sync::Once = ::std::sync::Once::new();
+                    static mut #type_: #glib::Type = #glib::Type::INVALID;
+                    #once.call_once(|| {
+                        let type_ = #register;
+                        unsafe {
+                            #type_ = type_;
+                        }
+                    });
+                    unsafe { #type_ }
+                }
+            }
+        }
+    }
+    fn raw_ptr(&self, value: TokenStream) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        if self.shared.is_some() {
+            quote! {
+                unsafe {
+                    #glib::subclass::shared::RefCounted::into_raw(
+                        <#ident as #glib::subclass::shared::SharedType>::into_refcounted(
+                            <#ident as ::std::clone::Clone>::clone(#value)
+                        )
+                    ) as #glib::ffi::gpointer
+                }
+            }
+        } else {
+            quote! {
+                ::std::boxed::Box::into_raw(::std::boxed::Box::new(
+                    <#ident as ::std::clone::Clone>::clone(#value)
+                )) as #glib::ffi::gpointer
+            }
+        }
+    }
+    fn value_impls(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let ptr = syn::Ident::new("ptr", Span::mixed_site());
+        let (from_ptr, borrow) = if self.shared.is_some() {
+            (
+                quote! {
+                    <Self as #glib::subclass::shared::SharedType>::from_refcounted(
+                        <<Self as #glib::subclass::shared::SharedType>::RefCountedType
+                            as #glib::subclass::shared::RefCounted>::from_raw(#ptr as *const _)
+                    )
+                },
+                None,
+            )
+        } else {
+            (
+                quote! {
+                    *::std::boxed::Box::from_raw(#ptr as *mut Self)
+                },
+                Some(quote! {
+                    unsafe impl<'a> #glib::value::FromValue<'a> for &'a #ident {
+                        type Checker = #glib::value::GenericValueTypeOrNoneChecker<Self>;
+                        unsafe fn from_value(value: &'a #glib::Value) -> Self {
+                            let #ptr = #glib::gobject_ffi::g_value_get_boxed(
+                                #glib::translate::ToGlibPtr::to_glib_none(value).0,
+                            );
+                            ::std::assert!(!#ptr.is_null());
+                            &*(#ptr as *const #ident)
+                        }
+                    }
+                }),
+            )
+        };
+        let s_ident = syn::Ident::new("s", Span::mixed_site());
+        let self_into_ptr = self.raw_ptr(quote! { self });
+        let opt_into_ptr = self.raw_ptr(quote! { #s_ident });
+        quote! {
+            impl #glib::value::ValueType for #ident {
+                type Type = Self;
+            }
+            impl #glib::value::ValueTypeOptional for #ident {}
+            unsafe impl<'a> #glib::value::FromValue<'a> for #ident {
+                type Checker = #glib::value::GenericValueTypeOrNoneChecker<Self>;
+                unsafe fn from_value(value: &'a #glib::Value) -> Self {
+                    let #ptr = #glib::gobject_ffi::g_value_dup_boxed(
+                        #glib::translate::ToGlibPtr::to_glib_none(value).0,
+                    );
+                    ::std::assert!(!#ptr.is_null());
+                    #from_ptr
+                }
+            }
+            #borrow
+            impl #glib::value::ToValue for #ident {
+                fn to_value(&self) -> #glib::Value {
+                    let mut value = #glib::Value::for_value_type::<Self>();
+                    let #ptr = #self_into_ptr;
+                    unsafe {
+                        #glib::gobject_ffi::g_value_take_boxed(
+                            #glib::translate::ToGlibPtrMut::to_glib_none_mut(&mut value).0,
+                            #ptr,
+                        );
+                    }
+                    value
+                }
+                fn value_type(&self) -> #glib::Type {
+                    <Self as #glib::StaticType>::static_type()
+                }
+            }
+            impl #glib::value::ToValueOptional for #ident {
+                fn to_value_optional(#s_ident: ::std::option::Option<&Self>) -> #glib::Value {
+                    let mut value = #glib::Value::for_value_type::<Self>();
+                    let #ptr = match #s_ident {
+                        ::std::option::Option::Some(#s_ident) => #opt_into_ptr,
+                        ::std::option::Option::None => ::std::ptr::null_mut(),
+                    };
+                    unsafe {
+                        #glib::gobject_ffi::g_value_take_boxed(
+                            #glib::translate::ToGlibPtrMut::to_glib_none_mut(&mut value).0,
+                            #ptr,
+                        );
+                    }
+                    value
+                }
+            }
+            impl #go::ParamSpecBuildable for #ident {
+                type ParamSpec = #go::ParamSpecBoxedType<Self>;
+            }
+        }
+    }
+}
+
+impl ToTokens for BoxedDefinition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+        self.static_type_impl().to_tokens(tokens);
+        self.value_impls().to_tokens(tokens);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     util::{self, Errors},
-    Concurrency, Properties, TypeBase, TypeDefinition, TypeMode,
+    Concurrency, Properties, PropertyType, TypeBase, TypeContext, TypeDefinition, TypeMode,
 };
 use darling::{
     util::{Flag, PathList, SpannedValue},
@@ -30,6 +30,17 @@ struct Attrs {
     pub implements: PathList,
     pub inherits: PathList,
     pub sync: Flag,
+    pub ffi: SpannedValue<Flag>,
+    pub dynamic: SpannedValue<Flag>,
+    pub mock: SpannedValue<Flag>,
+    pub dbus: Option<SpannedValue<DBusOptions>>,
+}
+
+/// Options for exporting a class on D-Bus, from `dbus(...)` on the class.
+#[derive(Debug, Clone, FromMeta)]
+pub struct DBusOptions {
+    /// The D-Bus interface name.
+    pub name: syn::LitStr,
 }
 
 impl Attrs {
@@ -41,12 +52,99 @@ impl Attrs {
     }
 }
 
+/// One `Name<Type> = "TypeName"` entry of `instantiate(...)`, registering a generic class for
+/// `Type`. Generic classes must be `final` and have exactly one type parameter.
+#[derive(Debug)]
+pub struct Instantiation {
+    pub ty: syn::Path,
+    pub name: syn::LitStr,
+}
+
+impl syn::parse::Parse for Instantiation {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let name = input.parse()?;
+        Ok(Self { ty, name })
+    }
+}
+
+impl Instantiation {
+    fn type_arg(&self) -> Option<&syn::Type> {
+        let segment = self.ty.segments.last()?;
+        match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                match args.args.first()? {
+                    syn::GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+    fn extract(tokens: TokenStream, errors: &Errors) -> (TokenStream, Vec<Self>) {
+        use proc_macro2::{Delimiter, TokenTree};
+        use syn::parse::Parser;
+
+        let mut rest = Vec::new();
+        let mut instantiations = Vec::new();
+        let mut iter = tokens.into_iter().peekable();
+        while let Some(tt) = iter.next() {
+            let group = match (&tt, iter.peek()) {
+                (TokenTree::Ident(ident), Some(TokenTree::Group(group)))
+                    if ident == "instantiate" && group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    group.stream()
+                }
+                _ => {
+                    rest.push(tt);
+                    continue;
+                }
+            };
+            iter.next();
+            if matches!(iter.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+                iter.next();
+            }
+            let parser = syn::punctuated::Punctuated::<Self, syn::Token![,]>::parse_terminated;
+            match parser.parse2(group) {
+                Ok(list) => instantiations.extend(list),
+                Err(e) => errors.push_syn(e),
+            }
+        }
+        (rest.into_iter().collect(), instantiations)
+    }
+}
+
+/// A virtual method or signal handler that can be replaced in a mock subclass.
+pub(crate) struct MockItem {
+    pub ident: syn::Ident,
+    pub name: String,
+    pub inputs: Vec<syn::Type>,
+    pub owned: Vec<TokenStream>,
+    pub output: syn::ReturnType,
+}
+
+impl MockItem {
+    fn method_type(&self, wrapper_ty: &syn::Type, go: &syn::Path) -> TokenStream {
+        let inputs = &self.inputs;
+        let output = &self.output;
+        let owned = &self.owned;
+        quote! {
+            #go::MockMethod<
+                dyn ::std::ops::Fn(&#wrapper_ty, #(#inputs),*) #output,
+                (#(#owned,)*),
+            >
+        }
+    }
+}
+
 #[derive(Debug)]
-pub struct ClassOptions(Attrs);
+pub struct ClassOptions(Attrs, Vec<Instantiation>);
 
 impl ClassOptions {
     pub fn parse(tokens: TokenStream, errors: &Errors) -> Self {
-        Self(util::parse_list(tokens, errors))
+        let (tokens, instantiate) = Instantiation::extract(tokens, errors);
+        Self(util::parse_list(tokens, errors), instantiate)
     }
 }
 
@@ -65,6 +163,11 @@ pub struct ClassDefinition {
     pub extends: Vec<syn::Path>,
     pub implements: Vec<syn::Path>,
     pub inherits: Vec<syn::Path>,
+    pub ffi: bool,
+    pub ffi_skip: Vec<syn::Ident>,
+    pub mock: bool,
+    pub dbus: Option<DBusOptions>,
+    pub instantiate: Vec<Instantiation>,
 }
 
 impl ClassDefinition {
@@ -74,7 +177,7 @@ impl ClassDefinition {
         crate_path: syn::Path,
         errors: &Errors,
     ) -> Self {
-        let attrs = opts.0;
+        let ClassOptions(attrs, instantiate) = opts;
         attrs.validate(errors);
 
         let mut inner =
@@ -82,11 +185,54 @@ impl ClassDefinition {
 
         if attrs.sync.is_some() {
             inner.concurrency = Concurrency::SendSync;
+            if let Some(span) = inner.history {
+                errors.push(span, "`history` not allowed on sync class");
+            }
+        }
+
+        if attrs.ffi.is_some() {
+            if attrs.wrapper == Some(false) {
+                errors.push(attrs.ffi.span(), "`ffi` requires the wrapper type");
+            }
+            if !inner.generics.params.is_empty() {
+                errors.push(attrs.ffi.span(), "`ffi` not supported on generic classes");
+            }
+        }
+
+        if attrs.dynamic.is_some() {
+            if !inner.generics.params.is_empty() {
+                errors.push(
+                    attrs.dynamic.span(),
+                    "`dynamic` not supported on generic classes",
+                );
+            }
+            inner.dynamic = true;
+        }
+
+        if attrs.mock.is_some() {
+            if attrs.final_.is_some() {
+                errors.push(attrs.mock.span(), "`mock` not allowed on final class");
+            }
+            if attrs.wrapper == Some(false) {
+                errors.push(attrs.mock.span(), "`mock` requires the wrapper type");
+            }
+            if !inner.generics.params.is_empty() {
+                errors.push(attrs.mock.span(), "`mock` not supported on generic classes");
+            }
+        }
+
+        if let Some(dbus) = &attrs.dbus {
+            if attrs.wrapper == Some(false) {
+                errors.push(dbus.span(), "`dbus` requires the wrapper type");
+            }
+            if !inner.generics.params.is_empty() {
+                errors.push(dbus.span(), "`dbus` not supported on generic classes");
+            }
         }
 
         let name = inner.name.clone();
         let final_ = attrs.final_.is_some();
-        let class = Self {
+        let mut class = Self {
             inner,
             ns: attrs.ns,
             class: attrs
@@ -114,6 +260,11 @@ impl ClassDefinition {
             extends: (*attrs.extends).clone(),
             implements: (*attrs.implements).clone(),
             inherits: (*attrs.inherits).clone(),
+            ffi: attrs.ffi.is_some(),
+            ffi_skip: Vec::new(),
+            mock: attrs.mock.is_some(),
+            dbus: attrs.dbus.map(|d| (*d).clone()),
+            instantiate,
         };
 
         if class.final_ {
@@ -132,8 +283,145 @@ impl ClassDefinition {
             }
         }
 
+        class.validate_generics(errors);
+        class.validate_transactions(errors);
+        class.parse_ffi_methods(errors);
+        class.add_property_bindings(errors);
+
         class
     }
+    fn add_property_bindings(&self, errors: &Errors) {
+        let properties = &self.inner.properties;
+        let signals = &self.inner.signals;
+        if properties.iter().all(|p| p.bind.is_none())
+            && signals.iter().all(|s| s.forward.is_none())
+        {
+            return;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+        let source_property = |source: &syn::LitStr, name: String| {
+            let prop = properties.iter().find(|p| {
+                p.field.ident.as_ref().map(|i| i == &name).unwrap_or(false)
+                    || p.name.to_string() == name
+            });
+            match prop {
+                Some(prop) if !prop.get.is_allowed() => {
+                    errors.push_spanned(
+                        source,
+                        format!("Binding source property `{}` is not readable", prop.name),
+                    );
+                    None
+                }
+                Some(prop) if prop.special_type != PropertyType::Object => {
+                    errors.push_spanned(
+                        source,
+                        format!(
+                            "Binding source property `{}` must be an `object` property",
+                            prop.name
+                        ),
+                    );
+                    None
+                }
+                Some(prop) => Some(prop.name.to_string()),
+                None => {
+                    errors.push_spanned(
+                        source,
+                        format!("No property `{}` found for binding source", name),
+                    );
+                    None
+                }
+            }
+        };
+        let sync = self.inner.concurrency == Concurrency::SendSync;
+        let bindings = properties
+            .iter()
+            .filter_map(|prop| {
+                let bind = prop.bind.as_ref()?;
+                if sync {
+                    errors.push_spanned(&bind.source, "`bind` not allowed on sync class");
+                    return None;
+                }
+                let source = source_property(&bind.source, bind.source_name())?;
+                prop.binding_impl(&source, &obj_ident, go)
+            })
+            .collect::<Vec<_>>();
+        let forwards = signals
+            .iter()
+            .filter_map(|signal| {
+                let forward = signal.forward.as_ref()?;
+                if sync {
+                    errors
+                        .push_spanned(&forward.source, "`forward_from` not allowed on sync class");
+                    return None;
+                }
+                let source = source_property(&forward.source, forward.source_name())?;
+                signal.forward_impl(&source, &obj_ident, go)
+            })
+            .collect::<Vec<_>>();
+        let parent_constructed = (!self.inner.has_method(TypeMode::Subclass, "constructed"))
+            .then(|| {
+                quote! {
+                    #glib::subclass::object::ObjectImplExt::parent_constructed(#self_ident, #obj_ident);
+                }
+            });
+        self.inner.add_custom_stmt(
+            "constructed",
+            parse_quote! {{
+                #parent_constructed
+                #(#bindings)*
+                #(#forwards)*
+            };},
+        );
+    }
+    fn validate_generics(&mut self, errors: &Errors) {
+        let name = &self.inner.name;
+        let generics = &self.inner.generics;
+        if generics.params.is_empty() {
+            if let Some(inst) = self.instantiate.first() {
+                errors.push_spanned(&inst.ty, "`instantiate` requires a generic class");
+            }
+            return;
+        }
+        if self.instantiate.is_empty() {
+            errors.push_spanned(generics, "Generic class must specify `instantiate`");
+        }
+        if !self.final_ {
+            errors.push_spanned(generics, "Generic class must be `final`");
+        }
+        let is_single_type = generics.params.len() == 1
+            && matches!(generics.params.first(), Some(syn::GenericParam::Type(_)));
+        if !is_single_type {
+            errors.push_spanned(
+                generics,
+                "Generic class must have exactly one type parameter",
+            );
+            return;
+        }
+        for inst in &self.instantiate {
+            let last = inst.ty.segments.last().map(|s| &s.ident);
+            if last != Some(name) || inst.type_arg().is_none() {
+                errors.push_spanned(&inst.ty, format!("Expected `{}<Type>`", name));
+            }
+        }
+        let trait_ident = self.instance_trait_alias();
+        if let Some(syn::GenericParam::Type(param)) = self.inner.generics.params.first_mut() {
+            param.bounds.push(parse_quote! { #trait_ident });
+        }
+    }
+    pub fn instance_trait_alias(&self) -> syn::Ident {
+        format_ident!("_{}Instance", self.inner.name)
+    }
+    pub fn gtype_name(&self) -> String {
+        if let Some(ns) = &self.ns {
+            format!("{}{}", ns, self.inner.name)
+        } else {
+            self.inner.name.to_string()
+        }
+        .to_upper_camel_case()
+    }
     pub fn add_private_items(&mut self) {
         let extra = self.extra_private_items();
         self.inner.ensure_items().extend(extra);
@@ -145,6 +433,7 @@ impl ClassDefinition {
             .chain(
                 [
                     self.properties_base_index_definition(),
+                    self.instance_trait_import(),
                     Some(self.object_subclass_impl()),
                     Some(self.object_impl_impl()),
                     self.class_struct_definition(),
@@ -155,6 +444,10 @@ impl ClassDefinition {
                         self.parent_trait.as_ref(),
                     ),
                     self.inner.public_methods(self.ext_trait.as_ref()),
+                    self.ffi.then(|| self.ffi_shims()),
+                    self.register_dynamic_type_method(),
+                    self.transaction_methods(),
+                    self.signal_hook_handles(),
                 ]
                 .into_iter()
                 .flatten(),
@@ -193,10 +486,11 @@ impl ClassDefinition {
         let name = &self.inner.name;
         let glib = self.inner.glib();
         let generics = &self.inner.generics;
+        let (_, type_generics, _) = generics.split_for_impl();
         let vis = &self.inner.vis;
         Some(quote! {
             #glib::wrapper! {
-                #vis struct #name #generics(ObjectSubclass<self::#mod_name::#name #generics>) #(#params),*;
+                #vis struct #name #generics(ObjectSubclass<self::#mod_name::#name #type_generics>) #(#params),*;
             }
         })
     }
@@ -209,12 +503,27 @@ impl ClassDefinition {
             .has_method(TypeMode::Subclass, "class_init")
             .then(|| quote! { Self::class_init(#class_ident); });
         let extra = self.inner.custom_stmts_for("class_init");
-        if body.is_none() && custom.is_none() && extra.is_none() {
+        let hooks = self.signal_hooks_install();
+        let dependents = self.dependents_install(&class_ident);
+        let transactions = self.inner.transactions.map(|_| {
+            let go = &self.inner.crate_path;
+            quote! { #go::install_transactions(#class_ident); }
+        });
+        if body.is_none()
+            && custom.is_none()
+            && extra.is_none()
+            && hooks.is_none()
+            && dependents.is_none()
+            && transactions.is_none()
+        {
             return None;
         }
         Some(quote! {
             fn class_init(#class_ident: &mut <Self as #glib::subclass::types::ObjectSubclass>::Class) {
                 #body
+                #hooks
+                #dependents
+                #transactions
                 #extra
                 #custom
             }
@@ -227,6 +536,7 @@ impl ClassDefinition {
         }
         let name = &self.inner.name;
         let generics = &self.inner.generics;
+        let (_, type_generics, _) = generics.split_for_impl();
         let class_name = &self.class;
         let glib = self.inner.glib();
         let parent_class = if self.extends.is_empty() {
@@ -265,10 +575,10 @@ impl ClassDefinition {
                 #(pub #fields),*
             }
             unsafe #class_struct_head {
-                type Type = #name #generics;
+                type Type = #name #type_generics;
             }
             #deref_head {
-                type Target = #glib::Class<<#name #generics as #glib::subclass::types::ObjectSubclass>::Type>;
+                type Target = #glib::Class<<#name #type_generics as #glib::subclass::types::ObjectSubclass>::Type>;
 
                 fn deref(&self) -> &<Self as ::std::ops::Deref>::Target {
                     unsafe {
@@ -302,12 +612,7 @@ impl ClassDefinition {
                 #glib::subclass::types::ObjectSubclass
             },
         );
-        let gtype_name = if let Some(ns) = &self.ns {
-            format!("{}{}", ns, name)
-        } else {
-            name.to_string()
-        }
-        .to_upper_camel_case();
+        let gtype_name = self.gtype_name();
         let abstract_ = self.abstract_;
         let parent_type = self.parent_type_alias();
         let interfaces = self.interfaces_alias();
@@ -336,6 +641,22 @@ impl ClassDefinition {
                 fn #ident(klass: &<Self as #glib::subclass::types::ObjectSubclass>::Class) -> Self
             }
         });
+        if self.inner.has_type_statics() {
+            return self.manual_object_subclass_impl(
+                head,
+                class_struct_type,
+                quote! {
+                    const ABSTRACT: bool = #abstract_;
+                    type ParentType = super::#parent_type;
+                    type Interfaces = super::#interfaces;
+                    #class_init
+                    #instance_init
+                    #type_init
+                    #new
+                    #with_class
+                },
+            );
+        }
         quote! {
             const _: () = {
                 #[allow(unused_imports)]
@@ -357,6 +678,421 @@ impl ClassDefinition {
             };
         }
     }
+    fn manual_object_subclass_impl(
+        &self,
+        head: TokenStream,
+        class_struct_type: Option<TokenStream>,
+        items: TokenStream,
+    ) -> TokenStream {
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let (_, type_generics, _) = self.inner.generics.split_for_impl();
+        let (gtype_name, type_data, type_) = if self.inner.is_generic() {
+            let param = match self.inner.generics.params.first() {
+                Some(syn::GenericParam::Type(param)) => &param.ident,
+                _ => return Default::default(),
+            };
+            let instance_trait = self.instance_trait_alias();
+            (
+                quote! { <#param as self::#instance_trait>::NAME },
+                quote! { #go::GenericTypeData::type_data::<Self>() },
+                quote! { #go::GenericTypeData::type_::<Self>() },
+            )
+        } else {
+            let gtype_name = self.gtype_name();
+            (
+                quote! { #gtype_name },
+                quote! {
+                    static mut DATA: #glib::subclass::types::TypeData =
+                        #glib::subclass::types::INIT_TYPE_DATA;
+                    unsafe { ::std::ptr::NonNull::new_unchecked(::std::ptr::addr_of_mut!(DATA)) }
+                },
+                quote! { #go::dynamic_type::<Self>() },
+            )
+        };
+        let class_struct_type = class_struct_type.unwrap_or_else(|| {
+            quote! { type Class = #glib::subclass::basic::ClassStruct<Self>; }
+        });
+        let default_new = (!self.inner.has_method(TypeMode::Subclass, "new")
+            && !self.inner.has_method(TypeMode::Subclass, "with_class"))
+        .then(|| {
+            quote! {
+                fn new() -> Self {
+                    ::std::default::Default::default()
+                }
+            }
+        });
+        let subclass_type_head = self.inner.trait_head(
+            &parse_quote! { #name },
+            quote! { #glib::subclass::types::ObjectSubclassType },
+        );
+        let from_object_head = self.inner.trait_head(
+            &parse_quote! { #name },
+            quote! { #glib::subclass::types::FromObject },
+        );
+        quote! {
+            #head {
+                const NAME: &'static ::std::primitive::str = #gtype_name;
+                type Type = super::#name #type_generics;
+                type Instance = #glib::subclass::basic::InstanceStruct<Self>;
+                #class_struct_type
+                #items
+                #default_new
+            }
+            unsafe #subclass_type_head {
+                #[inline]
+                fn type_data() -> ::std::ptr::NonNull<#glib::subclass::types::TypeData> {
+                    #type_data
+                }
+                #[inline]
+                fn type_() -> #glib::Type {
+                    #type_
+                }
+            }
+            #[doc(hidden)]
+            #from_object_head {
+                type FromObjectType = <Self as #glib::subclass::types::ObjectSubclass>::Type;
+                #[inline]
+                fn from_object(obj: &Self::FromObjectType) -> &Self {
+                    #glib::subclass::prelude::ObjectSubclassIsExt::imp(obj)
+                }
+            }
+        }
+    }
+    pub fn mock_definition(&self) -> Option<TokenStream> {
+        if !self.mock || self.final_ || !self.wrapper || self.inner.is_generic() {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let mock_name = format_ident!("Mock{}", name);
+        let mod_name = format_ident!("{}_mock", self.inner.module.ident);
+        let vis = match &self.inner.vis {
+            syn::Visibility::Inherited => syn::Visibility::Inherited,
+            _ => self.inner.inner_vis.clone(),
+        };
+        let outer_vis = &self.inner.vis;
+        let ns = self.ns.as_ref().map(|ns| quote! { ns = #ns, });
+        let extends = &self.extends;
+        let uses = self
+            .inner
+            .module
+            .content
+            .iter()
+            .flat_map(|(_, items)| items.iter())
+            .filter(|item| match item {
+                syn::Item::Use(u) => !matches!(&u.tree, syn::UseTree::Path(p) if p.ident == "self"),
+                _ => false,
+            });
+        let items = self
+            .inner
+            .virtual_methods
+            .iter()
+            .map(|m| m.mock_item())
+            .chain(self.inner.signals.iter().filter_map(|s| s.mock_item()))
+            .collect::<Vec<_>>();
+        let inner_ty: syn::Type = parse_quote! { super::#mock_name };
+        let outer_ty: syn::Type = parse_quote! { #mock_name };
+        let fields = items.iter().map(|item| {
+            let ident = &item.ident;
+            let ty = item.method_type(&inner_ty, go);
+            quote! { pub(super) #ident: #ty }
+        });
+        let field_defaults = items.iter().map(|item| {
+            let ident = &item.ident;
+            let name = &item.name;
+            quote! { #ident: #go::MockMethod::new(#name) }
+        });
+        let signals = self
+            .inner
+            .signals
+            .iter()
+            .filter_map(|s| s.mock_definition(go));
+        let impl_trait = self.impl_trait.as_ref()?;
+        let virtual_methods = self.impl_ext_trait.as_ref().map(|impl_ext| {
+            let impl_ext: syn::Path = parse_quote! { super::#impl_ext };
+            let methods = self
+                .inner
+                .virtual_methods
+                .iter()
+                .map(|m| m.mock_definition(&impl_ext, &glib));
+            quote! {
+                impl super::#impl_trait for #mock_name {
+                    #(#methods)*
+                }
+            }
+        });
+        let parent_impl = self.parent_trait.as_ref().map(|parent_trait| {
+            quote! {
+                impl #parent_trait for #mock_name {}
+            }
+        });
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let imp_ident = syn::Ident::new("imp", Span::mixed_site());
+        let accessors = items.iter().map(|item| {
+            let ident = &item.ident;
+            let ty = item.method_type(&outer_ty, go);
+            let set_ident = format_ident!("mock_{}", ident);
+            let get_ident = format_ident!("{}_mock", ident);
+            let inputs = &item.inputs;
+            let output = &item.output;
+            quote! {
+                #outer_vis fn #set_ident(
+                    &#self_ident,
+                    #func_ident: impl ::std::ops::Fn(&Self, #(#inputs),*) #output + 'static,
+                ) -> &#ty {
+                    let #imp_ident = #glib::subclass::prelude::ObjectSubclassIsExt::imp(#self_ident);
+                    #imp_ident.#ident.set(::std::boxed::Box::new(#func_ident))
+                }
+                #outer_vis fn #get_ident(&#self_ident) -> &#ty {
+                    &#glib::subclass::prelude::ObjectSubclassIsExt::imp(#self_ident).#ident
+                }
+            }
+        });
+        let verifies = items.iter().map(|item| {
+            let ident = &item.ident;
+            quote! {
+                #glib::subclass::prelude::ObjectSubclassIsExt::imp(#self_ident).#ident.verify();
+            }
+        });
+        Some(quote! {
+            #[#go::class(final, #ns extends(#name, #(#extends),*))]
+            mod #mod_name {
+                #(
+                    #[allow(unused_imports)]
+                    #uses
+                )*
+                #vis struct #mock_name {
+                    #(#fields),*
+                }
+                impl ::std::default::Default for #mock_name {
+                    fn default() -> Self {
+                        Self {
+                            #(#field_defaults),*
+                        }
+                    }
+                }
+                impl #mock_name {
+                    #(#signals)*
+                }
+                #virtual_methods
+                #parent_impl
+            }
+            impl #mock_name {
+                #(#accessors)*
+                #outer_vis fn verify_mocks(&#self_ident) {
+                    #(#verifies)*
+                }
+            }
+        })
+    }
+    fn dependents_install(&self, class_ident: &syn::Ident) -> Option<TokenStream> {
+        let dependents = self
+            .inner
+            .properties
+            .iter()
+            .filter(|p| !p.dependents.is_empty())
+            .map(|p| {
+                let name = p.name.to_string();
+                let dependents = &p.dependents;
+                quote! { (#name, &[#(#dependents),*]) }
+            })
+            .collect::<Vec<_>>();
+        if dependents.is_empty() {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        Some(quote! {
+            #go::install_dependents(#class_ident, &[#(#dependents),*]);
+        })
+    }
+    fn signal_hooks_install(&self) -> Option<TokenStream> {
+        let hooks = &self.inner.signal_hooks;
+        if hooks.is_empty() {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let wrapper_ty =
+            self.inner
+                .type_(TypeMode::Subclass, TypeMode::Wrapper, TypeContext::External);
+        let wrapper_ty: syn::Type = parse_quote! { #wrapper_ty };
+        let sub_ty = self.inner.type_(
+            TypeMode::Subclass,
+            TypeMode::Subclass,
+            TypeContext::External,
+        );
+        let sub_ty: syn::Type = parse_quote! { #sub_ty };
+        let installs = hooks.iter().map(|h| h.install(&wrapper_ty, &sub_ty, go));
+        Some(quote! { #(#installs)* })
+    }
+    fn signal_hook_handles(&self) -> Option<TokenStream> {
+        let hooks = &self.inner.signal_hooks;
+        if hooks.is_empty() {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let name = &self.inner.name;
+        let handles = hooks.iter().map(|h| h.handle_definition(go));
+        Some(quote! {
+            impl super::#name {
+                #(#handles)*
+            }
+        })
+    }
+    fn validate_transactions(&self, errors: &Errors) {
+        let span = match self.inner.transactions {
+            Some(span) => span,
+            None => return,
+        };
+        if !self.wrapper {
+            errors.push(span, "`transactions` requires a wrapper type");
+            return;
+        }
+        for ident in self.inner.wrapper_method_names() {
+            if ident == "batch" || ident == "edit" {
+                errors.push(
+                    ident.span(),
+                    format!("Method `{}` conflicts with `transactions`", ident),
+                );
+            }
+        }
+    }
+    fn transaction_methods(&self) -> Option<TokenStream> {
+        self.inner.transactions?;
+        if !self.wrapper {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let (impl_generics, type_generics, where_clause) = self.inner.generics.split_for_impl();
+        let properties_path = self.inner.method_path("properties", TypeMode::Subclass);
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let result_ident = syn::Ident::new("result", Span::mixed_site());
+        let transaction_ident = syn::Ident::new("transaction", Span::mixed_site());
+        let watched = self
+            .inner
+            .properties
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_watchable())
+            .map(|(index, p)| {
+                let old = format_ident!("old_{}", index, span = Span::mixed_site());
+                (index, p, p.name.to_string(), old)
+            })
+            .collect::<Vec<_>>();
+        let snapshots = watched.iter().map(|(_, _, name, old)| {
+            quote! {
+                let #old = #glib::object::ObjectExt::property_value(#self_ident, #name);
+            }
+        });
+        let unchanged = |index: &usize, name: &String, old: &syn::Ident| {
+            quote! {
+                #go::param_values_eq(
+                    &#properties_path()[#index],
+                    &#old,
+                    &#glib::object::ObjectExt::property_value(#self_ident, #name),
+                )
+            }
+        };
+        let restores = watched
+            .iter()
+            .filter(|(_, p, _, _)| p.is_restorable())
+            .map(|(index, _, name, old)| {
+                let unchanged = unchanged(index, name, old);
+                quote! {
+                    if !#unchanged {
+                        #glib::object::ObjectExt::set_property_from_value(#self_ident, #name, &#old);
+                    }
+                }
+            });
+        let discards = watched.iter().map(|(index, _, name, old)| {
+            let unchanged = unchanged(index, name, old);
+            quote! {
+                if #unchanged {
+                    #transaction_ident.discard(&#properties_path()[#index]);
+                }
+            }
+        });
+        Some(quote! {
+            impl #impl_generics super::#name #type_generics #where_clause {
+                pub fn batch<R>(&#self_ident, #func_ident: impl ::std::ops::FnOnce(&Self) -> R) -> R {
+                    let _transaction = #go::Transaction::begin(
+                        #glib::Cast::upcast_ref::<#glib::Object>(#self_ident),
+                    );
+                    #func_ident(#self_ident)
+                }
+                pub fn edit<R, E>(
+                    &#self_ident,
+                    #func_ident: impl ::std::ops::FnOnce(&Self) -> ::std::result::Result<R, E>,
+                ) -> ::std::result::Result<R, E> {
+                    let #transaction_ident = #go::Transaction::begin(
+                        #glib::Cast::upcast_ref::<#glib::Object>(#self_ident),
+                    );
+                    #(#snapshots)*
+                    let #result_ident = #func_ident(#self_ident);
+                    if #result_ident.is_err() {
+                        #(#restores)*
+                        #(#discards)*
+                    }
+                    #result_ident
+                }
+            }
+        })
+    }
+    fn register_dynamic_type_method(&self) -> Option<TokenStream> {
+        if !self.inner.dynamic {
+            return None;
+        }
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let module_ident = syn::Ident::new("type_module", Span::mixed_site());
+        Some(quote! {
+            impl super::#name {
+                pub fn register_type(
+                    #module_ident: &impl #glib::object::IsA<#go::TypeModule>,
+                ) -> #glib::Type {
+                    #go::register_dynamic_type::<#name>(#module_ident)
+                }
+            }
+        })
+    }
+    fn instance_trait_import(&self) -> Option<TokenStream> {
+        let trait_ident = self.instance_trait_alias();
+        self.inner.is_generic().then(|| {
+            quote! {
+                #[allow(unused_imports)]
+                use super::#trait_ident;
+            }
+        })
+    }
+    fn instance_trait_definition(&self) -> Option<TokenStream> {
+        if !self.inner.is_generic() {
+            return None;
+        }
+        let trait_ident = self.instance_trait_alias();
+        let impls = self.instantiate.iter().filter_map(|inst| {
+            let ty = inst.type_arg()?;
+            let gtype_name = &inst.name;
+            Some(quote! {
+                impl #trait_ident for #ty {
+                    const NAME: &'static ::std::primitive::str = #gtype_name;
+                }
+            })
+        });
+        Some(quote! {
+            #[doc(hidden)]
+            pub trait #trait_ident: 'static {
+                const NAME: &'static ::std::primitive::str;
+            }
+            #(#impls)*
+        })
+    }
     pub(crate) fn properties_base_index_definition(&self) -> Option<TokenStream> {
         if self.inner.properties.is_empty()
             || (!self.inner.has_method(TypeMode::Subclass, "properties")
@@ -630,8 +1366,11 @@ impl ToTokens for ClassDefinition {
         let interfaces_ident = self.interfaces_alias();
         let interfaces = &self.implements;
 
+        let instance_trait = self.instance_trait_definition();
+
         let class = quote! {
             #module
+            #instance_trait
             #wrapper
             #use_ext
             #use_impl
@@ -656,6 +1395,12 @@ pub fn derived_class_properties(
         properties,
         ..
     } = Properties::from_derive_input(input, None, errors);
+    for bind in properties.iter().filter_map(|p| p.bind.as_ref()) {
+        errors.push_spanned(
+            &bind.source,
+            "`bind` not supported with #[derive(Properties)]",
+        );
+    }
     let glib: syn::Path = parse_quote! { #go::glib };
     let name = &input.ident;
     let generics = &input.generics;
@@ -0,0 +1,325 @@
+use crate::util::{self, Errors};
+use darling::{util::Flag, FromAttributes, FromMeta};
+use heck::ToUpperCamelCase;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashSet;
+use syn::parse_quote;
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct Attrs {
+    pub name: Option<syn::Ident>,
+    pub ns: Option<syn::Ident>,
+    pub dynamic: Flag,
+}
+
+#[derive(Debug)]
+pub struct EnumOptions(Attrs);
+
+impl EnumOptions {
+    pub fn parse(tokens: TokenStream, errors: &Errors) -> Self {
+        Self(util::parse_list(tokens, errors))
+    }
+}
+
+#[derive(Default, FromAttributes)]
+#[darling(default, attributes(enum_value))]
+struct EnumValueAttrs {
+    name: Option<syn::LitStr>,
+    nick: Option<syn::LitStr>,
+}
+
+#[derive(Debug)]
+pub struct EnumValue {
+    pub ident: syn::Ident,
+    pub name: String,
+    pub nick: String,
+}
+
+#[derive(Debug)]
+pub struct EnumDefinition {
+    pub item: syn::ItemEnum,
+    pub crate_path: syn::Path,
+    pub ns: Option<syn::Ident>,
+    pub name: syn::Ident,
+    pub values: Vec<EnumValue>,
+    pub dynamic: bool,
+}
+
+impl EnumDefinition {
+    pub fn parse(
+        mut item: syn::ItemEnum,
+        opts: EnumOptions,
+        crate_path: syn::Path,
+        errors: &Errors,
+    ) -> Self {
+        let attrs = opts.0;
+        if !item.generics.params.is_empty() {
+            errors.push_spanned(&item.generics, "Generic enums not supported");
+        }
+        let mut values = Vec::new();
+        let mut nicks = HashSet::new();
+        for variant in &mut item.variants {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                errors.push_spanned(&variant.fields, "Enum values must be unit variants");
+            }
+            let attrs = util::extract_attrs(&mut variant.attrs, "enum_value")
+                .map(|attrs| util::parse_attributes::<EnumValueAttrs>(&attrs, errors))
+                .unwrap_or_default();
+            let ident = variant.ident.clone();
+            let name = attrs
+                .name
+                .map(|n| n.value())
+                .unwrap_or_else(|| ident.to_string());
+            let nick = attrs
+                .nick
+                .map(|n| n.value())
+                .unwrap_or_else(|| util::format_name(&ident));
+            if !nicks.insert(nick.clone()) {
+                errors.push_spanned(&ident, format!("Duplicate enum value nick `{}`", nick));
+            }
+            values.push(EnumValue { ident, name, nick });
+        }
+        let name = attrs.name.unwrap_or_else(|| item.ident.clone());
+        Self {
+            item,
+            crate_path,
+            ns: attrs.ns,
+            name,
+            values,
+            dynamic: attrs.dynamic.is_some(),
+        }
+    }
+    pub fn glib(&self) -> syn::Path {
+        let go = &self.crate_path;
+        parse_quote! { #go::glib }
+    }
+    pub fn gtype_name(&self) -> String {
+        if let Some(ns) = &self.ns {
+            format!("{}{}", ns, self.name)
+        } else {
+            self.name.to_string()
+        }
+        .to_upper_camel_case()
+    }
+    fn values_definition(&self, values_ident: &syn::Ident) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let values = self.values.iter().map(|v| {
+            let value_ident = &v.ident;
+            let name = format!("{}\0", v.name);
+            let nick = format!("{}\0", v.nick);
+            quote! {
+                #glib::gobject_ffi::GEnumValue {
+                    value: #ident::#value_ident as ::std::primitive::i32,
+                    value_name: #name as *const _ as *const _,
+                    value_nick: #nick as *const _ as *const _,
+                },
+            }
+        });
+        let count = self.values.len() + 1;
+        quote! {
+            static mut #values_ident: [#glib::gobject_ffi::GEnumValue; #count] = [
+                #(#values)*
+                #glib::gobject_ffi::GEnumValue {
+                    value: 0,
+                    value_name: ::std::ptr::null(),
+                    value_nick: ::std::ptr::null(),
+                },
+            ];
+        }
+    }
+    fn static_type_impl(&self) -> TokenStream {
+        if self.dynamic {
+            return self.dynamic_type_impl();
+        }
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let gtype_name = self.gtype_name();
+        let once = format_ident!("ONCE", span = Span::mixed_site());
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let values_ident = format_ident!("VALUES", span = Span::mixed_site());
+        let values = self.values_definition(&values_ident);
+        quote! {
+            impl #glib::StaticType for #ident {
+                fn static_type() -> #glib::Type {
+                    static #once: ::std::sync::Once = ::std::sync::Once::new();
+                    static mut #type_: #glib::Type = #glib::Type::INVALID;
+                    #once.call_once(|| {
+                        #values
+                        let name = ::std::ffi::CString::new(#gtype_name).unwrap();
+                        unsafe {
+                            let type_ = #glib::gobject_ffi::g_enum_register_static(
+                                name.as_ptr(),
+                                #values_ident.as_ptr(),
+                            );
+                            let type_: #glib::Type = #glib::translate::from_glib(type_);
+                            ::std::assert!(type_.is_valid());
+                            #type_ = type_;
+                        }
+                    });
+                    unsafe { #type_ }
+                }
+            }
+        }
+    }
+    fn dynamic_type_impl(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let vis = &self.item.vis;
+        let gtype_name = self.gtype_name();
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let values_ident = format_ident!("VALUES", span = Span::mixed_site());
+        let module_ident = format_ident!("type_module", span = Span::mixed_site());
+        let values = self.values_definition(&values_ident);
+        quote! {
+            const _: () = {
+                static #type_: #go::SyncOnceCell<#glib::Type> = #go::SyncOnceCell::new();
+                impl #glib::StaticType for #ident {
+                    fn static_type() -> #glib::Type {
+                        *#type_.get().unwrap_or_else(|| {
+                            ::std::panic!(
+                                "Dynamic type `{}` used before being registered with a TypeModule",
+                                #gtype_name
+                            )
+                        })
+                    }
+                }
+                impl #ident {
+                    #vis fn register_type(
+                        #module_ident: &impl #glib::object::IsA<#go::TypeModule>,
+                    ) -> #glib::Type {
+                        #values
+                        let name = ::std::ffi::CString::new(#gtype_name).unwrap();
+                        let type_: #glib::Type = unsafe {
+                            #glib::translate::from_glib(#glib::gobject_ffi::g_type_module_register_enum(
+                                #glib::translate::ToGlibPtr::to_glib_none(
+                                    #glib::Cast::upcast_ref::<#go::TypeModule>(#module_ident),
+                                ).0,
+                                name.as_ptr(),
+                                #values_ident.as_ptr(),
+                            ))
+                        };
+                        ::std::assert!(type_.is_valid());
+                        *#type_.get_or_init(|| type_)
+                    }
+                }
+            };
+        }
+    }
+    fn translate_impls(&self) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let checks = self.values.iter().map(|v| {
+            let value_ident = &v.ident;
+            quote! {
+                if value == #ident::#value_ident as ::std::primitive::i32 {
+                    return ::std::result::Result::Ok(#ident::#value_ident);
+                }
+            }
+        });
+        quote! {
+            impl #glib::translate::IntoGlib for #ident {
+                type GlibType = ::std::primitive::i32;
+                #[inline]
+                fn into_glib(self) -> ::std::primitive::i32 {
+                    self as ::std::primitive::i32
+                }
+            }
+            impl #glib::translate::TryFromGlib<::std::primitive::i32> for #ident {
+                type Error = ::std::primitive::i32;
+                unsafe fn try_from_glib(
+                    value: ::std::primitive::i32,
+                ) -> ::std::result::Result<Self, ::std::primitive::i32> {
+                    #(#checks)*
+                    ::std::result::Result::Err(value)
+                }
+            }
+            impl #glib::translate::FromGlib<::std::primitive::i32> for #ident {
+                #[inline]
+                unsafe fn from_glib(value: ::std::primitive::i32) -> Self {
+                    <Self as #glib::translate::TryFromGlib<::std::primitive::i32>>::try_from_glib(value)
+                        .unwrap()
+                }
+            }
+        }
+    }
+    fn value_impls(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        quote! {
+            impl #glib::value::ValueType for #ident {
+                type Type = Self;
+            }
+            unsafe impl<'a> #glib::value::FromValue<'a> for #ident {
+                type Checker = #glib::value::GenericValueTypeChecker<Self>;
+                unsafe fn from_value(value: &'a #glib::Value) -> Self {
+                    #glib::translate::from_glib(#glib::gobject_ffi::g_value_get_enum(
+                        #glib::translate::ToGlibPtr::to_glib_none(value).0,
+                    ))
+                }
+            }
+            impl #glib::value::ToValue for #ident {
+                fn to_value(&self) -> #glib::Value {
+                    let mut value = #glib::Value::for_value_type::<Self>();
+                    unsafe {
+                        #glib::gobject_ffi::g_value_set_enum(
+                            #glib::translate::ToGlibPtrMut::to_glib_none_mut(&mut value).0,
+                            #glib::translate::IntoGlib::into_glib(*self),
+                        );
+                    }
+                    value
+                }
+                fn value_type(&self) -> #glib::Type {
+                    <Self as #glib::StaticType>::static_type()
+                }
+            }
+            impl #go::ParamSpecBuildable for #ident {
+                type ParamSpec = #go::ParamSpecEnumType<Self>;
+            }
+        }
+    }
+    fn signal_detail_impl(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.item.ident;
+        let to_arms = self.values.iter().map(|v| {
+            let value_ident = &v.ident;
+            let nick = &v.nick;
+            quote! { Self::#value_ident => #nick, }
+        });
+        let from_arms = self.values.iter().map(|v| {
+            let value_ident = &v.ident;
+            let nick = &v.nick;
+            quote! { #nick => ::std::option::Option::Some(Self::#value_ident), }
+        });
+        quote! {
+            impl #go::SignalDetail for #ident {
+                fn to_quark(&self) -> #glib::Quark {
+                    <#glib::Quark as ::std::convert::From<&str>>::from(match self {
+                        #(#to_arms)*
+                    })
+                }
+                fn from_quark(quark: #glib::Quark) -> ::std::option::Option<Self> {
+                    match quark.as_str() {
+                        #(#from_arms)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ToTokens for EnumDefinition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.item.to_tokens(tokens);
+        self.static_type_impl().to_tokens(tokens);
+        self.translate_impls().to_tokens(tokens);
+        self.value_impls().to_tokens(tokens);
+        self.signal_detail_impl().to_tokens(tokens);
+    }
+}
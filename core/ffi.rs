@@ -0,0 +1,402 @@
+use crate::{
+    util::{self, Errors},
+    ClassDefinition, PublicMethod,
+};
+use darling::{util::Flag, FromAttributes};
+use heck::{ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::fmt::Write;
+use syn::parse_quote;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FfiType {
+    Void,
+    Primitive(&'static str),
+    Bool,
+    Str,
+    String,
+    Instance,
+}
+
+impl FfiType {
+    fn from_type(ty: &syn::Type, wrapper: &syn::Ident) -> Option<Self> {
+        match ty {
+            syn::Type::Tuple(t) if t.elems.is_empty() => Some(Self::Void),
+            syn::Type::Paren(p) => Self::from_type(&p.elem, wrapper),
+            syn::Type::Group(g) => Self::from_type(&g.elem, wrapper),
+            syn::Type::Reference(r) if r.mutability.is_none() => {
+                match Self::from_type(&r.elem, wrapper)? {
+                    Self::Instance => Some(Self::Instance),
+                    Self::Str => Some(Self::Str),
+                    _ => None,
+                }
+            }
+            syn::Type::Path(p) if p.qself.is_none() => {
+                let ident = &p.path.segments.last()?.ident;
+                if ident == "Self" || ident == wrapper {
+                    return Some(Self::Instance);
+                }
+                if p.path.segments.len() != 1 {
+                    return None;
+                }
+                Some(match ident.to_string().as_str() {
+                    "bool" => Self::Bool,
+                    "str" => Self::Str,
+                    "String" => Self::String,
+                    "i8" => Self::Primitive("gint8"),
+                    "u8" => Self::Primitive("guint8"),
+                    "i16" => Self::Primitive("gint16"),
+                    "u16" => Self::Primitive("guint16"),
+                    "i32" => Self::Primitive("gint"),
+                    "u32" => Self::Primitive("guint"),
+                    "i64" => Self::Primitive("gint64"),
+                    "u64" => Self::Primitive("guint64"),
+                    "f32" => Self::Primitive("gfloat"),
+                    "f64" => Self::Primitive("gdouble"),
+                    _ => return None,
+                })
+            }
+            _ => None,
+        }
+    }
+    fn from_return(output: &syn::ReturnType, wrapper: &syn::Ident) -> Option<Self> {
+        match output {
+            syn::ReturnType::Default => Some(Self::Void),
+            syn::ReturnType::Type(_, ty) => {
+                let ty = Self::from_type(ty, wrapper)?;
+                (ty != Self::Str).then_some(ty)
+            }
+        }
+    }
+    fn c_type(self, ctype: &str, is_return: bool) -> String {
+        match self {
+            Self::Void => "void".into(),
+            Self::Primitive(name) => name.into(),
+            Self::Bool => "gboolean".into(),
+            Self::Str | Self::String if !is_return => "const char *".into(),
+            Self::Str | Self::String => "char *".into(),
+            Self::Instance => format!("{} *", ctype),
+        }
+    }
+    fn rust_type(self, wrapper: &syn::Type, glib: &syn::Path, is_return: bool) -> TokenStream {
+        match self {
+            Self::Void => quote! { () },
+            Self::Primitive(_) => unreachable!(),
+            Self::Bool => quote! { #glib::ffi::gboolean },
+            Self::Str | Self::String if !is_return => {
+                quote! { *const ::std::os::raw::c_char }
+            }
+            Self::Str | Self::String => quote! { *mut ::std::os::raw::c_char },
+            Self::Instance => quote! { *mut <#wrapper as #glib::object::ObjectType>::GlibType },
+        }
+    }
+    fn arg_expr(self, ident: &syn::Ident, wrapper: &syn::Type, glib: &syn::Path) -> TokenStream {
+        match self {
+            Self::Void | Self::Primitive(_) => quote! { #ident },
+            Self::Bool => quote! { #glib::translate::from_glib(#ident) },
+            Self::Str => quote! { &*::std::ffi::CStr::from_ptr(#ident).to_string_lossy() },
+            Self::String => {
+                quote! { ::std::ffi::CStr::from_ptr(#ident).to_string_lossy().into_owned() }
+            }
+            Self::Instance => {
+                quote! { &*#glib::translate::from_glib_borrow::<_, #wrapper>(#ident) }
+            }
+        }
+    }
+    fn return_expr(self, ident: &syn::Ident, glib: &syn::Path) -> TokenStream {
+        match self {
+            Self::Void | Self::Primitive(_) => quote! { #ident },
+            Self::Bool => quote! { #glib::translate::IntoGlib::into_glib(#ident) },
+            Self::Str | Self::String => quote! {
+                #glib::ffi::g_strndup(#ident.as_ptr() as *const _, #ident.len() as _)
+            },
+            Self::Instance => quote! { #glib::translate::ToGlibPtr::to_glib_full(&#ident) },
+        }
+    }
+}
+
+#[derive(Default, FromAttributes)]
+#[darling(default, attributes(ffi))]
+struct FfiMethodAttrs {
+    skip: Flag,
+}
+
+pub(crate) struct FfiMethod<'a> {
+    pub(crate) method: &'a PublicMethod,
+    instance: bool,
+    args: Vec<(syn::Ident, FfiType, &'a syn::Type)>,
+    output: FfiType,
+}
+
+impl<'a> FfiMethod<'a> {
+    fn new(method: &'a PublicMethod, wrapper: &syn::Ident) -> Option<Self> {
+        let sig = &method.sig;
+        if sig.asyncness.is_some()
+            || !sig.generics.params.is_empty()
+            || !method.generic_args.is_empty()
+            || method.constructor.as_ref().map(|c| c.fallible()) == Some(true)
+        {
+            return None;
+        }
+        let instance = !method.is_static();
+        let mut args = Vec::new();
+        for (index, arg) in sig.inputs.iter().enumerate() {
+            if let syn::FnArg::Typed(arg) = arg {
+                let ident = match &*arg.pat {
+                    syn::Pat::Ident(p) => p.ident.clone(),
+                    _ => format_ident!("arg{}", index, span = Span::mixed_site()),
+                };
+                let ty = FfiType::from_type(&arg.ty, wrapper)?;
+                if ty == FfiType::Void {
+                    return None;
+                }
+                args.push((ident, ty, &*arg.ty));
+            }
+        }
+        let output = FfiType::from_return(&sig.output, wrapper)?;
+        Some(Self {
+            method,
+            instance,
+            args,
+            output,
+        })
+    }
+    pub(crate) fn c_name(&self, prefix: &str) -> String {
+        format!("{}_{}", prefix, self.method.sig.ident)
+    }
+}
+
+impl ClassDefinition {
+    /// Removes `#[ffi(skip)]` from methods, and reports public methods that can't be exported
+    /// and aren't skipped.
+    pub(crate) fn parse_ffi_methods(&mut self, errors: &Errors) {
+        let ffi = self.ffi;
+        let mut skipped = Vec::new();
+        for impl_ in self.inner.methods_items_mut() {
+            for item in &mut impl_.items {
+                if let syn::ImplItem::Method(method) = item {
+                    if let Some(attrs) = util::extract_attrs(&mut method.attrs, "ffi") {
+                        if !ffi {
+                            errors.push_spanned(&attrs[0], "`#[ffi]` requires the `ffi` option");
+                        }
+                        let attrs = util::parse_attributes::<FfiMethodAttrs>(&attrs, errors);
+                        if attrs.skip.is_some() {
+                            skipped.push(method.sig.ident.clone());
+                        }
+                    }
+                }
+            }
+        }
+        if !ffi {
+            return;
+        }
+        let wrapper = &self.inner.name;
+        for method in &self.inner.public_methods {
+            if !skipped.contains(&method.sig.ident) && FfiMethod::new(method, wrapper).is_none() {
+                errors.push_spanned(
+                    &method.sig,
+                    "Public method can't be exported with `ffi`, mark it with `#[ffi(skip)]`",
+                );
+            }
+        }
+        self.ffi_skip = skipped;
+    }
+    pub(crate) fn ffi_methods(&self) -> impl Iterator<Item = FfiMethod<'_>> {
+        let wrapper = &self.inner.name;
+        self.inner
+            .public_methods
+            .iter()
+            .filter(|m| !self.ffi_skip.contains(&m.sig.ident))
+            .filter_map(move |m| FfiMethod::new(m, wrapper))
+    }
+    /// The prefix of exported C symbols, e.g. `my_object` for `my_object_get_type`.
+    pub fn c_symbol_prefix(&self) -> String {
+        self.gtype_name().to_snake_case()
+    }
+    pub(crate) fn ffi_shims(&self) -> TokenStream {
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let wrapper: syn::Type = parse_quote! { super::#name };
+        let prefix = self.c_symbol_prefix();
+        let get_type = format_ident!("{}_get_type", prefix);
+        let this = syn::Ident::new("this", Span::mixed_site());
+        let ret = syn::Ident::new("ret", Span::mixed_site());
+        let methods = self.ffi_methods().map(|m| {
+            let symbol = format_ident!("{}", m.c_name(&prefix));
+            let ident = &m.method.sig.ident;
+            let instance_arg = m.instance.then(|| {
+                quote! { #this: *mut <#wrapper as #glib::object::ObjectType>::GlibType, }
+            });
+            let args = m.args.iter().map(|(ident, ty, orig)| {
+                let ty = match ty {
+                    FfiType::Primitive(_) => quote! { #orig },
+                    ty => ty.rust_type(&wrapper, &glib, false),
+                };
+                quote! { #ident: #ty }
+            });
+            let call_args = m
+                .args
+                .iter()
+                .map(|(ident, ty, _)| ty.arg_expr(ident, &wrapper, &glib));
+            let output = match (&m.output, &m.method.sig.output) {
+                (FfiType::Primitive(_), syn::ReturnType::Type(_, ty)) => quote! { #ty },
+                (ty, _) => ty.rust_type(&wrapper, &glib, true),
+            };
+            let (borrow, call) = if m.instance {
+                (
+                    Some(quote! {
+                        let #this = #glib::translate::from_glib_borrow::<_, #wrapper>(#this);
+                    }),
+                    quote! { #this.#ident(#(#call_args),*) },
+                )
+            } else {
+                (None, quote! { #wrapper::#ident(#(#call_args),*) })
+            };
+            let body = if m.output == FfiType::Void {
+                quote! { #call; }
+            } else {
+                let convert = m.output.return_expr(&ret, &glib);
+                quote! {
+                    let #ret = #call;
+                    #convert
+                }
+            };
+            quote! {
+                #[doc(hidden)]
+                #[no_mangle]
+                #[allow(clippy::missing_safety_doc)]
+                pub unsafe extern "C" fn #symbol(#instance_arg #(#args),*) -> #output {
+                    #borrow
+                    #body
+                }
+            }
+        });
+        quote! {
+            #[doc(hidden)]
+            #[no_mangle]
+            pub extern "C" fn #get_type() -> #glib::ffi::GType {
+                #glib::translate::IntoGlib::into_glib(
+                    <#wrapper as #glib::StaticType>::static_type()
+                )
+            }
+            #(#methods)*
+        }
+    }
+    fn c_parent_type(&self) -> (String, &'static str) {
+        match self.extends.first() {
+            Some(parent) => {
+                let name = crate::gir::path_type_name(parent);
+                let include = match name.split_once('.').map(|s| s.0) {
+                    Some("Gtk") | Some("Gdk") | Some("Gsk") => "gtk/gtk.h",
+                    Some("Gio") => "gio/gio.h",
+                    _ => "glib-object.h",
+                };
+                let ctype = match name.as_str() {
+                    "GObject.Object" => "GObject".into(),
+                    "GObject.InitiallyUnowned" => "GInitiallyUnowned".into(),
+                    _ => name.replace('.', ""),
+                };
+                (ctype, include)
+            }
+            None => ("GObject".into(), "glib-object.h"),
+        }
+    }
+    /// Generates a C header declaring the type, its instance and class structs, and the
+    /// `extern "C"` functions emitted with the `ffi` option.
+    pub fn to_c_header(&self) -> String {
+        let ctype = self.gtype_name();
+        let prefix = self.c_symbol_prefix();
+        let guard = format!("__{}_H__", prefix.to_uppercase());
+        let type_macro = match self.ns.as_ref() {
+            Some(ns) => {
+                let ns = ns.to_string().to_upper_camel_case();
+                let rest = ctype.strip_prefix(&ns).unwrap_or(&ctype);
+                format!(
+                    "{}_TYPE_{}",
+                    ns.to_shouty_snake_case(),
+                    rest.to_shouty_snake_case()
+                )
+            }
+            None => format!("TYPE_{}", ctype.to_shouty_snake_case()),
+        };
+        let (parent, include) = self.c_parent_type();
+
+        let mut out = String::new();
+        writeln!(out, "/* Generated by gobject. Do not edit. */").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "#ifndef {}", guard).unwrap();
+        writeln!(out, "#define {}", guard).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "#include <{}>", include).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "G_BEGIN_DECLS").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "#define {} ({}_get_type ())", type_macro, prefix).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "typedef struct _{0} {0};", ctype).unwrap();
+        writeln!(out, "typedef struct _{0}Class {0}Class;", ctype).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "struct _{}\n{{\n  {} parent_instance;\n}};",
+            ctype, parent
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "struct _{}Class\n{{\n  {}Class parent_class;",
+            ctype, parent
+        )
+        .unwrap();
+        if !self.inner.virtual_methods.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "  /* virtual methods use the Rust calling convention */"
+            )
+            .unwrap();
+            for method in &self.inner.virtual_methods {
+                writeln!(out, "  gpointer {};", method.sig.ident).unwrap();
+            }
+        }
+        writeln!(out, "}};").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "GType {}_get_type (void);", prefix).unwrap();
+        let methods = self.ffi_methods().collect::<Vec<_>>();
+        if !methods.is_empty() {
+            writeln!(out).unwrap();
+        }
+        for method in methods {
+            let mut args = Vec::new();
+            if method.instance {
+                args.push(format!("{} *self", ctype));
+            }
+            for (ident, ty, _) in &method.args {
+                let ty = ty.c_type(&ctype, false);
+                let sep = if ty.ends_with('*') { "" } else { " " };
+                args.push(format!("{}{}{}", ty, sep, ident));
+            }
+            if args.is_empty() {
+                args.push("void".into());
+            }
+            let output = method.output.c_type(&ctype, true);
+            let sep = if output.ends_with('*') { "" } else { " " };
+            writeln!(
+                out,
+                "{}{}{} ({});",
+                output,
+                sep,
+                method.c_name(&prefix),
+                args.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "G_END_DECLS").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "#endif /* {} */", guard).unwrap();
+        out
+    }
+}
@@ -0,0 +1,328 @@
+use crate::util::{self, Errors};
+use darling::{util::Flag, FromAttributes, FromMeta};
+use heck::{ToShoutySnakeCase, ToUpperCamelCase};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashSet;
+use syn::parse_quote;
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct Attrs {
+    pub name: Option<syn::Ident>,
+    pub ns: Option<syn::Ident>,
+    pub numeric: Flag,
+    pub dynamic: Flag,
+}
+
+#[derive(Debug)]
+pub struct FlagsOptions(Attrs);
+
+impl FlagsOptions {
+    pub fn parse(tokens: TokenStream, errors: &Errors) -> Self {
+        Self(util::parse_list(tokens, errors))
+    }
+}
+
+#[derive(Default, FromAttributes)]
+#[darling(default, attributes(flags_value))]
+struct FlagsValueAttrs {
+    name: Option<syn::LitStr>,
+    nick: Option<syn::LitStr>,
+}
+
+#[derive(Debug)]
+pub struct FlagsValue {
+    pub ident: syn::Ident,
+    pub attrs: Vec<syn::Attribute>,
+    pub value: syn::Expr,
+    pub name: String,
+    pub nick: String,
+}
+
+#[derive(Debug)]
+pub struct FlagsDefinition {
+    pub attrs: Vec<syn::Attribute>,
+    pub vis: syn::Visibility,
+    pub ident: syn::Ident,
+    pub crate_path: syn::Path,
+    pub ns: Option<syn::Ident>,
+    pub name: syn::Ident,
+    pub numeric: bool,
+    pub values: Vec<FlagsValue>,
+    pub dynamic: bool,
+}
+
+impl FlagsDefinition {
+    pub fn parse(
+        item: syn::ItemEnum,
+        opts: FlagsOptions,
+        crate_path: syn::Path,
+        errors: &Errors,
+    ) -> Self {
+        let attrs = opts.0;
+        if !item.generics.params.is_empty() {
+            errors.push_spanned(&item.generics, "Generic flags not supported");
+        }
+        let mut values = Vec::new();
+        let mut nicks = HashSet::new();
+        for mut variant in item.variants {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                errors.push_spanned(&variant.fields, "Flags values must be unit variants");
+            }
+            let value = match variant.discriminant {
+                Some((_, value)) => value,
+                None => {
+                    errors.push_spanned(&variant.ident, "Flags values must have a value");
+                    continue;
+                }
+            };
+            let value_attrs = util::extract_attrs(&mut variant.attrs, "flags_value")
+                .map(|attrs| util::parse_attributes::<FlagsValueAttrs>(&attrs, errors))
+                .unwrap_or_default();
+            let ident = format_ident!(
+                "{}",
+                variant.ident.to_string().to_shouty_snake_case(),
+                span = variant.ident.span()
+            );
+            let name = value_attrs
+                .name
+                .map(|n| n.value())
+                .unwrap_or_else(|| variant.ident.to_string());
+            let nick = value_attrs
+                .nick
+                .map(|n| n.value())
+                .unwrap_or_else(|| util::format_name(&variant.ident));
+            if !nicks.insert(nick.clone()) {
+                errors.push_spanned(
+                    &variant.ident,
+                    format!("Duplicate flags value nick `{}`", nick),
+                );
+            }
+            values.push(FlagsValue {
+                ident,
+                attrs: variant.attrs,
+                value,
+                name,
+                nick,
+            });
+        }
+        let name = attrs.name.unwrap_or_else(|| item.ident.clone());
+        Self {
+            attrs: item.attrs,
+            vis: item.vis,
+            ident: item.ident,
+            crate_path,
+            ns: attrs.ns,
+            name,
+            numeric: attrs.numeric.is_some(),
+            values,
+            dynamic: attrs.dynamic.is_some(),
+        }
+    }
+    pub fn glib(&self) -> syn::Path {
+        let go = &self.crate_path;
+        parse_quote! { #go::glib }
+    }
+    pub fn gtype_name(&self) -> String {
+        if let Some(ns) = &self.ns {
+            format!("{}{}", ns, self.name)
+        } else {
+            self.name.to_string()
+        }
+        .to_upper_camel_case()
+    }
+    fn bitflags_definition(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let attrs = &self.attrs;
+        let vis = &self.vis;
+        let ident = &self.ident;
+        let consts = self.values.iter().map(|v| {
+            let attrs = &v.attrs;
+            let const_ident = &v.ident;
+            let value = &v.value;
+            quote! {
+                #(#attrs)*
+                const #const_ident = #value;
+            }
+        });
+        quote! {
+            #go::bitflags::bitflags! {
+                #(#attrs)*
+                #vis struct #ident: ::std::primitive::u32 {
+                    #(#consts)*
+                }
+            }
+        }
+    }
+    fn values_definition(&self, values_ident: &syn::Ident) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.ident;
+        let values = self.values.iter().map(|v| {
+            let const_ident = &v.ident;
+            let name = format!("{}\0", v.name);
+            let nick = format!("{}\0", v.nick);
+            quote! {
+                #glib::gobject_ffi::GFlagsValue {
+                    value: #ident::#const_ident.bits(),
+                    value_name: #name as *const _ as *const _,
+                    value_nick: #nick as *const _ as *const _,
+                },
+            }
+        });
+        let count = self.values.len() + 1;
+        quote! {
+            static mut #values_ident: [#glib::gobject_ffi::GFlagsValue; #count] = [
+                #(#values)*
+                #glib::gobject_ffi::GFlagsValue {
+                    value: 0,
+                    value_name: ::std::ptr::null(),
+                    value_nick: ::std::ptr::null(),
+                },
+            ];
+        }
+    }
+    fn static_type_impl(&self) -> TokenStream {
+        if self.dynamic {
+            return self.dynamic_type_impl();
+        }
+        let glib = self.glib();
+        let ident = &self.ident;
+        let gtype_name = self.gtype_name();
+        let once = format_ident!("ONCE", span = Span::mixed_site());
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let values_ident = format_ident!("VALUES", span = Span::mixed_site());
+        let values = self.values_definition(&values_ident);
+        quote! {
+            impl #glib::StaticType for #ident {
+                fn static_type() -> #glib::Type {
+                    static #once: ::std::sync::Once = ::std::sync::Once::new();
+                    static mut #type_: #glib::Type = #glib::Type::INVALID;
+                    #once.call_once(|| {
+                        #values
+                        let name = ::std::ffi::CString::new(#gtype_name).unwrap();
+                        unsafe {
+                            let type_ = #glib::gobject_ffi::g_flags_register_static(
+                                name.as_ptr(),
+                                #values_ident.as_ptr(),
+                            );
+                            let type_: #glib::Type = #glib::translate::from_glib(type_);
+                            ::std::assert!(type_.is_valid());
+                            #type_ = type_;
+                        }
+                    });
+                    unsafe { #type_ }
+                }
+            }
+        }
+    }
+    fn dynamic_type_impl(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.ident;
+        let vis = &self.vis;
+        let gtype_name = self.gtype_name();
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let values_ident = format_ident!("VALUES", span = Span::mixed_site());
+        let module_ident = format_ident!("type_module", span = Span::mixed_site());
+        let values = self.values_definition(&values_ident);
+        quote! {
+            const _: () = {
+                static #type_: #go::SyncOnceCell<#glib::Type> = #go::SyncOnceCell::new();
+                impl #glib::StaticType for #ident {
+                    fn static_type() -> #glib::Type {
+                        *#type_.get().unwrap_or_else(|| {
+                            ::std::panic!(
+                                "Dynamic type `{}` used before being registered with a TypeModule",
+                                #gtype_name
+                            )
+                        })
+                    }
+                }
+                impl #ident {
+                    #vis fn register_type(
+                        #module_ident: &impl #glib::object::IsA<#go::TypeModule>,
+                    ) -> #glib::Type {
+                        #values
+                        let name = ::std::ffi::CString::new(#gtype_name).unwrap();
+                        let type_: #glib::Type = unsafe {
+                            #glib::translate::from_glib(#glib::gobject_ffi::g_type_module_register_flags(
+                                #glib::translate::ToGlibPtr::to_glib_none(
+                                    #glib::Cast::upcast_ref::<#go::TypeModule>(#module_ident),
+                                ).0,
+                                name.as_ptr(),
+                                #values_ident.as_ptr(),
+                            ))
+                        };
+                        ::std::assert!(type_.is_valid());
+                        *#type_.get_or_init(|| type_)
+                    }
+                }
+            };
+        }
+    }
+    fn translate_impls(&self) -> TokenStream {
+        let glib = self.glib();
+        let ident = &self.ident;
+        quote! {
+            impl #glib::translate::IntoGlib for #ident {
+                type GlibType = ::std::primitive::u32;
+                #[inline]
+                fn into_glib(self) -> ::std::primitive::u32 {
+                    self.bits()
+                }
+            }
+            impl #glib::translate::FromGlib<::std::primitive::u32> for #ident {
+                #[inline]
+                unsafe fn from_glib(value: ::std::primitive::u32) -> Self {
+                    Self::from_bits_truncate(value)
+                }
+            }
+        }
+    }
+    fn value_impls(&self) -> TokenStream {
+        let go = &self.crate_path;
+        let glib = self.glib();
+        let ident = &self.ident;
+        quote! {
+            impl #glib::value::ValueType for #ident {
+                type Type = Self;
+            }
+            unsafe impl<'a> #glib::value::FromValue<'a> for #ident {
+                type Checker = #glib::value::GenericValueTypeChecker<Self>;
+                unsafe fn from_value(value: &'a #glib::Value) -> Self {
+                    #glib::translate::from_glib(#glib::gobject_ffi::g_value_get_flags(
+                        #glib::translate::ToGlibPtr::to_glib_none(value).0,
+                    ))
+                }
+            }
+            impl #glib::value::ToValue for #ident {
+                fn to_value(&self) -> #glib::Value {
+                    let mut value = #glib::Value::for_value_type::<Self>();
+                    unsafe {
+                        #glib::gobject_ffi::g_value_set_flags(
+                            #glib::translate::ToGlibPtrMut::to_glib_none_mut(&mut value).0,
+                            #glib::translate::IntoGlib::into_glib(*self),
+                        );
+                    }
+                    value
+                }
+                fn value_type(&self) -> #glib::Type {
+                    <Self as #glib::StaticType>::static_type()
+                }
+            }
+            impl #go::ParamSpecBuildable for #ident {
+                type ParamSpec = #go::ParamSpecFlagsType<Self>;
+            }
+        }
+    }
+}
+
+impl ToTokens for FlagsDefinition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.bitflags_definition().to_tokens(tokens);
+        self.static_type_impl().to_tokens(tokens);
+        self.translate_impls().to_tokens(tokens);
+        self.value_impls().to_tokens(tokens);
+    }
+}
@@ -0,0 +1,506 @@
+use crate::{
+    ClassDefinition, InterfaceDefinition, Property, PropertyFlags, PublicMethod, Signal,
+    SignalFlags, TypeDefinition, VirtualMethod,
+};
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use std::fmt::Write;
+
+const WRAPPER_TYPES: &[&str] = &[
+    "Arc",
+    "ArcCell",
+    "AtomicCell",
+    "Box",
+    "Cell",
+    "ConstructCell",
+    "ConstructDefaultCell",
+    "ConstructOnlyCell",
+    "ConstructOnlyDefaultCell",
+    "DowngradeCell",
+    "Mutex",
+    "OnceBox",
+    "OnceCell",
+    "Option",
+    "PhantomData",
+    "Rc",
+    "RefCell",
+    "Result",
+    "RwLock",
+    "SyncConstructCell",
+    "SyncOnceCell",
+    "SyncWeakCell",
+    "TemplateChild",
+    "WeakCell",
+    "WeakRef",
+];
+
+#[derive(Debug, Clone)]
+pub struct GirNamespace {
+    pub name: String,
+    pub version: String,
+}
+
+impl GirNamespace {
+    pub fn new(ns: Option<&syn::Ident>, package: &str, version: &str) -> Self {
+        let name = ns
+            .map(|ns| ns.to_string())
+            .unwrap_or_else(|| package.to_upper_camel_case());
+        Self {
+            name,
+            version: version.to_owned(),
+        }
+    }
+    /// Uses the name and major version of the package Cargo is building.
+    pub fn from_env(ns: Option<&syn::Ident>) -> Self {
+        let package = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+        let version = std::env::var("CARGO_PKG_VERSION_MAJOR").unwrap_or_else(|_| "0".into());
+        Self::new(ns, &package, &format!("{}.0", version))
+    }
+    fn symbol_prefix(&self) -> String {
+        self.name.to_snake_case()
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub(crate) fn path_type_name(path: &syn::Path) -> String {
+    let last = path
+        .segments
+        .last()
+        .map(|s| s.ident.to_string())
+        .unwrap_or_default();
+    let prefix =
+        path.segments
+            .iter()
+            .rev()
+            .skip(1)
+            .find_map(|s| match s.ident.to_string().as_str() {
+                "glib" => Some("GObject"),
+                "gio" => Some("Gio"),
+                "gtk4" | "gtk" => Some("Gtk"),
+                "gdk4" | "gdk" => Some("Gdk"),
+                "gsk4" | "gsk" => Some("Gsk"),
+                "graphene" => Some("Graphene"),
+                "cairo" => Some("cairo"),
+                _ => None,
+            });
+    match (prefix, last.as_str()) {
+        (Some("GObject"), "Variant" | "Bytes" | "DateTime" | "Date" | "TimeZone") => {
+            format!("GLib.{}", last)
+        }
+        (Some(prefix), _) => format!("{}.{}", prefix, last),
+        (None, "Object") => String::from("GObject.Object"),
+        (None, _) => last,
+    }
+}
+
+/// Returns the GIR `name` and `c:type` for a Rust type, looking through cells and other
+/// storage wrappers.
+fn gir_type(ty: &syn::Type) -> (String, String) {
+    match ty {
+        syn::Type::Reference(r) => gir_type(&r.elem),
+        syn::Type::Paren(p) => gir_type(&p.elem),
+        syn::Type::Group(g) => gir_type(&g.elem),
+        syn::Type::Tuple(t) if t.elems.is_empty() => ("none".into(), "void".into()),
+        syn::Type::Slice(s) => {
+            let (name, ctype) = gir_type(&s.elem);
+            (name, format!("{}*", ctype))
+        }
+        syn::Type::Path(syn::TypePath { path, .. }) => {
+            let segment = match path.segments.last() {
+                Some(segment) => segment,
+                None => return ("gpointer".into(), "gpointer".into()),
+            };
+            let ident = segment.ident.to_string();
+            if WRAPPER_TYPES.contains(&ident.as_str()) {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                        return gir_type(ty);
+                    }
+                }
+            }
+            let basic = match ident.as_str() {
+                "bool" | "AtomicBool" | "OnceBool" => "gboolean",
+                "i8" | "AtomicI8" => "gint8",
+                "u8" | "AtomicU8" => "guint8",
+                "i16" => "gint16",
+                "u16" => "guint16",
+                "i32" | "AtomicI32" | "NonZeroI32" => "gint",
+                "u32" | "AtomicU32" | "NonZeroU32" => "guint",
+                "i64" | "AtomicI64" | "NonZeroI64" => "gint64",
+                "u64" | "AtomicU64" | "NonZeroU64" => "guint64",
+                "ILong" => "glong",
+                "ULong" => "gulong",
+                "f32" => "gfloat",
+                "f64" => "gdouble",
+                "char" => "gunichar",
+                "Type" => "GType",
+                _ => "",
+            };
+            if !basic.is_empty() {
+                return (basic.into(), basic.into());
+            }
+            match ident.as_str() {
+                "String" | "str" | "GString" | "GStr" => ("utf8".into(), "gchar*".into()),
+                "Variant" => ("GLib.Variant".into(), "GVariant*".into()),
+                "ParamSpec" => ("GObject.ParamSpec".into(), "GParamSpec*".into()),
+                _ => {
+                    let name = path_type_name(path);
+                    let ctype = format!("{}*", name.replace('.', ""));
+                    (name, ctype)
+                }
+            }
+        }
+        _ => ("gpointer".into(), "gpointer".into()),
+    }
+}
+
+fn write_type(out: &mut String, indent: &str, ty: &syn::Type) {
+    let (name, ctype) = gir_type(ty);
+    writeln!(
+        out,
+        "{}<type name=\"{}\" c:type=\"{}\"/>",
+        indent,
+        escape(&name),
+        escape(&ctype)
+    )
+    .unwrap();
+}
+
+fn write_return(out: &mut String, indent: &str, output: &syn::ReturnType) {
+    writeln!(out, "{}<return-value transfer-ownership=\"full\">", indent).unwrap();
+    let inner = format!("{}  ", indent);
+    match output {
+        syn::ReturnType::Default => {
+            writeln!(out, "{}<type name=\"none\" c:type=\"void\"/>", inner).unwrap()
+        }
+        syn::ReturnType::Type(_, ty) => write_type(out, &inner, ty),
+    }
+    writeln!(out, "{}</return-value>", indent).unwrap();
+}
+
+fn write_parameters<'a>(
+    out: &mut String,
+    indent: &str,
+    instance: Option<(&str, &str)>,
+    args: impl Iterator<Item = &'a syn::FnArg>,
+) {
+    let args = args
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(t) => Some(t),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if instance.is_none() && args.is_empty() {
+        return;
+    }
+    let inner = format!("{}  ", indent);
+    let type_indent = format!("{}    ", indent);
+    writeln!(out, "{}<parameters>", indent).unwrap();
+    if let Some((name, ctype)) = instance {
+        writeln!(
+            out,
+            "{}<instance-parameter name=\"self\" transfer-ownership=\"none\">",
+            inner
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "{}<type name=\"{}\" c:type=\"{}*\"/>",
+            type_indent, name, ctype
+        )
+        .unwrap();
+        writeln!(out, "{}</instance-parameter>", inner).unwrap();
+    }
+    for (index, arg) in args.into_iter().enumerate() {
+        let name = match &*arg.pat {
+            syn::Pat::Ident(p) => p.ident.to_string(),
+            _ => format!("arg{}", index),
+        };
+        writeln!(
+            out,
+            "{}<parameter name=\"{}\" transfer-ownership=\"none\">",
+            inner,
+            escape(name.trim_start_matches('_'))
+        )
+        .unwrap();
+        write_type(out, &type_indent, &arg.ty);
+        writeln!(out, "{}</parameter>", inner).unwrap();
+    }
+    writeln!(out, "{}</parameters>", indent).unwrap();
+}
+
+impl Property {
+    fn write_gir(&self, out: &mut String) {
+        let mut attrs = format!("name=\"{}\"", escape(&self.name.to_string()));
+        if !self.get.is_allowed() {
+            attrs.push_str(" readable=\"0\"");
+        }
+        if self.set.is_allowed() {
+            attrs.push_str(" writable=\"1\"");
+        }
+        if self.flags.contains(PropertyFlags::CONSTRUCT) {
+            attrs.push_str(" construct=\"1\"");
+        }
+        if self.flags.contains(PropertyFlags::CONSTRUCT_ONLY) {
+            attrs.push_str(" construct-only=\"1\"");
+        }
+        if self.flags.contains(PropertyFlags::DEPRECATED) {
+            attrs.push_str(" deprecated=\"1\"");
+        }
+        writeln!(
+            out,
+            "      <property {} transfer-ownership=\"none\">",
+            attrs
+        )
+        .unwrap();
+        if let Some(blurb) = &self.blurb {
+            writeln!(
+                out,
+                "        <doc xml:space=\"preserve\">{}</doc>",
+                escape(blurb)
+            )
+            .unwrap();
+        }
+        write_type(out, "        ", &self.field.ty);
+        writeln!(out, "      </property>").unwrap();
+    }
+}
+
+impl Signal {
+    fn write_gir(&self, out: &mut String) {
+        let sig = match &self.sig {
+            Some(sig) if !self.override_ => sig,
+            _ => return,
+        };
+        let mut attrs = format!("name=\"{}\"", escape(&self.name));
+        let when = if self.flags.contains(SignalFlags::RUN_FIRST) {
+            Some("first")
+        } else if self.flags.contains(SignalFlags::RUN_LAST) {
+            Some("last")
+        } else if self.flags.contains(SignalFlags::RUN_CLEANUP) {
+            Some("cleanup")
+        } else {
+            None
+        };
+        if let Some(when) = when {
+            write!(attrs, " when=\"{}\"", when).unwrap();
+        }
+        for (flag, name) in [
+            (SignalFlags::NO_RECURSE, "no-recurse"),
+            (SignalFlags::DETAILED, "detailed"),
+            (SignalFlags::ACTION, "action"),
+            (SignalFlags::NO_HOOKS, "no-hooks"),
+            (SignalFlags::DEPRECATED, "deprecated"),
+        ] {
+            if self.flags.contains(flag) {
+                write!(attrs, " {}=\"1\"", name).unwrap();
+            }
+        }
+        writeln!(out, "      <glib:signal {}>", attrs).unwrap();
+        write_return(out, "        ", &sig.output);
+        write_parameters(out, "        ", None, sig.inputs.iter().skip(1));
+        writeln!(out, "      </glib:signal>").unwrap();
+    }
+}
+
+impl VirtualMethod {
+    fn write_gir(&self, out: &mut String, name: &str, ctype: &str) {
+        writeln!(
+            out,
+            "      <virtual-method name=\"{}\">",
+            self.sig.ident.unraw_string()
+        )
+        .unwrap();
+        write_return(out, "        ", &self.sig.output);
+        write_parameters(
+            out,
+            "        ",
+            Some((name, ctype)),
+            self.sig.inputs.iter().skip(1),
+        );
+        writeln!(out, "      </virtual-method>").unwrap();
+    }
+}
+
+impl PublicMethod {
+    fn write_gir(&self, out: &mut String, name: &str, ctype: &str, c_identifier: &str) {
+        let ident = self.sig.ident.unraw_string();
+        let (tag, instance) = if self.constructor.is_some() {
+            ("constructor", None)
+        } else if self.is_static() {
+            ("function", None)
+        } else {
+            ("method", Some((name, ctype)))
+        };
+        writeln!(
+            out,
+            "      <{} name=\"{}\" c:identifier=\"{}\">",
+            tag, ident, c_identifier
+        )
+        .unwrap();
+        if self.constructor.is_some() {
+            writeln!(out, "        <return-value transfer-ownership=\"full\">").unwrap();
+            writeln!(
+                out,
+                "          <type name=\"{}\" c:type=\"{}*\"/>",
+                name, ctype
+            )
+            .unwrap();
+            writeln!(out, "        </return-value>").unwrap();
+        } else {
+            write_return(out, "        ", &self.sig.output);
+        }
+        let skip = if instance.is_some() { 1 } else { 0 };
+        write_parameters(out, "        ", instance, self.sig.inputs.iter().skip(skip));
+        writeln!(out, "      </{}>", tag).unwrap();
+    }
+}
+
+trait UnrawString {
+    fn unraw_string(&self) -> String;
+}
+
+impl UnrawString for syn::Ident {
+    fn unraw_string(&self) -> String {
+        let s = self.to_string();
+        s.strip_prefix("r#").map(String::from).unwrap_or(s)
+    }
+}
+
+impl TypeDefinition {
+    /// Writes the members of the type. Only `exported` public methods are written, paired with
+    /// their C symbol, since the others have no symbol that introspection could bind.
+    fn write_gir_members(
+        &self,
+        out: &mut String,
+        name: &str,
+        ctype: &str,
+        exported: &[(&PublicMethod, String)],
+    ) {
+        for (method, c_identifier) in exported {
+            method.write_gir(out, name, ctype, c_identifier);
+        }
+        for method in &self.virtual_methods {
+            method.write_gir(out, name, ctype);
+        }
+        for prop in &self.properties {
+            if prop.override_.is_none() {
+                prop.write_gir(out);
+            }
+        }
+        for signal in &self.signals {
+            signal.write_gir(out);
+        }
+    }
+}
+
+fn write_repository(namespace: &GirNamespace, body: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\"?>").unwrap();
+    writeln!(
+        out,
+        "<repository version=\"1.2\" \
+        xmlns=\"http://www.gtk.org/introspection/core/1.0\" \
+        xmlns:c=\"http://www.gtk.org/introspection/c/1.0\" \
+        xmlns:glib=\"http://www.gtk.org/introspection/glib/1.0\">"
+    )
+    .unwrap();
+    writeln!(out, "  <include name=\"GObject\" version=\"2.0\"/>").unwrap();
+    writeln!(
+        out,
+        "  <namespace name=\"{}\" version=\"{}\" c:identifier-prefixes=\"{}\" c:symbol-prefixes=\"{}\">",
+        escape(&namespace.name),
+        escape(&namespace.version),
+        escape(&namespace.name),
+        escape(&namespace.symbol_prefix()),
+    )
+    .unwrap();
+    out.push_str(body);
+    writeln!(out, "  </namespace>").unwrap();
+    writeln!(out, "</repository>").unwrap();
+    out
+}
+
+impl ClassDefinition {
+    /// Generates a standalone GIR document describing this class.
+    pub fn to_gir(&self, namespace: &GirNamespace) -> String {
+        let name = self.inner.name.to_string();
+        let ctype = self.gtype_name();
+        let parent = self
+            .extends
+            .first()
+            .map(path_type_name)
+            .unwrap_or_else(|| String::from("GObject.Object"));
+        let mut attrs = format!(
+            "name=\"{}\" c:type=\"{}\" glib:type-name=\"{}\"",
+            name, ctype, ctype,
+        );
+        // Without `ffi` there are no C symbols, so neither the type function nor the methods
+        // can be referenced.
+        let mut exported = Vec::new();
+        if self.ffi {
+            let prefix = self.c_symbol_prefix();
+            write!(attrs, " glib:get-type=\"{}_get_type\"", prefix).unwrap();
+            exported.extend(self.ffi_methods().map(|m| (m.method, m.c_name(&prefix))));
+        }
+        write!(attrs, " parent=\"{}\"", escape(&parent)).unwrap();
+        if self.abstract_ {
+            attrs.push_str(" abstract=\"1\"");
+        }
+        if self.final_ {
+            attrs.push_str(" final=\"1\"");
+        }
+        let mut body = String::new();
+        writeln!(body, "    <class {}>", attrs).unwrap();
+        for iface in &self.implements {
+            writeln!(
+                body,
+                "      <implements name=\"{}\"/>",
+                escape(&path_type_name(iface))
+            )
+            .unwrap();
+        }
+        self.inner
+            .write_gir_members(&mut body, &name, &ctype, &exported);
+        writeln!(body, "    </class>").unwrap();
+        write_repository(namespace, &body)
+    }
+}
+
+impl InterfaceDefinition {
+    /// Generates a standalone GIR document describing this interface.
+    pub fn to_gir(&self, namespace: &GirNamespace) -> String {
+        let name = self.inner.name.to_string();
+        let ctype = self.gtype_name();
+        let mut body = String::new();
+        writeln!(
+            body,
+            "    <interface name=\"{}\" c:type=\"{}\" glib:type-name=\"{}\">",
+            name, ctype, ctype,
+        )
+        .unwrap();
+        for prereq in &self.requires {
+            writeln!(
+                body,
+                "      <prerequisite name=\"{}\"/>",
+                escape(&path_type_name(prereq))
+            )
+            .unwrap();
+        }
+        self.inner.write_gir_members(&mut body, &name, &ctype, &[]);
+        writeln!(body, "    </interface>").unwrap();
+        write_repository(namespace, &body)
+    }
+}
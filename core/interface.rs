@@ -2,7 +2,10 @@ use crate::{
     util::{self, Errors},
     TypeBase, TypeDefinition, TypeMode,
 };
-use darling::{util::PathList, FromMeta};
+use darling::{
+    util::{Flag, PathList, SpannedValue},
+    FromMeta,
+};
 use heck::ToUpperCamelCase;
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
@@ -19,6 +22,7 @@ struct Attrs {
     pub parent_trait: Option<syn::TypePath>,
     pub wrapper: Option<bool>,
     pub requires: PathList,
+    pub dynamic: SpannedValue<Flag>,
 }
 
 #[derive(Debug)]
@@ -40,6 +44,7 @@ pub struct InterfaceDefinition {
     pub parent_trait: Option<syn::TypePath>,
     pub wrapper: bool,
     pub requires: Vec<syn::Path>,
+    pub dynamic: bool,
 }
 
 impl InterfaceDefinition {
@@ -54,6 +59,18 @@ impl InterfaceDefinition {
         let inner =
             TypeDefinition::parse(module, TypeBase::Interface, attrs.name, crate_path, errors);
 
+        if attrs.dynamic.is_some() {
+            if inner.is_generic() {
+                errors.push(
+                    attrs.dynamic.span(),
+                    "`dynamic` not supported on generic interfaces",
+                );
+            }
+            for signal in &inner.signals {
+                errors.push_spanned(&signal.ident, "Signals not supported on dynamic interfaces");
+            }
+        }
+
         let name = inner.name.clone();
         Self {
             inner,
@@ -70,8 +87,17 @@ impl InterfaceDefinition {
             parent_trait: attrs.parent_trait,
             wrapper: attrs.wrapper.unwrap_or(true),
             requires: (*attrs.requires).clone(),
+            dynamic: attrs.dynamic.is_some(),
         }
     }
+    pub fn gtype_name(&self) -> String {
+        if let Some(ns) = &self.ns {
+            format!("{}{}", ns, self.inner.name)
+        } else {
+            self.inner.name.to_string()
+        }
+        .to_upper_camel_case()
+    }
     pub fn add_private_items(&mut self, errors: &Errors) {
         let extra = self.extra_private_items();
         self.inner.ensure_items().extend(extra);
@@ -195,12 +221,7 @@ impl InterfaceDefinition {
                 #glib::subclass::prelude::ObjectInterface
             },
         );
-        let gtype_name = if let Some(ns) = &self.ns {
-            format!("{}{}", ns, name)
-        } else {
-            name.to_string()
-        }
-        .to_upper_camel_case();
+        let gtype_name = self.gtype_name();
         let prerequisites = self.prerequisites_alias();
         let interface_init = self.interface_init_method();
         let properties = self.inner.properties_method();
@@ -210,18 +231,58 @@ impl InterfaceDefinition {
                 fn #ident(type_: &mut #glib::subclass::types::InitializingType<Self>)
             }
         });
+        let items = quote! {
+            const NAME: &'static ::std::primitive::str = #gtype_name;
+            type Prerequisites = super::#prerequisites;
+            #interface_init
+            #properties
+            #signals
+            #type_init
+        };
+        if self.dynamic {
+            return self.dynamic_object_interface_impl(head, items);
+        }
         quote! {
             const _: () = {
                 #[allow(unused_imports)]
                 use #glib;
                 #[#glib::object_interface]
                 unsafe #head {
-                    const NAME: &'static ::std::primitive::str = #gtype_name;
-                    type Prerequisites = super::#prerequisites;
-                    #interface_init
-                    #properties
-                    #signals
-                    #type_init
+                    #items
+                }
+            };
+        }
+    }
+    fn dynamic_object_interface_impl(&self, head: TokenStream, items: TokenStream) -> TokenStream {
+        let go = &self.inner.crate_path;
+        let glib = self.inner.glib();
+        let name = &self.inner.name;
+        let gtype_name = self.gtype_name();
+        let type_ = format_ident!("TYPE", span = Span::mixed_site());
+        let module_ident = format_ident!("type_module", span = Span::mixed_site());
+        quote! {
+            const _: () = {
+                static #type_: #go::SyncOnceCell<#glib::Type> = #go::SyncOnceCell::new();
+                unsafe #head {
+                    #items
+                }
+                unsafe impl #glib::subclass::interface::ObjectInterfaceType for #name {
+                    fn type_() -> #glib::Type {
+                        *#type_.get().unwrap_or_else(|| {
+                            ::std::panic!(
+                                "Dynamic type `{}` used before being registered with a TypeModule",
+                                #gtype_name
+                            )
+                        })
+                    }
+                }
+                impl super::#name {
+                    pub fn register_type(
+                        #module_ident: &impl #glib::object::IsA<#go::TypeModule>,
+                    ) -> #glib::Type {
+                        let type_ = #go::register_dynamic_interface::<#name>(#module_ident);
+                        *#type_.get_or_init(|| type_)
+                    }
                 }
             };
         }
@@ -1,19 +1,30 @@
+mod boxed;
 mod class;
 mod closures;
+mod enums;
+mod ffi;
+mod flags;
+mod gir;
 mod interface;
 mod property;
 mod public_method;
 mod signal;
+mod signal_hook;
 mod type_definition;
 pub mod util;
 pub mod validations;
 mod virtual_method;
 
+pub use boxed::*;
 pub use class::*;
 pub use closures::*;
+pub use enums::*;
+pub use flags::*;
+pub use gir::*;
 pub use interface::*;
 pub use property::*;
 pub use public_method::*;
 pub use signal::*;
+pub use signal_hook::*;
 pub use type_definition::*;
 pub use virtual_method::*;
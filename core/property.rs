@@ -21,6 +21,9 @@ pub(crate) struct PropertiesAttrs {
     pod: Flag,
     final_type: Option<syn::Ident>,
     interface: SpannedValue<Flag>,
+    history: SpannedValue<Flag>,
+    transactions: SpannedValue<Flag>,
+    settings_schema: Option<syn::LitStr>,
     data: darling::ast::Data<darling::util::Ignored, PropertyAttrs>,
 }
 
@@ -30,6 +33,9 @@ impl Default for PropertiesAttrs {
             pod: Default::default(),
             final_type: None,
             interface: Default::default(),
+            history: Default::default(),
+            transactions: Default::default(),
+            settings_schema: None,
             data: darling::ast::Data::empty_from(&syn::Data::Struct(syn::DataStruct {
                 struct_token: Default::default(),
                 fields: syn::Fields::Unit,
@@ -71,6 +77,7 @@ struct PropertyAttrs {
     boxed: SpannedValue<Flag>,
     object: SpannedValue<Flag>,
     computed: SpannedValue<Flag>,
+    depends_on: Option<SpannedValue<DependsOn>>,
     storage: Option<SpannedValue<PropertyStorageAttr>>,
     #[darling(rename = "abstract")]
     abstract_: SpannedValue<Flag>,
@@ -78,11 +85,31 @@ struct PropertyAttrs {
     override_iface: Option<syn::Path>,
     builder_defaults: Option<syn::ExprArray>,
     builder: SpannedValue<HashMap<syn::Ident, InnerExpr>>,
+    validate: Option<syn::Path>,
+    bind: Option<SpannedValue<PropertyBindAttr>>,
+    settings: Option<SpannedValue<PropertySettingsAttr>>,
 }
 
 #[derive(Debug)]
 struct InnerExpr(syn::Expr);
 
+#[derive(Debug, Default)]
+struct DependsOn(Vec<syn::LitStr>);
+
+impl FromMeta for DependsOn {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        items
+            .iter()
+            .map(|item| match item {
+                syn::NestedMeta::Lit(syn::Lit::Str(lit)) => Ok(lit.clone()),
+                syn::NestedMeta::Lit(lit) => Err(darling::Error::unexpected_lit_type(lit)),
+                syn::NestedMeta::Meta(_) => Err(darling::Error::unsupported_format("meta")),
+            })
+            .collect::<darling::Result<_>>()
+            .map(Self)
+    }
+}
+
 impl FromMeta for InnerExpr {
     fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
         if items.len() != 1 {
@@ -274,6 +301,7 @@ impl PropertyAttrs {
         let storage = ("storage", self.storage.as_ref().map(|s| s.storage.span()));
         let abstract_ = ("abstract", check_flag(&self.abstract_));
         let computed = ("computed", check_flag(&self.computed));
+        let depends_on = ("depends_on", self.depends_on.as_ref().map(|d| d.span()));
         let write_only = (
             "write-only",
             (*self.get)
@@ -312,11 +340,20 @@ impl PropertyAttrs {
             "builder",
             (!self.builder.is_empty()).then(|| self.builder.span()),
         );
+        let validate = ("validate", self.validate.as_ref().map(|v| v.span()));
+        let bind = ("bind", self.bind.as_ref().map(|b| b.span()));
+        let settings = ("settings", self.settings.as_ref().map(|s| s.span()));
 
         only_one([&enum_, &flags, &boxed, &object], errors);
         only_one([&override_class, &override_iface], errors);
         only_one([&storage, &abstract_, &computed], errors);
 
+        if let Some(depends_on) = &self.depends_on {
+            if self.computed.is_none() {
+                errors.push(depends_on.span(), "`depends_on` requires `computed`");
+            }
+        }
+
         if interface.1.is_some() {
             disallow(
                 "interface property",
@@ -326,6 +363,10 @@ impl PropertyAttrs {
                     &computed,
                     &custom_getter,
                     &custom_setter,
+                    &validate,
+                    &bind,
+                    &settings,
+                    &depends_on,
                 ],
                 errors,
             );
@@ -355,13 +396,86 @@ impl PropertyAttrs {
                     &user_8,
                     &explicit_notify,
                     &deprecated,
+                    &validate,
+                    &bind,
+                    &settings,
                 ],
                 errors,
             );
         }
 
         if matches!(*self.set, Some(PropertyPermission::Deny)) {
-            disallow("read-only property", [&construct, &construct_only], errors);
+            disallow(
+                "read-only property",
+                [&construct, &construct_only, &validate, &bind],
+                errors,
+            );
+        }
+
+        if let Some(bind) = &self.bind {
+            if bind.bidirectional.is_some() && write_only.1.is_some() {
+                errors.push(
+                    bind.span(),
+                    "`bidirectional` binding not allowed on write-only property",
+                );
+            }
+            if bind.transform_from.is_some() && bind.bidirectional.is_none() {
+                errors.push_spanned(
+                    &bind.transform_from,
+                    "`transform_from` requires a `bidirectional` binding",
+                );
+            }
+        }
+
+        if let Some(settings) = &self.settings {
+            let key = settings.key(&name.to_string());
+            if !is_valid_settings_key(&key) {
+                errors.push(
+                    settings.span(),
+                    format!(
+                        "Invalid settings key '{}'. Keys must start with a lowercase letter and only contain lowercase letters, numbers and single '-'",
+                        key
+                    ),
+                );
+            }
+            let readable = (*self.get)
+                .as_ref()
+                .map(|p| p.is_allowed())
+                .unwrap_or(false);
+            let writable = (*self.set)
+                .as_ref()
+                .map(|p| p.is_allowed())
+                .unwrap_or(false);
+            if settings.reads_key() && !writable {
+                errors.push(
+                    settings.span(),
+                    "read-only property can only be bound with `settings(set)`",
+                );
+            } else if settings.writes_key() && !readable {
+                errors.push(
+                    settings.span(),
+                    "write-only property can only be bound with `settings(get)`",
+                );
+            }
+            if settings.0.with.is_none() {
+                for (attr_name, span) in [("boxed", &boxed.1), ("object", &object.1)] {
+                    if span.is_some() {
+                        errors.push(
+                            settings.span(),
+                            format!(
+                                "`{}` property can only be bound to settings with `with`",
+                                attr_name
+                            ),
+                        );
+                    }
+                }
+            }
+            if settings.reads_key() && self.construct_only.unwrap_or(false) {
+                errors.push(
+                    settings.span(),
+                    "construct-only property can only be bound with `settings(set)`",
+                );
+            }
         }
 
         if self.borrow.is_some() {
@@ -378,6 +492,137 @@ impl PropertyAttrs {
     }
 }
 
+#[derive(Debug, FromMeta)]
+struct PropertyBindAttr {
+    source: syn::LitStr,
+    #[darling(default)]
+    property: Option<syn::LitStr>,
+    #[darling(default)]
+    bidirectional: Flag,
+    #[darling(default)]
+    invert_boolean: Flag,
+    #[darling(default)]
+    transform_to: Option<syn::Path>,
+    #[darling(default)]
+    transform_from: Option<syn::Path>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PropertyBinding {
+    pub source: syn::LitStr,
+    pub property: Option<syn::LitStr>,
+    pub bidirectional: bool,
+    pub invert_boolean: bool,
+    pub transform_to: Option<syn::Path>,
+    pub transform_from: Option<syn::Path>,
+}
+
+impl From<&PropertyBindAttr> for PropertyBinding {
+    fn from(attr: &PropertyBindAttr) -> Self {
+        Self {
+            source: attr.source.clone(),
+            property: attr.property.clone(),
+            bidirectional: attr.bidirectional.is_some(),
+            invert_boolean: attr.invert_boolean.is_some(),
+            transform_to: attr.transform_to.clone(),
+            transform_from: attr.transform_from.clone(),
+        }
+    }
+}
+
+impl PropertyBinding {
+    /// Name of the field or property on `self` holding the source object.
+    pub fn source_name(&self) -> String {
+        let source = self.source.value();
+        source
+            .strip_prefix("self.")
+            .map(|s| s.to_owned())
+            .unwrap_or(source)
+    }
+}
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct PropertySettingsOptions {
+    key: Option<syn::LitStr>,
+    get: Flag,
+    set: Flag,
+    no_sensitivity: Flag,
+    with: Option<syn::Path>,
+    default: Option<syn::LitStr>,
+}
+
+#[derive(Debug)]
+struct PropertySettingsAttr(PropertySettingsOptions);
+
+impl FromMeta for PropertySettingsAttr {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self(Default::default()))
+    }
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        PropertySettingsOptions::from_list(items).map(Self)
+    }
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        match value {
+            syn::Lit::Str(key) => Ok(Self(PropertySettingsOptions {
+                key: Some(key.clone()),
+                ..Default::default()
+            })),
+            lit => Err(darling::Error::unexpected_lit_type(lit)),
+        }
+    }
+}
+
+impl PropertySettingsAttr {
+    fn key(&self, name: &str) -> String {
+        self.0
+            .key
+            .as_ref()
+            .map(|k| k.value())
+            .unwrap_or_else(|| name.to_owned())
+    }
+    fn reads_key(&self) -> bool {
+        self.0.get.is_some() || self.0.set.is_none()
+    }
+    fn writes_key(&self) -> bool {
+        self.0.set.is_some() || self.0.get.is_none()
+    }
+}
+
+fn is_valid_settings_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !key.contains("--")
+        && !key.ends_with('-')
+}
+
+/// A GSettings key bound to a property, from `settings = "..."` or `settings(...)` on the
+/// property.
+#[derive(Debug, Clone)]
+pub struct PropertySettings {
+    pub key: String,
+    pub get: bool,
+    pub set: bool,
+    pub no_sensitivity: bool,
+    pub with: Option<syn::Path>,
+    pub default: Option<syn::LitStr>,
+}
+
+impl PropertySettings {
+    fn new(attr: &PropertySettingsAttr, name: &str) -> Self {
+        Self {
+            key: attr.key(name),
+            get: attr.0.get.is_some(),
+            set: attr.0.set.is_some(),
+            no_sensitivity: attr.0.no_sensitivity.is_some(),
+            with: attr.0.with.clone(),
+            default: attr.0.default.clone(),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct PropertyStorageAttr {
     storage: syn::Expr,
@@ -630,6 +875,9 @@ pub(crate) struct Properties {
     pub(crate) base: TypeBase,
     pub(crate) properties: Vec<Property>,
     pub(crate) fields: syn::Fields,
+    pub(crate) settings_schema: Option<syn::LitStr>,
+    pub(crate) history: Option<Span>,
+    pub(crate) transactions: Option<Span>,
 }
 
 impl Default for Properties {
@@ -639,6 +887,9 @@ impl Default for Properties {
             base: TypeBase::Class,
             properties: Vec::new(),
             fields: syn::Fields::Unit,
+            settings_schema: None,
+            history: None,
+            transactions: None,
         }
     }
 }
@@ -653,6 +904,9 @@ impl Properties {
             pod,
             final_type,
             interface,
+            history,
+            transactions,
+            settings_schema,
             data,
         } = match PropertiesAttrs::from_derive_input(input) {
             Ok(attrs) => attrs,
@@ -665,6 +919,12 @@ impl Properties {
             if let Some(final_type) = &final_type {
                 errors.push_spanned(final_type, "`final_type` not allowed here");
             }
+            if let Some(schema) = &settings_schema {
+                errors.push_spanned(schema, "`settings_schema` not allowed here");
+            }
+            if transactions.is_some() {
+                errors.push(transactions.span(), "`transactions` not allowed here");
+            }
         } else if interface.is_some() {
             errors.push(interface.span(), "`interface` not allowed here");
         }
@@ -674,6 +934,20 @@ impl Properties {
                 .map(|_| TypeBase::Interface)
                 .unwrap_or(TypeBase::Class)
         });
+        if history.is_some() && base == TypeBase::Interface {
+            errors.push(history.span(), "`history` not allowed on interface");
+        }
+        if transactions.is_some() && base == TypeBase::Interface {
+            errors.push(
+                transactions.span(),
+                "`transactions` not allowed on interface",
+            );
+        }
+        if let Some(schema) = &settings_schema {
+            if base == TypeBase::Interface {
+                errors.push_spanned(schema, "`settings_schema` not allowed on interface");
+            }
+        }
         let data = data.take_struct().map(|s| s.fields).unwrap_or_default();
 
         let fields = match &input.data {
@@ -682,6 +956,7 @@ impl Properties {
         };
 
         let mut prop_names = HashSet::new();
+        let mut settings_keys = HashSet::new();
         let mut properties = vec![];
         let mut out_fields = Vec::new();
         for (index, (attrs, mut field)) in
@@ -689,7 +964,8 @@ impl Properties {
         {
             let prop = Property::new(attrs, &field, index, pod, base, errors);
             let mut has_field = true;
-            if let Some(prop) = prop {
+            if let Some(mut prop) = prop {
+                prop.history = history.is_some();
                 let name = prop.name.to_string();
                 if prop_names.contains(&name) {
                     errors.push(
@@ -698,6 +974,20 @@ impl Properties {
                     );
                 }
                 prop_names.insert(name);
+                if let Some(settings) = &prop.settings {
+                    if settings_schema.is_none() {
+                        errors.push_spanned(
+                            &prop.field,
+                            "`settings` requires `#[properties(settings_schema = \"...\")]`",
+                        );
+                    }
+                    if !settings_keys.insert(settings.key.clone()) {
+                        errors.push_spanned(
+                            &prop.field,
+                            format!("Duplicate binding for settings key `{}`", settings.key),
+                        );
+                    }
+                }
                 has_field = prop.storage.has_field();
                 properties.push(prop);
             }
@@ -707,6 +997,8 @@ impl Properties {
             }
         }
 
+        resolve_dependencies(&mut properties, errors);
+
         let fields = match fields {
             syn::Fields::Named(_) => syn::Fields::Named(syn::FieldsNamed {
                 brace_token: Default::default(),
@@ -724,10 +1016,92 @@ impl Properties {
             base,
             properties,
             fields,
+            settings_schema,
+            history: history.is_some().then(|| history.span()),
+            transactions: transactions.is_some().then(|| transactions.span()),
         }
     }
 }
 
+fn resolve_dependencies(properties: &mut [Property], errors: &Errors) {
+    let find = |name: &syn::LitStr| {
+        let value = name.value().replace('_', "-");
+        properties.iter().position(|p| p.name.to_string() == value)
+    };
+    let mut depends_on = vec![Vec::new(); properties.len()];
+    for (index, prop) in properties.iter().enumerate() {
+        for name in &prop.depends_on {
+            match find(name) {
+                Some(dep) => depends_on[index].push(dep),
+                None => errors.push_spanned(
+                    name,
+                    format!("Unknown property `{}` in `depends_on`", name.value()),
+                ),
+            }
+        }
+    }
+
+    fn visit(
+        index: usize,
+        depends_on: &[Vec<usize>],
+        stack: &mut Vec<usize>,
+        done: &mut [bool],
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        if let Some(start) = stack.iter().position(|i| *i == index) {
+            cycles.push(stack[start..].to_vec());
+            return;
+        }
+        if done[index] {
+            return;
+        }
+        stack.push(index);
+        for dep in &depends_on[index] {
+            visit(*dep, depends_on, stack, done, cycles);
+        }
+        stack.pop();
+        done[index] = true;
+    }
+    let mut done = vec![false; properties.len()];
+    let mut cycles = Vec::new();
+    for index in 0..properties.len() {
+        visit(index, &depends_on, &mut Vec::new(), &mut done, &mut cycles);
+    }
+    if !cycles.is_empty() {
+        for cycle in cycles {
+            let path = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|i| properties[*i].name.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            errors.push(
+                properties[cycle[0]].span(),
+                format!("Dependency cycle in `depends_on`: {}", path),
+            );
+        }
+        return;
+    }
+
+    for index in 0..properties.len() {
+        let mut dependents = Vec::new();
+        let mut pending = vec![index];
+        while let Some(current) = pending.pop() {
+            for (dependent, deps) in depends_on.iter().enumerate() {
+                if deps.contains(&current) && !dependents.contains(&dependent) {
+                    dependents.push(dependent);
+                    pending.push(dependent);
+                }
+            }
+        }
+        dependents.sort_unstable();
+        properties[index].dependents = dependents
+            .into_iter()
+            .map(|i| properties[i].name.to_string())
+            .collect();
+    }
+}
+
 #[derive(Debug)]
 pub struct Property {
     pub field: syn::Field,
@@ -745,6 +1119,12 @@ pub struct Property {
     pub buildable_defaults: Vec<syn::Expr>,
     pub buildable_props: Vec<(syn::Ident, syn::Expr)>,
     pub flags: PropertyFlags,
+    pub validate: Option<syn::Path>,
+    pub bind: Option<PropertyBinding>,
+    pub settings: Option<PropertySettings>,
+    pub depends_on: Vec<syn::LitStr>,
+    pub dependents: Vec<String>,
+    pub history: bool,
 }
 
 impl Property {
@@ -763,9 +1143,14 @@ impl Property {
         }
 
         let flags = attrs.flags(pod);
+        let name = attrs.name(index);
+        let settings = attrs
+            .settings
+            .as_deref()
+            .map(|s| PropertySettings::new(s, &name.to_string()));
         Some(Self {
             field: field.clone(),
-            name: attrs.name(index),
+            name,
             special_type: attrs.special_type(),
             storage: attrs.storage(index, base),
             override_: attrs.override_(),
@@ -785,6 +1170,16 @@ impl Property {
                 .map(|(i, e)| (i, e.0))
                 .collect(),
             flags,
+            validate: attrs.validate.take(),
+            bind: attrs.bind.as_deref().map(From::from),
+            settings,
+            depends_on: attrs
+                .depends_on
+                .as_deref()
+                .map(|d| d.0.clone())
+                .unwrap_or_default(),
+            dependents: Vec::new(),
+            history: false,
         })
     }
     pub(crate) fn definition(&self, go: &syn::Path) -> TokenStream {
@@ -941,8 +1336,12 @@ impl Property {
             set_ty
                 .map(|s| {
                     let value_ident = syn::Ident::new("value", Span::mixed_site());
-                    quote_spanned! { self.span() =>
-                        #path(#recv, #value_ident.get::<#s>().unwrap())
+                    if self.validate.is_some() {
+                        quote_spanned! { self.span() => #path(#recv, #value_ident) }
+                    } else {
+                        quote_spanned! { self.span() =>
+                            #path(#recv, #value_ident.get::<#s>().unwrap())
+                        }
                     }
                 })
                 .unwrap_or_else(|| {
@@ -1117,6 +1516,45 @@ impl Property {
             }
         }
     }
+    fn validate_impl(&self, recv: &TokenStream, go: &syn::Path) -> Option<TokenStream> {
+        self.validate.as_ref().map(|validate| {
+            let value_ident = syn::Ident::new("value", Span::mixed_site());
+            let error_ident = syn::Ident::new("error", Span::mixed_site());
+            let name = self.name.to_string();
+            quote_spanned! { validate.span() =>
+                let #value_ident = match #validate(#recv, #value_ident) {
+                    ::std::result::Result::Ok(#value_ident) => #value_ident,
+                    ::std::result::Result::Err(#error_ident) => {
+                        #go::reject_property_value(
+                            #recv,
+                            #name,
+                            <#go::ValidationError as ::std::convert::From<_>>::from(#error_ident),
+                        );
+                        return;
+                    }
+                };
+            }
+        })
+    }
+    fn records_history(&self) -> bool {
+        self.history
+            && self.get.is_allowed()
+            && self.set.is_allowed()
+            && !self.flags.contains(PropertyFlags::CONSTRUCT_ONLY)
+    }
+    fn history_guard(
+        &self,
+        recv: TokenStream,
+        pspec: TokenStream,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        self.records_history().then(|| {
+            let guard_ident = syn::Ident::new("_history", Span::mixed_site());
+            quote_spanned! { Span::mixed_site() =>
+                let #guard_ident = #go::HistoryGuard::new(#recv, #pspec);
+            }
+        })
+    }
     pub(crate) fn set_impl(
         &self,
         index: usize,
@@ -1128,25 +1566,39 @@ impl Property {
             let cmp = self.pspec_cmp(index);
             let ty = self.store_write_type(go);
             let value_ident = syn::Ident::new("value", Span::mixed_site());
+            let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+            let validate = self
+                .validate_impl(&obj_ident.to_token_stream(), go)
+                .map(|validate| {
+                    quote_spanned! { self.span() =>
+                        let #value_ident = #value_ident.get::<#ty>().unwrap();
+                        #validate
+                    }
+                });
+            let explicit_notify = self.flags.contains(PropertyFlags::EXPLICIT_NOTIFY);
             let body = if let Some(call) = self.custom_call(Some(&ty), method, &glib) {
                 quote! { #call; }
-            } else if self.is_set_inline() {
-                let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+            } else if self.is_set_inline() || validate.is_some() {
                 let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
                 let body = self.inline_set_impl(
                     None,
-                    self.flags.contains(PropertyFlags::EXPLICIT_NOTIFY)
-                    .then(|| || quote_spanned! { self.span() =>
-                        <<Self as #glib::subclass::types::ObjectSubclass>::Type as #glib::object::ObjectExt>::notify_by_pspec(
-                            #obj_ident,
-                            #pspec_ident
-                        );
+                    (self.is_set_inline() && explicit_notify).then(|| || {
+                        quote_spanned! { self.span() =>
+                            <<Self as #glib::subclass::types::ObjectSubclass>::Type as #glib::object::ObjectExt>::notify_by_pspec(
+                                #obj_ident,
+                                #pspec_ident
+                            );
+                        }
                     }),
                     go
                 );
-                quote! {
-                    let #value_ident = #value_ident.get::<#ty>().unwrap();
-                    #body
+                if validate.is_some() {
+                    body
+                } else {
+                    quote! {
+                        let #value_ident = #value_ident.get::<#ty>().unwrap();
+                        #body
+                    }
                 }
             } else {
                 let (storage, field) = self.field_storage(None, go);
@@ -1160,8 +1612,16 @@ impl Property {
                     }
                 }
             };
+            let pspec_ident = syn::Ident::new("pspec", Span::mixed_site());
+            let history = self.history_guard(
+                obj_ident.to_token_stream(),
+                pspec_ident.to_token_stream(),
+                go,
+            );
             quote_spanned! { self.span() =>
                 if #cmp {
+                    #history
+                    #validate
                     #body
                     return;
                 }
@@ -1192,25 +1652,18 @@ impl Property {
     ) -> Option<TokenStream> {
         self.setter_prototype(go).map(|proto| {
             let self_ident = syn::Ident::new("self", Span::mixed_site());
-            let value_ident = syn::Ident::new("value", Span::mixed_site());
             let body = if !self.is_abstract() && self.is_set_inline() {
-                self.inline_set_impl(
-                    Some(object_type),
-                    Some(|| {
-                        quote_spanned! { self.span() =>
-                            <Self as #go::glib::object::ObjectExt>::notify_by_pspec(
-                                #self_ident,
-                                &#properties_path()[#index]
-                            );
-                        }
-                    }),
-                    go,
-                )
-            } else {
-                let name = self.name.to_string();
-                quote_spanned! { self.span() =>
-                    <Self as #go::glib::object::ObjectExt>::set_property(#self_ident, #name, #value_ident);
+                let recv = quote_spanned! { Span::mixed_site() =>
+                    #go::glib::Cast::upcast_ref::<#object_type>(#self_ident)
+                };
+                let validate = self.validate_impl(&recv, go);
+                let body = self.inline_store(index, object_type, properties_path, go);
+                quote! {
+                    #validate
+                    #body
                 }
+            } else {
+                self.property_store(go)
             };
             quote_spanned! { self.span() =>
                 #proto {
@@ -1220,6 +1673,96 @@ impl Property {
             }
         })
     }
+    fn inline_store(
+        &self,
+        index: usize,
+        object_type: &syn::Type,
+        properties_path: &syn::ExprPath,
+        go: &syn::Path,
+    ) -> TokenStream {
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let history = self.history_guard(
+            quote_spanned! { Span::mixed_site() =>
+                #glib::Cast::upcast_ref::<#object_type>(#self_ident)
+            },
+            quote_spanned! { Span::mixed_site() => &#properties_path()[#index] },
+            go,
+        );
+        let body = self.inline_set_impl(
+            Some(object_type),
+            Some(|| {
+                quote_spanned! { self.span() =>
+                    <Self as #glib::object::ObjectExt>::notify_by_pspec(
+                        #self_ident,
+                        &#properties_path()[#index]
+                    );
+                }
+            }),
+            go,
+        );
+        quote! {
+            #history
+            #body
+        }
+    }
+    fn property_store(&self, go: &syn::Path) -> TokenStream {
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let name = self.name.to_string();
+        quote_spanned! { self.span() =>
+            <Self as #go::glib::object::ObjectExt>::set_property(#self_ident, #name, #value_ident);
+        }
+    }
+    #[inline]
+    fn try_setter_name(&self) -> syn::Ident {
+        format_ident!("try_set_{}", self.name.field_name(), span = self.span())
+    }
+    fn try_setter_prototype(&self, go: &syn::Path) -> Option<TokenStream> {
+        self.validate.as_ref()?;
+        self.setter_prototype(go).map(|_| {
+            let method_name = self.try_setter_name();
+            let ty = self.store_write_type(go);
+            quote_spanned! { Span::mixed_site() =>
+                fn #method_name(&self, value: #ty) -> ::std::result::Result<(), #go::ValidationError>
+            }
+        })
+    }
+    fn try_setter_definition(
+        &self,
+        index: usize,
+        object_type: &syn::Type,
+        properties_path: &syn::ExprPath,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        self.try_setter_prototype(go).map(|proto| {
+            let self_ident = syn::Ident::new("self", Span::mixed_site());
+            let value_ident = syn::Ident::new("value", Span::mixed_site());
+            if !self.is_abstract() && self.is_set_inline() {
+                let validate = self.validate.as_ref().unwrap();
+                let body = self.inline_store(index, object_type, properties_path, go);
+                quote_spanned! { self.span() =>
+                    #proto {
+                        let #value_ident = #validate(
+                            #go::glib::Cast::upcast_ref::<#object_type>(#self_ident),
+                            #value_ident,
+                        )?;
+                        #body
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            } else {
+                // validated once by `set_property`, which hands the error back here
+                let name = self.name.to_string();
+                let body = self.property_store(go);
+                quote_spanned! { self.span() =>
+                    #proto {
+                        #go::try_set_property(#self_ident, #name, move || { #body })
+                    }
+                }
+            }
+        })
+    }
     fn notify_prototype(&self) -> Option<TokenStream> {
         (!self.is_inherited()
             && self.get.is_allowed()
@@ -1238,11 +1781,12 @@ impl Property {
         glib: &syn::Path,
     ) -> Option<TokenStream> {
         self.notify_prototype().map(|proto| {
+            let self_ident = syn::Ident::new("self", Span::mixed_site());
             quote_spanned! { Span::mixed_site() =>
                 #proto {
                     #![inline]
                     <Self as #glib::object::ObjectExt>::notify_by_pspec(
-                        self,
+                        #self_ident,
                         &#properties_path()[#index]
                     );
                 }
@@ -1296,6 +1840,112 @@ impl Property {
                 }
             })
     }
+    fn connect_scoped_prototype(
+        &self,
+        concurrency: Concurrency,
+        local: bool,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        self.connect_prototype(concurrency, local, &glib)?;
+        let method_name = if local {
+            format_ident!(
+                "connect_{}_notify_local_scoped",
+                self.name.field_name(),
+                span = self.span()
+            )
+        } else {
+            format_ident!(
+                "connect_{}_notify_scoped",
+                self.name.field_name(),
+                span = self.span()
+            )
+        };
+        Some(quote_spanned! { Span::mixed_site() =>
+            fn #method_name<Func: Fn(&Self) #concurrency + 'static>(&self, func: Func) -> #go::SignalGuard<Self>
+        })
+    }
+    fn connect_scoped_definition(
+        &self,
+        concurrency: Concurrency,
+        local: bool,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let proto = self.connect_scoped_prototype(concurrency, local, go)?;
+        let connect = if local {
+            format_ident!(
+                "connect_{}_notify_local",
+                self.name.field_name(),
+                span = self.span()
+            )
+        } else {
+            format_ident!(
+                "connect_{}_notify",
+                self.name.field_name(),
+                span = self.span()
+            )
+        };
+        Some(quote_spanned! { Span::mixed_site() =>
+            #proto {
+                #![inline]
+                #go::SignalGuard::new(self, Self::#connect(self, func))
+            }
+        })
+    }
+    /// Whether the value can change after construction and be read back, so a transaction can
+    /// tell if it changed.
+    pub(crate) fn is_watchable(&self) -> bool {
+        self.get.is_allowed() && !self.flags.contains(PropertyFlags::CONSTRUCT_ONLY)
+    }
+    /// Whether a transaction can put back the previous value.
+    pub(crate) fn is_restorable(&self) -> bool {
+        self.is_watchable() && self.set.is_allowed()
+    }
+    pub(crate) fn binding_impl(
+        &self,
+        source_property: &str,
+        obj_ident: &syn::Ident,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let bind = self.bind.as_ref()?;
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let name = self.name.to_string();
+        let property = bind
+            .property
+            .as_ref()
+            .map(|p| p.value())
+            .unwrap_or_else(|| name.clone());
+        let mut flags = vec![quote! { #glib::BindingFlags::SYNC_CREATE }];
+        if bind.bidirectional {
+            flags.push(quote! { #glib::BindingFlags::BIDIRECTIONAL });
+        }
+        if bind.invert_boolean {
+            flags.push(quote! { #glib::BindingFlags::INVERT_BOOLEAN });
+        }
+        let transform_to = bind
+            .transform_to
+            .as_ref()
+            .map(|t| quote_spanned! { t.span() => .transform_to(#t) });
+        let transform_from = bind
+            .transform_from
+            .as_ref()
+            .map(|t| quote_spanned! { t.span() => .transform_from(#t) });
+        let source_ident = syn::Ident::new("source", Span::mixed_site());
+        Some(quote_spanned! { bind.source.span() =>
+            #go::bind_property_from(#obj_ident, #source_property, |#obj_ident, #source_ident| {
+                #glib::object::ObjectExt::bind_property(
+                    #source_ident,
+                    #property,
+                    #obj_ident,
+                    #name,
+                )
+                .flags(#(#flags)|*)
+                #transform_to
+                #transform_from
+                .build()
+            });
+        })
+    }
     pub(crate) fn method_prototypes(
         &self,
         concurrency: Concurrency,
@@ -1304,6 +1954,7 @@ impl Property {
         let glib: syn::Path = parse_quote! { #go::glib };
         [
             self.setter_prototype(go),
+            self.try_setter_prototype(go),
             self.getter_prototype(go),
             self.borrow_prototype(go),
             self.notify_prototype(),
@@ -1311,6 +1962,10 @@ impl Property {
             (concurrency != Concurrency::None)
                 .then(|| self.connect_prototype(Concurrency::None, true, &glib))
                 .flatten(),
+            self.connect_scoped_prototype(concurrency, false, go),
+            (concurrency != Concurrency::None)
+                .then(|| self.connect_scoped_prototype(Concurrency::None, true, go))
+                .flatten(),
         ]
         .into_iter()
         .flatten()
@@ -1327,6 +1982,7 @@ impl Property {
         let glib: syn::Path = parse_quote! { #go::glib };
         [
             self.setter_definition(index, ty, properties_path, go),
+            self.try_setter_definition(index, ty, properties_path, go),
             self.getter_definition(ty, go),
             self.borrow_definition(ty, go),
             self.notify_definition(index, properties_path, &glib),
@@ -1334,6 +1990,10 @@ impl Property {
             (concurrency != Concurrency::None)
                 .then(|| self.connect_definition(Concurrency::None, true, &glib))
                 .flatten(),
+            self.connect_scoped_definition(concurrency, false, go),
+            (concurrency != Concurrency::None)
+                .then(|| self.connect_scoped_definition(Concurrency::None, true, go))
+                .flatten(),
         ]
         .into_iter()
         .flatten()
@@ -350,7 +350,7 @@ impl PublicMethod {
                     #cast_args
                     let #this_ident = #glib::Cast::#cast::<#wrapper_ty>(self);
                     #unwrap_recv
-                    #dest::#target(#this_ident, #(#args),*) #await_
+                    <#dest>::#target(#this_ident, #(#args),*) #await_
                 }
             })
         } else {
@@ -358,7 +358,7 @@ impl PublicMethod {
                 #sig {
                     #![inline]
                     #cast_args
-                    #dest::#target(#(#args),*) #await_
+                    <#dest>::#target(#(#args),*) #await_
                 }
             })
         }
@@ -1,8 +1,8 @@
 use crate::{
     util::{self, Errors},
-    Concurrency, TypeBase, TypeMode,
+    Concurrency, MockItem, TypeBase, TypeMode,
 };
-use darling::{util::Flag, FromAttributes};
+use darling::{util::Flag, FromAttributes, FromMeta};
 use heck::{ToShoutySnakeCase, ToSnakeCase};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
@@ -43,6 +43,21 @@ impl SignalFlags {
     }
 }
 
+#[derive(Debug)]
+struct SignalDetailedAttr(Option<syn::Type>);
+
+impl FromMeta for SignalDetailedAttr {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self(None))
+    }
+    fn from_value(lit: &syn::Lit) -> darling::Result<Self> {
+        match lit {
+            syn::Lit::Str(lit) => Ok(Self(Some(lit.parse()?))),
+            _ => Err(darling::Error::unexpected_lit_type(lit)),
+        }
+    }
+}
+
 #[derive(Default, FromAttributes)]
 #[darling(default, attributes(signal))]
 struct SignalAttrs {
@@ -50,7 +65,7 @@ struct SignalAttrs {
     run_last: Flag,
     run_cleanup: Flag,
     no_recurse: Flag,
-    detailed: Flag,
+    detailed: Option<SignalDetailedAttr>,
     action: Flag,
     no_hooks: Flag,
     must_collect: Flag,
@@ -59,6 +74,8 @@ struct SignalAttrs {
     override_: Flag,
     connect: Option<bool>,
     name: Option<syn::LitStr>,
+    forward_from: Option<syn::LitStr>,
+    signal: Option<syn::LitStr>,
 }
 
 impl SignalAttrs {
@@ -82,6 +99,245 @@ struct AccumulatorAttrs {
     signal: Option<syn::LitStr>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignalArgKind {
+    Value,
+    Str,
+    OptionStr,
+    Ref,
+    OptionRef,
+    Bytes,
+    Slice,
+    Vec,
+}
+
+/// How a signal argument is stored in a `glib::Value`. Borrowed types are copied into the
+/// emission's values, and handlers borrow them back from those values without another copy.
+pub(crate) struct SignalArg<'a> {
+    ty: &'a syn::Type,
+    kind: SignalArgKind,
+    inner: Option<&'a syn::Type>,
+}
+
+fn single_generic_arg<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(ty)) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_type_named(ty: &syn::Type, name: &str) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .map(|s| s.ident == name && s.arguments.is_empty())
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+impl<'a> SignalArg<'a> {
+    pub(crate) fn new(ty: &'a syn::Type) -> Self {
+        let (kind, inner) = match ty {
+            syn::Type::Reference(r) => match &*r.elem {
+                elem if is_type_named(elem, "str") => (SignalArgKind::Str, None),
+                syn::Type::Slice(s) if is_type_named(&s.elem, "u8") => (SignalArgKind::Bytes, None),
+                syn::Type::Slice(s) => (SignalArgKind::Slice, Some(&*s.elem)),
+                elem => (SignalArgKind::Ref, Some(elem)),
+            },
+            ty => {
+                if let Some(inner) = single_generic_arg(ty, "Option") {
+                    match inner {
+                        syn::Type::Reference(r) if is_type_named(&r.elem, "str") => {
+                            (SignalArgKind::OptionStr, None)
+                        }
+                        syn::Type::Reference(r) => (SignalArgKind::OptionRef, Some(&*r.elem)),
+                        _ => (SignalArgKind::Value, None),
+                    }
+                } else if let Some(inner) = single_generic_arg(ty, "Vec") {
+                    if is_type_named(inner, "String") || is_type_named(inner, "GString") {
+                        (SignalArgKind::Value, None)
+                    } else {
+                        (SignalArgKind::Vec, Some(inner))
+                    }
+                } else {
+                    (SignalArgKind::Value, None)
+                }
+            }
+        };
+        Self { ty, kind, inner }
+    }
+    /// The type registered for the argument on the signal.
+    pub(crate) fn static_type(&self, go: &syn::Path) -> TokenStream {
+        let glib = quote! { #go::glib };
+        let ty = self.ty;
+        let inner = self.inner;
+        let value_ty = match self.kind {
+            SignalArgKind::Value | SignalArgKind::Str | SignalArgKind::OptionStr => {
+                quote_spanned! { ty.span() => #ty }
+            }
+            SignalArgKind::Ref | SignalArgKind::OptionRef => quote_spanned! { ty.span() => #inner },
+            SignalArgKind::Bytes => quote! { #glib::Bytes },
+            SignalArgKind::Slice | SignalArgKind::Vec => {
+                quote_spanned! { ty.span() => #go::BoxedVec<#inner> }
+            }
+        };
+        quote_spanned! { ty.span() =>
+            <#value_ty as #glib::types::StaticType>::static_type()
+        }
+    }
+    /// The owned type the argument is read as, for checking that two arguments hold the same
+    /// values.
+    pub(crate) fn owned_type(&self, go: &syn::Path) -> TokenStream {
+        let ty = self.ty;
+        let inner = self.inner;
+        match self.kind {
+            SignalArgKind::Value => quote! { #ty },
+            SignalArgKind::Str => quote! { ::std::string::String },
+            SignalArgKind::OptionStr => {
+                quote! { ::std::option::Option<::std::string::String> }
+            }
+            SignalArgKind::Ref => quote! { #inner },
+            SignalArgKind::OptionRef => quote! { ::std::option::Option<#inner> },
+            SignalArgKind::Bytes => quote! { #go::glib::Bytes },
+            SignalArgKind::Slice | SignalArgKind::Vec => quote! { #go::BoxedVec<#inner> },
+        }
+    }
+    /// Reads the argument out of `value` into `arg_name`. With `owned`, the argument is fetched
+    /// as an owned value that can be moved into a future, and the second statement borrows it
+    /// back as the argument type.
+    pub(crate) fn unwrap(
+        &self,
+        arg_name: &syn::Ident,
+        value: &TokenStream,
+        index: usize,
+        owned: bool,
+        go: &syn::Path,
+    ) -> (TokenStream, Option<TokenStream>) {
+        let glib = quote! { #go::glib };
+        let ty = self.ty;
+        let inner = self.inner;
+        let error_ident = syn::Ident::new("e", Span::mixed_site());
+        let fail = quote_spanned! { ty.span() =>
+            |#error_ident| {
+                ::std::panic!(
+                    "Wrong type for argument {}: {:?}",
+                    #index,
+                    #error_ident
+                )
+            }
+        };
+        let get = |ty: TokenStream| {
+            quote_spanned! { self.ty.span() =>
+                let #arg_name = #value.get::<#ty>().unwrap_or_else(#fail);
+            }
+        };
+        match (self.kind, owned) {
+            (SignalArgKind::Value, _)
+            | (SignalArgKind::Str, false)
+            | (SignalArgKind::OptionStr, false) => (get(quote! { #ty }), None),
+            (SignalArgKind::Str, true) => (
+                get(quote! { ::std::string::String }),
+                Some(quote! { let #arg_name = #arg_name.as_str(); }),
+            ),
+            (SignalArgKind::OptionStr, true) => (
+                get(quote! { ::std::option::Option<::std::string::String> }),
+                Some(quote! { let #arg_name = #arg_name.as_deref(); }),
+            ),
+            (SignalArgKind::Ref, _) => (
+                get(quote! { #inner }),
+                Some(quote! { let #arg_name = &#arg_name; }),
+            ),
+            (SignalArgKind::OptionRef, _) => (
+                get(quote! { ::std::option::Option<#inner> }),
+                Some(quote! { let #arg_name = #arg_name.as_ref(); }),
+            ),
+            (SignalArgKind::Bytes, _) => (
+                get(quote! { #glib::Bytes }),
+                Some(quote! { let #arg_name = &*#arg_name; }),
+            ),
+            (SignalArgKind::Slice, false) => (
+                quote_spanned! { ty.span() =>
+                    let #arg_name = #go::BoxedVec::<#inner>::slice_from_value(&#value)
+                        .unwrap_or_else(#fail);
+                },
+                None,
+            ),
+            (SignalArgKind::Slice, true) => (
+                get(quote! { #go::BoxedVec<#inner> }),
+                Some(quote! { let #arg_name = #arg_name.as_slice(); }),
+            ),
+            (SignalArgKind::Vec, _) => (
+                quote_spanned! { ty.span() =>
+                    let #arg_name = #value.get::<#go::BoxedVec<#inner>>().unwrap_or_else(#fail).0;
+                },
+                None,
+            ),
+        }
+    }
+    /// Converts the argument `arg` to a `glib::Value` for emission.
+    pub(crate) fn to_value(&self, arg: &syn::Ident, go: &syn::Path) -> TokenStream {
+        let glib = quote! { #go::glib };
+        let inner = self.inner;
+        let v = syn::Ident::new("v", Span::mixed_site());
+        match self.kind {
+            SignalArgKind::Value | SignalArgKind::Str | SignalArgKind::OptionStr => {
+                quote_spanned! { arg.span() => #glib::ToValue::to_value(&#arg) }
+            }
+            SignalArgKind::Ref => quote_spanned! { arg.span() => #glib::ToValue::to_value(#arg) },
+            SignalArgKind::OptionRef => quote_spanned! { arg.span() =>
+                match #arg {
+                    ::std::option::Option::Some(#v) => #glib::ToValue::to_value(#v),
+                    ::std::option::Option::None => #glib::Value::from_type(
+                        <#inner as #glib::types::StaticType>::static_type()
+                    ),
+                }
+            },
+            SignalArgKind::Bytes => quote_spanned! { arg.span() =>
+                #glib::ToValue::to_value(&#glib::Bytes::from(#arg))
+            },
+            SignalArgKind::Slice => quote_spanned! { arg.span() =>
+                #go::BoxedVec::<#inner>::slice_to_value(#arg)
+            },
+            SignalArgKind::Vec => quote_spanned! { arg.span() =>
+                <#glib::Value as ::std::convert::From<#go::BoxedVec<#inner>>>::from(
+                    #go::BoxedVec(#arg)
+                )
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalForward {
+    pub source: syn::LitStr,
+    pub signal: Option<syn::LitStr>,
+}
+
+impl SignalForward {
+    /// Name of the field or property on `self` holding the source object.
+    pub fn source_name(&self) -> String {
+        let source = self.source.value();
+        source
+            .strip_prefix("self.")
+            .map(|s| s.to_owned())
+            .unwrap_or(source)
+    }
+}
+
 #[derive(Debug)]
 pub struct Signal {
     pub ident: syn::Ident,
@@ -92,6 +348,8 @@ pub struct Signal {
     pub sig: Option<syn::Signature>,
     pub handler: bool,
     pub accumulator: Option<syn::Signature>,
+    pub forward: Option<SignalForward>,
+    pub detail_type: Option<syn::Type>,
     pub mode: TypeMode,
 }
 
@@ -136,6 +394,9 @@ impl Signal {
                 if signal.override_ {
                     errors.push_spanned(acc, "Accumulator not allowed on overriden signal");
                 }
+                if signal.is_async() && acc.inputs.len() != 2 {
+                    errors.push_spanned(acc, "Accumulator on async signal must have 2 arguments");
+                }
             }
         }
     }
@@ -182,6 +443,7 @@ impl Signal {
             );
         }
         signal.flags = attr.flags();
+        signal.detail_type = attr.detailed.as_ref().and_then(|d| d.0.clone());
         signal.connect = attr.connect.unwrap_or(true);
         signal.override_ = attr.override_.is_some();
         signal.sig = Some(method.sig);
@@ -190,6 +452,35 @@ impl Signal {
             errors.push_spanned(&signal.ident, "`override` not allowed on interface signal");
             signal.override_ = false;
         }
+        if signal.is_async() && signal.override_ {
+            errors.push_spanned(&signal.ident, "`override` not allowed on async signal");
+            signal.override_ = false;
+        }
+        if let Some(source_signal) = &attr.signal {
+            if attr.forward_from.is_none() {
+                errors.push_spanned(source_signal, "`signal` requires `forward_from`");
+            }
+        }
+        if let Some(source) = &attr.forward_from {
+            if base == TypeBase::Interface {
+                errors.push_spanned(source, "`forward_from` not allowed on interface signal");
+            } else if signal.override_ {
+                errors.push_spanned(source, "`forward_from` not allowed on `override` signal");
+            } else {
+                if let Some(source_signal) = &attr.signal {
+                    let value = source_signal.value();
+                    let name = value.split("::").next().unwrap_or_default();
+                    if !util::is_valid_name(name) {
+                        errors
+                            .push_spanned(source_signal, format!("Invalid signal name '{}'", name));
+                    }
+                }
+                signal.forward = Some(SignalForward {
+                    source: source.clone(),
+                    signal: attr.signal.clone(),
+                });
+            }
+        }
     }
     #[inline]
     #[allow(clippy::ptr_arg)]
@@ -242,9 +533,65 @@ impl Signal {
             sig: None,
             handler: false,
             accumulator: None,
+            forward: None,
+            detail_type: None,
             mode,
         }
     }
+    pub fn is_async(&self) -> bool {
+        self.sig
+            .as_ref()
+            .map(|s| s.asyncness.is_some())
+            .unwrap_or(false)
+    }
+    fn output_type(&self) -> syn::Type {
+        match self.sig.as_ref().map(|s| &s.output) {
+            Some(syn::ReturnType::Type(_, ty)) => (**ty).clone(),
+            _ => parse_quote! { () },
+        }
+    }
+    fn placeholder_return(&self, glib: &syn::Path) -> TokenStream {
+        match self.sig.as_ref().map(|s| &s.output) {
+            Some(syn::ReturnType::Type(_, ty)) => quote_spanned! { ty.span() =>
+                ::std::option::Option::Some(#glib::ToValue::to_value(
+                    &<#ty as ::std::default::Default>::default()
+                ))
+            },
+            _ => quote! { ::std::option::Option::None },
+        }
+    }
+    /// The detail argument for emit and connect methods. Typed details are required on emit and
+    /// optional on connect, untyped details are always optional.
+    fn details_arg(
+        &self,
+        ident: &syn::Ident,
+        optional: bool,
+        glib: &syn::Path,
+    ) -> Option<TokenStream> {
+        if !self.flags.contains(SignalFlags::DETAILED) {
+            return None;
+        }
+        Some(match &self.detail_type {
+            Some(ty) if optional => quote! { #ident: ::std::option::Option<#ty>, },
+            Some(ty) => quote! { #ident: #ty, },
+            None => quote! { #ident: ::std::option::Option<#glib::Quark>, },
+        })
+    }
+    /// Converts the connect detail argument to an `Option<Quark>`.
+    fn details_quark(&self, ident: &syn::Ident, go: &syn::Path) -> TokenStream {
+        if !self.flags.contains(SignalFlags::DETAILED) {
+            return quote! { ::std::option::Option::None };
+        }
+        match &self.detail_type {
+            Some(ty) => quote_spanned! { ty.span() =>
+                ::std::option::Option::map(
+                    #ident.as_ref(),
+                    <#ty as #go::SignalDetail>::to_quark,
+                )
+            },
+            None => quote! { #ident },
+        }
+    }
     fn inputs(&self) -> impl Iterator<Item = &syn::FnArg> + Clone {
         self.sig
             .as_ref()
@@ -260,8 +607,9 @@ impl Signal {
         &'a self,
         args_ident: &'a syn::Ident,
         self_ty: &'a syn::Type,
-        glib: &'a syn::Path,
+        go: &'a syn::Path,
     ) -> impl Iterator<Item = TokenStream> + 'a {
+        let glib: syn::Path = parse_quote! { #go::glib };
         let recv = self.sig.as_ref().and_then(|s| s.receiver()).map(|recv| {
             let arg_name = syn::Ident::new("arg0", Span::mixed_site());
             let ty = match recv {
@@ -292,25 +640,64 @@ impl Signal {
             .skip(offset)
             .map(move |(index, input)| {
                 let ty = match input {
-                    syn::FnArg::Typed(t) => {
-                        let ty = &t.ty;
-                        quote_spanned! { ty.span() => #ty }
-                    }
+                    syn::FnArg::Typed(t) => &*t.ty,
                     syn::FnArg::Receiver(_) => unreachable!(),
                 };
                 let arg_name = format_ident!("arg{}", index, span = Span::mixed_site());
+                let value = quote! { #args_ident[#index] };
+                let (unwrap, borrow) =
+                    SignalArg::new(ty).unwrap(&arg_name, &value, index, false, go);
+                quote! {
+                    #unwrap
+                    #borrow
+                }
+            });
+        recv.into_iter().chain(rest)
+    }
+    fn async_args_unwrap(
+        &self,
+        args_ident: &syn::Ident,
+        self_ty: &syn::Type,
+        go: &syn::Path,
+    ) -> (Vec<TokenStream>, Vec<TokenStream>) {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let mut unwraps = Vec::new();
+        let mut borrows = Vec::new();
+        let has_recv = self.sig.as_ref().and_then(|s| s.receiver()).is_some();
+        for (index, input) in self.inputs().enumerate() {
+            let arg_name = format_ident!("arg{}", index, span = Span::mixed_site());
+            let value = quote! { #args_ident[#index] };
+            let ty = match input {
+                syn::FnArg::Receiver(_) => self_ty,
+                syn::FnArg::Typed(t) => &*t.ty,
+            };
+            if index == 0 && has_recv {
                 let error_ident = syn::Ident::new("e", Span::mixed_site());
-                quote_spanned! { input.span() =>
-                    let #arg_name = #args_ident[#index].get::<#ty>().unwrap_or_else(|#error_ident| {
+                unwraps.push(quote_spanned! { input.span() =>
+                    let #arg_name = #value.get::<#self_ty>().unwrap_or_else(|#error_ident| {
                         ::std::panic!(
                             "Wrong type for argument {}: {:?}",
                             #index,
                             #error_ident
                         )
                     });
+                });
+                if self.mode == TypeMode::Subclass {
+                    borrows.push(quote_spanned! { input.span() =>
+                        let #arg_name = #glib::subclass::prelude::ObjectSubclassIsExt::imp(&#arg_name);
+                    });
+                } else {
+                    borrows.push(quote! { let #arg_name = &#arg_name; });
                 }
-            });
-        recv.into_iter().chain(rest)
+                continue;
+            }
+            // the handler future outlives the signal arguments, so fetch owned values and
+            // borrow them inside the future instead
+            let (unwrap, borrow) = SignalArg::new(ty).unwrap(&arg_name, &value, index, true, go);
+            unwraps.push(unwrap);
+            borrows.extend(borrow);
+        }
+        (unwraps, borrows)
     }
     fn arg_types(&self) -> impl Iterator<Item = syn::PatType> + Clone + '_ {
         self.inputs().skip(1).enumerate().map(|(index, arg)| {
@@ -338,6 +725,20 @@ impl Signal {
             span = Span::mixed_site()
         )
     }
+    fn signal_id(&self, lookup: bool, glib: &syn::Path) -> TokenStream {
+        if lookup {
+            let name = &self.name;
+            quote! {
+                #glib::subclass::SignalId::lookup(
+                    #name,
+                    <Self as #glib::StaticType>::static_type(),
+                ).unwrap()
+            }
+        } else {
+            let ident = self.signal_id_cell_ident();
+            quote! { *#ident }
+        }
+    }
     pub(crate) fn signal_id_cell_definition(
         &self,
         wrapper_ty: &syn::Type,
@@ -365,11 +766,12 @@ impl Signal {
         &self,
         wrapper_ty: &syn::Type,
         sub_ty: &syn::Type,
-        glib: &syn::Path,
+        go: &syn::Path,
     ) -> Option<TokenStream> {
         if self.override_ {
             return None;
         }
+        let glib: &syn::Path = &parse_quote! { #go::glib };
 
         let Self {
             name,
@@ -386,9 +788,10 @@ impl Signal {
                 syn::FnArg::Typed(t) => &t.ty,
                 _ => unimplemented!(),
             };
+            let static_type = SignalArg::new(ty).static_type(go);
             quote_spanned! { ty.span() =>
                 <#glib::subclass::SignalType as ::core::convert::From<#glib::Type>>::from(
-                    <#ty as #glib::types::StaticType>::static_type()
+                    #static_type
                 )
             }
         });
@@ -402,10 +805,31 @@ impl Signal {
             let token_ident = syn::Ident::new("_token", Span::mixed_site());
             let args_ident = syn::Ident::new("args", Span::mixed_site());
             let ret_ident = syn::Ident::new("ret", Span::mixed_site());
-            let args_unwrap = self.args_unwrap(&args_ident, wrapper_ty, glib);
             let method_name = &sig.ident;
             let handler_name =
                 format_ident!("{}_class_handler", method_name, span = method_name.span());
+            if self.is_async() {
+                let (unwraps, borrows) = self.async_args_unwrap(&args_ident, wrapper_ty, go);
+                let object_ident = syn::Ident::new("object", Span::mixed_site());
+                let placeholder = self.placeholder_return(glib);
+                return quote_spanned! { sig.span() =>
+                    #[inline]
+                    fn #handler_name(
+                        #token_ident: &#glib::subclass::SignalClassHandlerToken,
+                        #args_ident: &[#glib::Value]
+                    ) -> ::std::option::Option<#glib::Value> {
+                        let #object_ident = #args_ident[0usize].get::<#glib::Object>().unwrap();
+                        #(#unwraps)*
+                        #go::add_signal_future(&#object_ident, async move {
+                            #(#borrows)*
+                            #dest::#method_name(#(#arg_names),*).await
+                        });
+                        #placeholder
+                    }
+                    let #builder = #builder.class_handler(#handler_name);
+                };
+            }
+            let args_unwrap = self.args_unwrap(&args_ident, wrapper_ty, go);
             quote_spanned! { sig.span() =>
                 #[inline]
                 fn #handler_name(
@@ -480,21 +904,39 @@ impl Signal {
             }
         })
     }
+    pub(crate) fn forward_impl(
+        &self,
+        source_property: &str,
+        obj_ident: &syn::Ident,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let forward = self.forward.as_ref()?;
+        let name = &self.name;
+        let source_signal = forward
+            .signal
+            .as_ref()
+            .map(|s| s.value())
+            .unwrap_or_else(|| name.clone());
+        Some(quote_spanned! { forward.source.span() =>
+            #go::forward_signal_from(#obj_ident, #source_property, #source_signal, #name);
+        })
+    }
     pub(crate) fn class_init_override(
         &self,
         wrapper_ty: &syn::Type,
         sub_ty: &syn::Type,
         class_ident: &syn::Ident,
-        glib: &syn::Path,
+        go: &syn::Path,
     ) -> Option<TokenStream> {
         if !self.override_ {
             return None;
         }
+        let glib: &syn::Path = &parse_quote! { #go::glib };
         let arg_names = self.arg_names();
         let token_ident = syn::Ident::new("_token", Span::mixed_site());
         let args_ident = syn::Ident::new("args", Span::mixed_site());
         let ret_ident = syn::Ident::new("ret", Span::mixed_site());
-        let args_unwrap = self.args_unwrap(&args_ident, wrapper_ty, glib);
+        let args_unwrap = self.args_unwrap(&args_ident, wrapper_ty, go);
         let dest = match self.mode {
             TypeMode::Subclass => sub_ty,
             TypeMode::Wrapper => wrapper_ty,
@@ -524,10 +966,77 @@ impl Signal {
             );
         }})
     }
-    pub(crate) fn chain_definition(&self, mode: TypeMode, glib: &syn::Path) -> Option<TokenStream> {
+    /// Whether a mock subclass can replace the class handler of this signal.
+    pub(crate) fn is_mockable(&self) -> bool {
+        self.handler && !self.override_ && !self.is_async() && self.sig.is_some()
+    }
+    pub(crate) fn mock_item(&self) -> Option<MockItem> {
+        if !self.is_mockable() {
+            return None;
+        }
+        let sig = self.sig.as_ref()?;
+        let arg_types = self.arg_types().collect::<Vec<_>>();
+        Some(MockItem {
+            ident: sig.ident.clone(),
+            name: self.name.clone(),
+            owned: arg_types
+                .iter()
+                .map(|arg| match &*arg.pat {
+                    syn::Pat::Ident(syn::PatIdent { ident, .. }) => {
+                        util::mock_arg(ident, &arg.ty).0
+                    }
+                    _ => unimplemented!(),
+                })
+                .collect(),
+            inputs: arg_types.into_iter().map(|arg| *arg.ty).collect(),
+            output: sig.output.clone(),
+        })
+    }
+    pub(crate) fn mock_definition(&self, go: &syn::Path) -> Option<TokenStream> {
+        if !self.is_mockable() {
+            return None;
+        }
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let sig = self.sig.as_ref()?;
+        let ident = &sig.ident;
+        let output = &sig.output;
+        let name = &self.name;
+        let parent_ident = format_ident!(
+            "parent_{}",
+            self.name.to_snake_case(),
+            span = sig.ident.span()
+        );
+        let arg_types = self.arg_types().collect::<Vec<_>>();
+        let arg_names = arg_types
+            .iter()
+            .map(|arg| match &*arg.pat {
+                syn::Pat::Ident(syn::PatIdent { ident, .. }) => ident.clone(),
+                _ => unimplemented!(),
+            })
+            .collect::<Vec<_>>();
+        let copies = arg_types
+            .iter()
+            .zip(arg_names.iter())
+            .map(|(arg, name)| util::mock_arg(name, &arg.ty).1);
+        let func_ident = syn::Ident::new("____func", Span::mixed_site());
+        Some(quote_spanned! { sig.span() =>
+            #[signal(name = #name, override)]
+            fn #ident(&self, #(#arg_types),*) #output {
+                match self.#ident.record((#(#copies,)*)) {
+                    ::std::option::Option::Some(#func_ident) => #func_ident(
+                        &#glib::subclass::types::ObjectSubclassExt::instance(self),
+                        #(#arg_names),*
+                    ),
+                    ::std::option::Option::None => self.#parent_ident(#(#arg_names),*),
+                }
+            }
+        })
+    }
+    pub(crate) fn chain_definition(&self, mode: TypeMode, go: &syn::Path) -> Option<TokenStream> {
         if !self.override_ {
             return None;
         }
+        let glib: &syn::Path = &parse_quote! { #go::glib };
         if mode != self.mode {
             return None;
         }
@@ -551,11 +1060,10 @@ impl Signal {
             },
             TypeMode::Wrapper => quote! { #self_ident },
         };
-        let arg_values = arg_names.map(|arg| {
-            quote_spanned! { arg.span() =>
-                #glib::ToValue::to_value(&#arg)
-            }
-        });
+        let arg_values = arg_types
+            .clone()
+            .zip(arg_names)
+            .map(|(arg, name)| SignalArg::new(&arg.ty).to_value(&name, go));
         let result_ident = syn::Ident::new("result", Span::mixed_site());
         let values_ident = syn::Ident::new("values", Span::mixed_site());
         let declare_result = match output {
@@ -620,51 +1128,87 @@ impl Signal {
         let arg_types = self.arg_types();
         let self_ident = syn::Ident::new("self", Span::mixed_site());
         let details_ident = syn::Ident::new("signal_details", Span::mixed_site());
-        let details_arg = self
-            .flags
-            .contains(SignalFlags::DETAILED)
-            .then(|| quote! { #details_ident: ::std::option::Option<#glib::Quark>, });
+        let details_arg = self.details_arg(&details_ident, false, glib);
         Some(quote_spanned! { sig.span() =>
             fn #method_name(&#self_ident, #details_arg #(#arg_types),*) #output
         })
     }
-    fn emit_definition(&self, glib: &syn::Path) -> Option<TokenStream> {
-        let proto = self.emit_prototype(glib)?;
-        let sig = self.sig.as_ref()?;
-        let arg_types = self.arg_types();
-        let arg_names = arg_types.clone().map(|arg| match &*arg.pat {
-            syn::Pat::Ident(syn::PatIdent { ident, .. }) => ident.clone(),
-            _ => unimplemented!(),
-        });
+    fn emit_call(&self, signal_id: &TokenStream, go: &syn::Path) -> TokenStream {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let output = self.output_type();
         let self_ident = syn::Ident::new("self", Span::mixed_site());
         let details_ident = syn::Ident::new("signal_details", Span::mixed_site());
-        let signal_id_cell = self.signal_id_cell_ident();
-        let emit = {
-            let arg_names = arg_names.clone();
-            quote! {
-                <Self as #glib::object::ObjectExt>::emit(
+        let values_ident = syn::Ident::new("values", Span::mixed_site());
+        let ret_ident = syn::Ident::new("ret", Span::mixed_site());
+        let error_ident = syn::Ident::new("e", Span::mixed_site());
+        let values = self
+            .arg_types()
+            .map(|arg| match &*arg.pat {
+                syn::Pat::Ident(syn::PatIdent { ident, .. }) => {
+                    SignalArg::new(&arg.ty).to_value(ident, go)
+                }
+                _ => unimplemented!(),
+            })
+            .collect::<Vec<_>>();
+        let count = values.len();
+        let emit = if let Some(ty) = &self.detail_type {
+            quote_spanned! { ty.span() =>
+                <Self as #glib::object::ObjectExt>::emit_with_details_and_values(
                     #self_ident,
-                    *#signal_id_cell,
-                    &[#(&#arg_names),*]
+                    #signal_id,
+                    <#ty as #go::SignalDetail>::to_quark(&#details_ident),
+                    &#values_ident,
                 )
             }
-        };
-        let body = if self.flags.contains(SignalFlags::DETAILED) {
+        } else if self.flags.contains(SignalFlags::DETAILED) {
             quote! {
                 if let Some(#details_ident) = #details_ident {
-                    <Self as #glib::object::ObjectExt>::emit_with_details(
+                    <Self as #glib::object::ObjectExt>::emit_with_details_and_values(
                         #self_ident,
-                        *#signal_id_cell,
+                        #signal_id,
                         #details_ident,
-                        &[#(&#arg_names),*]
+                        &#values_ident,
                     )
                 } else {
-                    #emit
+                    <Self as #glib::object::ObjectExt>::emit_with_values(
+                        #self_ident,
+                        #signal_id,
+                        &#values_ident,
+                    )
                 }
             }
         } else {
-            emit
+            quote! {
+                <Self as #glib::object::ObjectExt>::emit_with_values(
+                    #self_ident,
+                    #signal_id,
+                    &#values_ident,
+                )
+            }
         };
+        let name = &self.name;
+        quote! {
+            {
+                let #values_ident: [#glib::Value; #count] = [#(#values),*];
+                let #ret_ident = #emit;
+                <#output as #glib::closure::TryFromClosureReturnValue>::try_from_closure_return_value(
+                    #ret_ident,
+                ).unwrap_or_else(|#error_ident| {
+                    ::std::panic!(
+                        "Invalid return value from signal `{}`: {}",
+                        #name,
+                        #error_ident,
+                    )
+                })
+            }
+        }
+    }
+    fn emit_definition(&self, lookup: bool, go: &syn::Path) -> Option<TokenStream> {
+        let glib: &syn::Path = &parse_quote! { #go::glib };
+        let proto = self.emit_prototype(glib)?;
+        let sig = self.sig.as_ref()?;
+        let signal_id = self.signal_id(lookup, glib);
+        let body = self.emit_call(&signal_id, go);
         Some(quote_spanned! { sig.span() =>
             #proto {
                 #![inline]
@@ -672,6 +1216,98 @@ impl Signal {
             }
         })
     }
+    fn emit_future_prototype(&self, glib: &syn::Path) -> Option<TokenStream> {
+        if self.override_ || !self.is_async() {
+            return None;
+        }
+        let sig = self.sig.as_ref()?;
+        let output = self.output_type();
+        let method_name = format_ident!(
+            "emit_{}_future",
+            self.name.to_snake_case(),
+            span = sig.ident.span()
+        );
+        let arg_types = self.arg_types();
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let details_ident = syn::Ident::new("signal_details", Span::mixed_site());
+        let details_arg = self.details_arg(&details_ident, false, glib);
+        Some(quote_spanned! { sig.span() =>
+            fn #method_name(&#self_ident, #details_arg #(#arg_types),*) -> ::std::pin::Pin<
+                ::std::boxed::Box<dyn ::std::future::Future<Output = #output> + 'static>
+            >
+        })
+    }
+    fn emit_future_definition(
+        &self,
+        lookup: bool,
+        wrapper_ty: &syn::Type,
+        sub_ty: &syn::Type,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let glib: &syn::Path = &parse_quote! { #go::glib };
+        let proto = self.emit_future_prototype(glib)?;
+        let sig = self.sig.as_ref()?;
+        let output = self.output_type();
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let signal_id_ident = syn::Ident::new("signal_id", Span::mixed_site());
+        let futures_ident = syn::Ident::new("futures", Span::mixed_site());
+        let future_ident = syn::Ident::new("future", Span::mixed_site());
+        let accu = syn::Ident::new("accu", Span::mixed_site());
+        let signal_id = self.signal_id(lookup, glib);
+        let emit = self.emit_call(&quote! { #signal_id_ident }, go);
+        let accumulate = match &self.accumulator {
+            Some(acc) => {
+                let dest = match self.mode {
+                    TypeMode::Subclass => sub_ty,
+                    TypeMode::Wrapper => wrapper_ty,
+                };
+                let method_name = &acc.ident;
+                let cur_accu = syn::Ident::new("cur_accu", Span::mixed_site());
+                let value = syn::Ident::new("value", Span::mixed_site());
+                let next = syn::Ident::new("next", Span::mixed_site());
+                let cont = syn::Ident::new("cont", Span::mixed_site());
+                quote_spanned! { acc.span() =>
+                    let mut #accu = #glib::ToValue::to_value(&#accu);
+                    for #future_ident in #futures_ident {
+                        let #value = #glib::ToValue::to_value(&#future_ident.await);
+                        let #cur_accu = #accu.get().unwrap();
+                        let #value = #value.get().unwrap();
+                        let (#next, #cont) = match #dest::#method_name(#cur_accu, #value) {
+                            ::std::ops::ControlFlow::Continue(#next) => (#next, true),
+                            ::std::ops::ControlFlow::Break(#next) => (#next, false),
+                        };
+                        if let ::std::option::Option::Some(#next) = #next {
+                            #accu = #glib::ToValue::to_value(&#next);
+                        }
+                        if !#cont {
+                            break;
+                        }
+                    }
+                    #accu.get::<#output>().unwrap()
+                }
+            }
+            None => quote! {
+                let mut #accu = #accu;
+                for #future_ident in #futures_ident {
+                    #accu = #future_ident.await;
+                }
+                #accu
+            },
+        };
+        Some(quote_spanned! { sig.span() =>
+            #proto {
+                let #signal_id_ident = #signal_id;
+                let (#futures_ident, #accu) = #go::collect_signal_futures::<_, #output, _>(
+                    #self_ident,
+                    #signal_id_ident,
+                    || #emit,
+                );
+                ::std::boxed::Box::pin(async move {
+                    #accumulate
+                })
+            }
+        })
+    }
     fn connect_prototype(
         &self,
         concurrency: Concurrency,
@@ -702,10 +1338,21 @@ impl Signal {
         });
         let self_ident = syn::Ident::new("self", Span::mixed_site());
         let func_ident = syn::Ident::new("func", Span::mixed_site());
-        let details_arg = self
-            .flags
-            .contains(SignalFlags::DETAILED)
-            .then(|| quote_spanned! { Span::mixed_site() => details: ::std::option::Option<#glib::Quark>, });
+        let details_ident = syn::Ident::new("details", Span::mixed_site());
+        let details_arg = self.details_arg(&details_ident, true, glib);
+        if self.is_async() {
+            let output = self.output_type();
+            return Some(quote_spanned! { sig.span() =>
+                fn #method_name<
+                    ____Func: Fn(&Self, #(#input_types),*) -> ____Fut #concurrency + 'static,
+                    ____Fut: ::std::future::Future<Output = #output> + 'static,
+                >(
+                    &#self_ident,
+                    #details_arg
+                    #func_ident: ____Func,
+                ) -> #glib::SignalHandlerId
+            });
+        }
         Some(quote_spanned! { sig.span() =>
             fn #method_name<____Func: Fn(&Self, #(#input_types),*) #output #concurrency + 'static>(
                 &#self_ident,
@@ -714,12 +1361,99 @@ impl Signal {
             ) -> #glib::SignalHandlerId
         })
     }
+    fn connect_scoped_prototype(
+        &self,
+        concurrency: Concurrency,
+        local: bool,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let glib: &syn::Path = &parse_quote! { #go::glib };
+        self.connect_prototype(concurrency, local, glib)?;
+        let sig = self.sig.as_ref()?;
+        let method_name = if local {
+            format_ident!(
+                "connect_{}_local_scoped",
+                self.name.to_snake_case(),
+                span = sig.ident.span()
+            )
+        } else {
+            format_ident!(
+                "connect_{}_scoped",
+                self.name.to_snake_case(),
+                span = sig.ident.span()
+            )
+        };
+        let input_types = self.inputs().skip(1).map(|arg| match arg {
+            syn::FnArg::Typed(t) => &t.ty,
+            _ => unimplemented!(),
+        });
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let details_ident = syn::Ident::new("details", Span::mixed_site());
+        let details_arg = self.details_arg(&details_ident, true, glib);
+        let generics = if self.is_async() {
+            let output = self.output_type();
+            quote! {
+                ____Func: Fn(&Self, #(#input_types),*) -> ____Fut #concurrency + 'static,
+                ____Fut: ::std::future::Future<Output = #output> + 'static,
+            }
+        } else {
+            let output = &sig.output;
+            quote! { ____Func: Fn(&Self, #(#input_types),*) #output #concurrency + 'static }
+        };
+        Some(quote_spanned! { sig.span() =>
+            fn #method_name<#generics>(
+                &#self_ident,
+                #details_arg
+                #func_ident: ____Func,
+            ) -> #go::SignalGuard<Self>
+        })
+    }
+    fn connect_scoped_definition(
+        &self,
+        concurrency: Concurrency,
+        local: bool,
+        go: &syn::Path,
+    ) -> Option<TokenStream> {
+        let proto = self.connect_scoped_prototype(concurrency, local, go)?;
+        let sig = self.sig.as_ref()?;
+        let connect = if local {
+            format_ident!(
+                "connect_{}_local",
+                self.name.to_snake_case(),
+                span = sig.ident.span()
+            )
+        } else {
+            format_ident!(
+                "connect_{}",
+                self.name.to_snake_case(),
+                span = sig.ident.span()
+            )
+        };
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let func_ident = syn::Ident::new("func", Span::mixed_site());
+        let details = self
+            .flags
+            .contains(SignalFlags::DETAILED)
+            .then(|| quote_spanned! { Span::mixed_site() => details, });
+        Some(quote_spanned! { sig.span() =>
+            #proto {
+                #![inline]
+                #go::SignalGuard::new(
+                    #self_ident,
+                    Self::#connect(#self_ident, #details #func_ident),
+                )
+            }
+        })
+    }
     fn connect_definition(
         &self,
         concurrency: Concurrency,
         local: bool,
-        glib: &syn::Path,
+        lookup: bool,
+        go: &syn::Path,
     ) -> Option<TokenStream> {
+        let glib: &syn::Path = &parse_quote! { #go::glib };
         let proto = self.connect_prototype(concurrency, local, glib)?;
         let sig = self.sig.as_ref()?;
         let arg_names = self.arg_names().skip(1);
@@ -730,32 +1464,37 @@ impl Signal {
         let args_ident = syn::Ident::new("args", Span::mixed_site());
         let recv_ident = syn::Ident::new("recv", Span::mixed_site());
         let ret_ident = syn::Ident::new("_ret", Span::mixed_site());
-        let args_unwrap = self.args_unwrap(&args_ident, &self_ty, glib).skip(1);
+        let args_unwrap = self.args_unwrap(&args_ident, &self_ty, go).skip(1);
 
-        let signal_id_cell = self.signal_id_cell_ident();
-        let details = if self.flags.contains(SignalFlags::DETAILED) {
-            quote_spanned! { Span::mixed_site() => details }
-        } else {
-            quote! { ::std::option::Option::None }
-        };
+        let signal_id = self.signal_id(lookup, glib);
+        let details_ident = syn::Ident::new("details", Span::mixed_site());
+        let details = self.details_quark(&details_ident, go);
         let call = if concurrency == Concurrency::None {
             format_ident!("connect_local_id")
         } else {
             format_ident!("connect_id")
         };
 
-        let unwrap = match &sig.output {
-            syn::ReturnType::Type(_, _) => quote! {
-                #glib::closure::ToClosureReturnValue::to_closure_return_value(&#ret_ident)
-            },
-            _ => quote! { ::core::option::Option::None },
+        let unwrap = if self.is_async() {
+            let placeholder = self.placeholder_return(glib);
+            quote! {
+                #go::add_signal_future(&#recv_ident, #ret_ident);
+                #placeholder
+            }
+        } else {
+            match &sig.output {
+                syn::ReturnType::Type(_, _) => quote! {
+                    #glib::closure::ToClosureReturnValue::to_closure_return_value(&#ret_ident)
+                },
+                _ => quote! { ::core::option::Option::None },
+            }
         };
         Some(quote_spanned! { sig.span() =>
             #proto {
                 #![inline]
                 <Self as #glib::object::ObjectExt>::#call(
                     #self_ident,
-                    *#signal_id_cell,
+                    #signal_id,
                     #details,
                     false,
                     move |#args_ident| {
@@ -771,14 +1510,20 @@ impl Signal {
     pub(crate) fn method_prototypes(
         &self,
         concurrency: Concurrency,
-        glib: &syn::Path,
+        go: &syn::Path,
     ) -> Vec<TokenStream> {
+        let glib: &syn::Path = &parse_quote! { #go::glib };
         [
             self.emit_prototype(glib),
+            self.emit_future_prototype(glib),
             self.connect_prototype(concurrency, false, glib),
             (concurrency != Concurrency::None)
                 .then(|| self.connect_prototype(Concurrency::None, true, glib))
                 .flatten(),
+            self.connect_scoped_prototype(concurrency, false, go),
+            (concurrency != Concurrency::None)
+                .then(|| self.connect_scoped_prototype(Concurrency::None, true, go))
+                .flatten(),
         ]
         .into_iter()
         .flatten()
@@ -787,13 +1532,21 @@ impl Signal {
     pub(crate) fn method_definitions(
         &self,
         concurrency: Concurrency,
-        glib: &syn::Path,
+        lookup: bool,
+        wrapper_ty: &syn::Type,
+        sub_ty: &syn::Type,
+        go: &syn::Path,
     ) -> Vec<TokenStream> {
         [
-            self.emit_definition(glib),
-            self.connect_definition(concurrency, false, glib),
+            self.emit_definition(lookup, go),
+            self.emit_future_definition(lookup, wrapper_ty, sub_ty, go),
+            self.connect_definition(concurrency, false, lookup, go),
+            (concurrency != Concurrency::None)
+                .then(|| self.connect_definition(Concurrency::None, true, lookup, go))
+                .flatten(),
+            self.connect_scoped_definition(concurrency, false, go),
             (concurrency != Concurrency::None)
-                .then(|| self.connect_definition(Concurrency::None, true, glib))
+                .then(|| self.connect_scoped_definition(Concurrency::None, true, go))
                 .flatten(),
         ]
         .into_iter()
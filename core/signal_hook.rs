@@ -0,0 +1,288 @@
+use crate::{
+    util::{self, Errors},
+    Signal, SignalArg, SignalFlags, TypeBase, TypeMode,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse::ParseStream, parse_quote, spanned::Spanned};
+
+#[derive(Debug)]
+pub struct SignalHook {
+    pub ident: syn::Ident,
+    pub vis: syn::Visibility,
+    pub signal: syn::LitStr,
+    pub type_: Option<syn::Path>,
+    pub hint: bool,
+    pub inputs: Vec<syn::Type>,
+    pub returns_bool: bool,
+    pub mode: TypeMode,
+    pub signal_inputs: Vec<syn::Type>,
+}
+
+fn parse_hook_attr(input: ParseStream) -> syn::Result<(syn::LitStr, Option<syn::Path>)> {
+    let signal = input.parse::<syn::LitStr>()?;
+    let mut type_ = None;
+    while !input.is_empty() {
+        input.parse::<syn::Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        let ident = input.parse::<syn::Token![type]>()?;
+        if type_.is_some() {
+            return Err(syn::Error::new(ident.span, "Duplicate `type`"));
+        }
+        input.parse::<syn::Token![=]>()?;
+        type_ = Some(input.parse::<syn::LitStr>()?.parse::<syn::Path>()?);
+    }
+    Ok((signal, type_))
+}
+
+fn is_hint_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(r) => match &*r.elem {
+            syn::Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident == "SignalInvocationHint")
+                .unwrap_or(false),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl SignalHook {
+    pub(crate) fn many_from_items(
+        items: &mut [syn::ImplItem],
+        base: TypeBase,
+        mode: TypeMode,
+        hooks: &mut Vec<Self>,
+        errors: &Errors,
+    ) {
+        for item in items {
+            if let syn::ImplItem::Method(method) = item {
+                if let Some(attrs) = util::extract_attrs(&mut method.attrs, "signal_hook") {
+                    if base == TypeBase::Interface {
+                        errors.push_spanned(&attrs[0], "Signal hooks not allowed on interfaces");
+                        continue;
+                    }
+                    for attr in &attrs[1..] {
+                        errors.push_spanned(attr, "Duplicate `signal_hook` attribute");
+                    }
+                    match attrs[0].parse_args_with(parse_hook_attr) {
+                        Ok((signal, type_)) => {
+                            if let Some(hook) =
+                                Self::from_method(method, mode, signal, type_, errors)
+                            {
+                                hooks.push(hook);
+                            }
+                        }
+                        Err(e) => errors.push_syn(e),
+                    }
+                }
+            }
+        }
+    }
+    fn from_method(
+        method: &syn::ImplItemMethod,
+        mode: TypeMode,
+        signal: syn::LitStr,
+        type_: Option<syn::Path>,
+        errors: &Errors,
+    ) -> Option<Self> {
+        let sig = &method.sig;
+        if let Some(recv) = sig.receiver() {
+            errors.push_spanned(
+                recv,
+                "Receiver argument not allowed on signal hook, hooks are called for all instances",
+            );
+            return None;
+        }
+        if let Some(async_) = &sig.asyncness {
+            errors.push_spanned(async_, "Signal hook cannot be async");
+        }
+        let mut inputs = sig
+            .inputs
+            .iter()
+            .map(|arg| match arg {
+                syn::FnArg::Typed(t) => (*t.ty).clone(),
+                syn::FnArg::Receiver(_) => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        let hint = inputs.first().map(is_hint_type).unwrap_or(false);
+        if hint {
+            inputs.remove(0);
+        }
+        if inputs.is_empty() {
+            errors.push_spanned(
+                &sig.inputs,
+                "Signal hook must have the emitting object as an argument",
+            );
+        }
+        let returns_bool = match &sig.output {
+            syn::ReturnType::Default => false,
+            syn::ReturnType::Type(_, ty) => {
+                let is_bool = matches!(&**ty, syn::Type::Path(p) if p.path.is_ident("bool"));
+                if !is_bool {
+                    errors.push_spanned(ty, "Signal hook must return `()` or `bool`");
+                }
+                true
+            }
+        };
+        let name = signal.value();
+        let signal_name = name.split("::").next().unwrap_or_default();
+        if !util::is_valid_name(signal_name) {
+            errors.push_spanned(&signal, format!("Invalid signal name '{}'", signal_name));
+        }
+        Some(Self {
+            ident: sig.ident.clone(),
+            vis: method.vis.clone(),
+            signal,
+            type_,
+            hint,
+            inputs,
+            returns_bool,
+            mode,
+            signal_inputs: Vec::new(),
+        })
+    }
+    pub(crate) fn validate_many(hooks: &mut [Self], signals: &[Signal], errors: &Errors) {
+        for hook in hooks {
+            if hook.type_.is_some() {
+                continue;
+            }
+            let name = hook.signal.value();
+            let (signal_name, detail) = match name.split_once("::") {
+                Some((name, detail)) => (name, Some(detail)),
+                None => (name.as_str(), None),
+            };
+            let signal = match signals
+                .iter()
+                .find(|s| s.name == signal_name && !s.override_)
+            {
+                Some(signal) => signal,
+                None => {
+                    errors.push_spanned(
+                        &hook.signal,
+                        format!(
+                            "Signal `{}` not defined on this class, use `type` to hook a signal of another type",
+                            signal_name
+                        ),
+                    );
+                    continue;
+                }
+            };
+            if detail.is_some() && !signal.flags.contains(SignalFlags::DETAILED) {
+                errors.push_spanned(
+                    &hook.signal,
+                    format!("Signal `{}` is not detailed", signal_name),
+                );
+            }
+            let inputs = signal
+                .sig
+                .iter()
+                .flat_map(|s| s.inputs.iter())
+                .map(|arg| match arg {
+                    syn::FnArg::Typed(t) => (*t.ty).clone(),
+                    syn::FnArg::Receiver(_) => parse_quote! { Self },
+                })
+                .collect::<Vec<_>>();
+            if hook.inputs.len() != inputs.len() {
+                errors.push_spanned(
+                    &hook.ident,
+                    format!(
+                        "Signal hook for `{}` must take {} arguments after the invocation hint",
+                        signal_name,
+                        inputs.len()
+                    ),
+                );
+                continue;
+            }
+            hook.signal_inputs = inputs;
+        }
+    }
+    fn handle_ident(&self) -> syn::Ident {
+        format_ident!("{}_hook", self.ident)
+    }
+    pub(crate) fn install(
+        &self,
+        wrapper_ty: &syn::Type,
+        sub_ty: &syn::Type,
+        go: &syn::Path,
+    ) -> TokenStream {
+        let glib = quote! { #go::glib };
+        let ident = &self.ident;
+        let handle = self.handle_ident();
+        let signal = &self.signal;
+        let hint_ident = syn::Ident::new("hint", Span::mixed_site());
+        let args_ident = syn::Ident::new("args", Span::mixed_site());
+        let (type_, ref_class) = match &self.type_ {
+            Some(path) => (quote! { #path }, true),
+            None => (quote! { #wrapper_ty }, false),
+        };
+        let arg_names = (0..self.inputs.len())
+            .map(|i| format_ident!("arg{}", i, span = Span::mixed_site()))
+            .collect::<Vec<_>>();
+        let unwraps = self.inputs.iter().enumerate().map(|(index, ty)| {
+            let value = quote! { #args_ident[#index] };
+            let (unwrap, borrow) =
+                SignalArg::new(ty).unwrap(&arg_names[index], &value, index, false, go);
+            quote! {
+                #unwrap
+                #borrow
+            }
+        });
+        // arguments after the emitting object must be read as the types the signal was declared
+        // with
+        let checks = self
+            .inputs
+            .iter()
+            .zip(&self.signal_inputs)
+            .skip(1)
+            .map(|(hook_ty, signal_ty)| {
+                let span = hook_ty.span();
+                let hook_ty = SignalArg::new(hook_ty).owned_type(go);
+                let signal_ty = SignalArg::new(signal_ty).owned_type(go);
+                quote_spanned! { span =>
+                    let _: fn(::std::marker::PhantomData<#signal_ty>) -> ::std::marker::PhantomData<#hook_ty> =
+                        |ty| ty;
+                }
+            });
+        let self_ty = match self.mode {
+            TypeMode::Subclass => sub_ty,
+            TypeMode::Wrapper => wrapper_ty,
+        };
+        let hint_arg = self.hint.then(|| quote! { #hint_ident, });
+        let call = quote! { #self_ty::#ident(#hint_arg #(#arg_names),*) };
+        let call = if self.returns_bool {
+            call
+        } else {
+            quote! { #call; true }
+        };
+        quote_spanned! { ident.span() =>
+            #(#checks)*
+            #wrapper_ty::#handle().install(
+                <#type_ as #glib::StaticType>::static_type(),
+                #signal,
+                #ref_class,
+                |#hint_ident, #args_ident| {
+                    let _ = #hint_ident;
+                    #(#unwraps)*
+                    #call
+                },
+            );
+        }
+    }
+    pub(crate) fn handle_definition(&self, go: &syn::Path) -> TokenStream {
+        let vis = &self.vis;
+        let handle = self.handle_ident();
+        let hook_ident = syn::Ident::new("HOOK", Span::mixed_site());
+        quote_spanned! { self.ident.span() =>
+            #vis fn #handle() -> &'static #go::EmissionHook {
+                static #hook_ident: #go::EmissionHook = #go::EmissionHook::new();
+                &#hook_ident
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     property::{Properties, Property},
     public_method::PublicMethod,
     signal::Signal,
+    signal_hook::SignalHook,
     util::{self, Errors},
     virtual_method::VirtualMethod,
 };
@@ -24,10 +25,15 @@ pub struct TypeDefinition {
     pub name: syn::Ident,
     pub crate_path: syn::Path,
     pub generics: syn::Generics,
+    pub dynamic: bool,
     pub properties_item_index: Option<usize>,
     pub methods_item_indices: BTreeSet<usize>,
     pub properties: Vec<Property>,
+    pub settings_schema: Option<syn::LitStr>,
+    pub history: Option<Span>,
+    pub transactions: Option<Span>,
     pub signals: Vec<Signal>,
+    pub signal_hooks: Vec<SignalHook>,
     pub public_methods: Vec<PublicMethod>,
     pub virtual_methods: Vec<VirtualMethod>,
     custom_stmts: RefCell<HashMap<String, Vec<syn::Stmt>>>,
@@ -122,10 +128,15 @@ impl TypeDefinition {
             name: default_name,
             crate_path,
             generics: Default::default(),
+            dynamic: false,
             properties_item_index: None,
             methods_item_indices: BTreeSet::new(),
             properties: Vec::new(),
+            settings_schema: None,
+            history: None,
+            transactions: None,
             signals: Vec::new(),
+            signal_hooks: Vec::new(),
             public_methods: Vec::new(),
             virtual_methods: Vec::new(),
             custom_stmts: RefCell::new(HashMap::new()),
@@ -273,6 +284,9 @@ impl TypeDefinition {
             let Properties {
                 properties,
                 mut fields,
+                settings_schema,
+                history,
+                transactions,
                 ..
             } = Properties::from_derive_input(&input, Some(base), errors);
             if base == TypeBase::Interface {
@@ -295,6 +309,9 @@ impl TypeDefinition {
             }
             struct_.fields = fields;
             def.properties.extend(properties);
+            def.settings_schema = settings_schema;
+            def.history = history;
+            def.transactions = transactions;
         } else {
             def.vis = def.module.vis.clone();
             match &def.vis {
@@ -321,6 +338,13 @@ impl TypeDefinition {
                 generics = Some(impl_.generics.clone());
             }
             Signal::many_from_items(&mut impl_.items, base, mode, &mut def.signals, errors);
+            SignalHook::many_from_items(
+                &mut impl_.items,
+                base,
+                mode,
+                &mut def.signal_hooks,
+                errors,
+            );
             def.public_methods.extend(PublicMethod::many_from_items(
                 &mut impl_.items,
                 base,
@@ -335,15 +359,39 @@ impl TypeDefinition {
             ));
         }
         Signal::validate_many(&def.signals, errors);
+        SignalHook::validate_many(&mut def.signal_hooks, &def.signals, errors);
         if let Some(name) = name {
             def.name = name;
         }
         if let Some(generics) = generics {
             def.generics = generics;
         }
+        if !def.generics.params.is_empty() {
+            for hook in &def.signal_hooks {
+                errors.push_spanned(&hook.ident, "Signal hooks not allowed on generic types");
+            }
+        }
         def.methods_item_indices = impls.into_iter().collect();
         def
     }
+    pub fn is_generic(&self) -> bool {
+        !self.generics.params.is_empty()
+    }
+    /// Whether statics in generated code have to be stored per type instead of in a `static`,
+    /// for generic classes or classes that are reinitialized when reloaded.
+    pub(crate) fn has_type_statics(&self) -> bool {
+        self.is_generic() || self.dynamic
+    }
+    fn type_static(&self, ty: TokenStream, init: TokenStream) -> TokenStream {
+        let go = &self.crate_path;
+        if self.dynamic {
+            quote! {
+                #go::DynamicClassStatic::<#ty>::leak(#go::dynamic_class_static::<Self, #ty>(#init))
+            }
+        } else {
+            quote! { #go::generic_static::<Self, #ty>(#init) }
+        }
+    }
     pub fn glib(&self) -> syn::Path {
         let go = &self.crate_path;
         parse_quote! { #go::glib }
@@ -355,7 +403,7 @@ impl TypeDefinition {
 
         let name = &self.name;
         let glib = self.glib();
-        let generics = &self.generics;
+        let (_, generics, _) = self.generics.split_for_impl();
 
         let recv = match ctx {
             Internal => parse_quote! { Self },
@@ -550,7 +598,7 @@ impl TypeDefinition {
         let defs = self.properties.iter().map(|p| p.definition(go));
         let extra = has_method.then(|| {
             quote_spanned! { Span::mixed_site() =>
-                properties.extend(#sub_ty::properties());
+                properties.extend(<#sub_ty>::properties());
             }
         });
         let base_index_set = (self.base == TypeBase::Class
@@ -558,20 +606,31 @@ impl TypeDefinition {
             && extra.is_some())
         .then(|| {
             quote_spanned! { Span::mixed_site() =>
-                self::_GENERATED_PROPERTIES_BASE_INDEX.set(properties.len()).unwrap();
+                self::_GENERATED_PROPERTIES_BASE_INDEX.get_or_init(|| properties.len());
             }
         });
+        let init = quote_spanned! { Span::mixed_site() =>
+            || {
+                let mut properties = ::std::vec::Vec::<#glib::ParamSpec>::new();
+                #extra
+                #custom
+                #base_index_set
+                properties.extend([#(#defs),*]);
+                properties
+            }
+        };
+        if self.has_type_statics() {
+            let props = self.type_static(quote! { ::std::vec::Vec<#glib::ParamSpec> }, init);
+            return Some(quote_spanned! { Span::mixed_site() =>
+                fn properties() -> &'static [#glib::ParamSpec] {
+                    #props
+                }
+            });
+        }
         Some(quote_spanned! { Span::mixed_site() =>
             fn properties() -> &'static [#glib::ParamSpec] {
                 static PROPS: #glib::once_cell::sync::Lazy<::std::vec::Vec<#glib::ParamSpec>> =
-                    #glib::once_cell::sync::Lazy::new(|| {
-                        let mut properties = ::std::vec::Vec::<#glib::ParamSpec>::new();
-                        #extra
-                        #custom
-                        #base_index_set
-                        properties.extend([#(#defs),*]);
-                        properties
-                    });
+                    #glib::once_cell::sync::Lazy::new(#init);
                 ::std::convert::AsRef::as_ref(::std::ops::Deref::deref(&PROPS))
             }
         })
@@ -592,26 +651,58 @@ impl TypeDefinition {
         let defs = self
             .signals
             .iter()
-            .map(|s| s.definition(&ty, &sub_ty, &glib));
+            .filter_map(|s| s.definition(&ty, &sub_ty, &self.crate_path));
         let extra = has_method.then(|| {
             quote_spanned! { Span::mixed_site() =>
-                signals.extend(#sub_ty::signals());
+                signals.extend(<#sub_ty>::signals());
             }
         });
+        let init = quote_spanned! { Span::mixed_site() =>
+            || {
+                let mut signals = ::std::vec::Vec::<#glib::subclass::Signal>::new();
+                #extra
+                #custom
+                signals.extend([#(#defs),*]);
+                signals
+            }
+        };
+        if self.has_type_statics() {
+            let signals =
+                self.type_static(quote! { ::std::vec::Vec<#glib::subclass::Signal> }, init);
+            return Some(quote_spanned! { Span::mixed_site() =>
+                fn signals() -> &'static [#glib::subclass::Signal] {
+                    #signals
+                }
+            });
+        }
         Some(quote_spanned! { Span::mixed_site() =>
             fn signals() -> &'static [#glib::subclass::Signal] {
                 static SIGNALS: #glib::once_cell::sync::Lazy<::std::vec::Vec<#glib::subclass::Signal>> =
-                    #glib::once_cell::sync::Lazy::new(|| {
-                        let mut signals = ::std::vec::Vec::<#glib::subclass::Signal>::new();
-                        #extra
-                        #custom
-                        signals.extend([#(#defs),*]);
-                        signals
-                    });
+                    #glib::once_cell::sync::Lazy::new(#init);
                 ::std::convert::AsRef::as_ref(::std::ops::Deref::deref(&SIGNALS))
             }
         })
     }
+    /// Names of the methods generated on the wrapper type.
+    pub(crate) fn wrapper_method_names(&self) -> Vec<syn::Ident> {
+        let static_methods = self
+            .public_methods
+            .iter()
+            .filter(|m| m.is_static())
+            .map(|m| m.sig.ident.clone());
+        self.public_method_prototypes()
+            .into_iter()
+            .filter_map(|proto| {
+                let mut tokens = proto.into_iter();
+                tokens.find(|t| matches!(t, proc_macro2::TokenTree::Ident(i) if i == "fn"))?;
+                match tokens.next()? {
+                    proc_macro2::TokenTree::Ident(ident) => Some(ident),
+                    _ => None,
+                }
+            })
+            .chain(static_methods)
+            .collect()
+    }
     fn public_method_prototypes(&self) -> Vec<TokenStream> {
         let go = &self.crate_path;
         let glib = self.glib();
@@ -621,7 +712,7 @@ impl TypeDefinition {
             .chain(
                 self.signals
                     .iter()
-                    .flat_map(|s| s.method_prototypes(self.concurrency, &glib)),
+                    .flat_map(|s| s.method_prototypes(self.concurrency, go)),
             )
             .chain(
                 self.public_methods
@@ -659,10 +750,16 @@ impl TypeDefinition {
             })
         };
         let signals = {
-            let glib = self.glib();
-            self.signals
-                .iter()
-                .flat_map(move |s| s.method_definitions(self.concurrency, &glib))
+            let go = self.crate_path.clone();
+            let ty = ty.clone();
+            let sub_ty = self.type_(
+                TypeMode::Subclass,
+                TypeMode::Subclass,
+                TypeContext::External,
+            );
+            self.signals.iter().flat_map(move |s| {
+                s.method_definitions(self.concurrency, self.has_type_statics(), &ty, &sub_ty, &go)
+            })
         };
         let public_methods = {
             let glib = self.glib();
@@ -814,7 +911,7 @@ impl TypeDefinition {
             .signals
             .iter()
             .filter_map(|signal| {
-                signal.class_init_override(&wrapper_ty, &sub_ty, &object_class, &glib)
+                signal.class_init_override(&wrapper_ty, &sub_ty, &object_class, &self.crate_path)
             })
             .collect::<Vec<_>>();
         if set_vtable.is_none() && overrides.is_empty() {
@@ -935,10 +1032,9 @@ impl TypeDefinition {
     }
     fn private_methods(&self, mode: TypeMode) -> Vec<TokenStream> {
         let mut methods = Vec::new();
-        let glib = self.glib();
 
         for signal in &self.signals {
-            if let Some(chain) = signal.chain_definition(mode, &glib) {
+            if let Some(chain) = signal.chain_definition(mode, &self.crate_path) {
                 methods.push(chain);
             }
         }
@@ -952,8 +1048,10 @@ impl TypeDefinition {
         let glib = self.glib();
         let wrapper_ty = self.type_(TypeMode::Subclass, TypeMode::Wrapper, TypeContext::External);
 
-        for signal in &self.signals {
-            items.push(signal.signal_id_cell_definition(&wrapper_ty, &glib));
+        if !self.has_type_statics() {
+            for signal in &self.signals {
+                items.push(signal.signal_id_cell_definition(&wrapper_ty, &glib));
+            }
         }
 
         let private_methods = self.private_methods(TypeMode::Subclass);
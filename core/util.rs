@@ -185,6 +185,46 @@ pub fn arg_reference(arg: &syn::FnArg) -> Option<TokenStream> {
     }
 }
 
+/// Returns the owned type recorded by a mock for an argument of type `ty`, and an expression
+/// copying `arg` into it.
+pub(crate) fn mock_arg(arg: &syn::Ident, ty: &syn::Type) -> (TokenStream, TokenStream) {
+    match ty {
+        syn::Type::Reference(syn::TypeReference { elem, .. }) => (
+            quote! { <#elem as ::std::borrow::ToOwned>::Owned },
+            quote! { <#elem as ::std::borrow::ToOwned>::to_owned(#arg) },
+        ),
+        ty => match option_ref_elem(ty) {
+            Some(elem) => (
+                quote! { ::std::option::Option<<#elem as ::std::borrow::ToOwned>::Owned> },
+                quote! {
+                    ::std::option::Option::map(#arg, <#elem as ::std::borrow::ToOwned>::to_owned)
+                },
+            ),
+            None => (quote! { #ty }, quote! { ::std::clone::Clone::clone(&#arg) }),
+        },
+    }
+}
+
+/// Returns `T` if `ty` is `Option<&T>`.
+fn option_ref_elem(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(syn::Type::Reference(r))) => Some(&r.elem),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 #[inline]
 pub fn signature_args(sig: &syn::Signature) -> impl Iterator<Item = &syn::Ident> + Clone {
     sig.inputs.iter().filter_map(arg_name)
@@ -315,6 +355,9 @@ impl GenericArgs {
     pub fn contains(&self, index: usize) -> bool {
         self.indices.contains(&index)
     }
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
     pub fn substitute(&self, sig: &mut syn::Signature, glib: &syn::Path) {
         for (index, arg) in sig.inputs.iter_mut().enumerate() {
             if self.indices.contains(&index) {
@@ -1,6 +1,6 @@
 use crate::{
     util::{self, Errors},
-    TypeBase, TypeMode,
+    MockItem, TypeBase, TypeMode,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
@@ -203,6 +203,54 @@ impl VirtualMethod {
             }
         }
     }
+    fn mock_args(&self) -> Vec<(syn::Ident, syn::Type)> {
+        self.external_sig()
+            .inputs
+            .into_iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(syn::PatType { pat, ty, .. }) => match *pat {
+                    syn::Pat::Ident(p) => Some((p.ident, *ty)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+    pub(crate) fn mock_item(&self) -> MockItem {
+        let args = self.mock_args();
+        MockItem {
+            ident: self.sig.ident.clone(),
+            name: self.sig.ident.to_string(),
+            owned: args
+                .iter()
+                .map(|(name, ty)| util::mock_arg(name, ty).0)
+                .collect(),
+            inputs: args.into_iter().map(|(_, ty)| ty).collect(),
+            output: self.sig.output.clone(),
+        }
+    }
+    pub(crate) fn mock_definition(&self, ext_trait: &syn::Path, glib: &syn::Path) -> TokenStream {
+        let this_ident = syn::Ident::new("____this", Span::mixed_site());
+        let func_ident = syn::Ident::new("____func", Span::mixed_site());
+        let mut sig = self.parent_sig(&this_ident, glib);
+        let parent_ident = std::mem::replace(&mut sig.ident, self.sig.ident.clone());
+        let ident = &self.sig.ident;
+        let args = self.mock_args();
+        let arg_names = args.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        let copies = args.iter().map(|(name, ty)| util::mock_arg(name, ty).1);
+        quote_spanned! { self.sig.span() =>
+            #sig {
+                match self.#ident.record((#(#copies,)*)) {
+                    ::std::option::Option::Some(#func_ident) => {
+                        #func_ident(#this_ident, #(#arg_names),*)
+                    }
+                    ::std::option::Option::None => {
+                        <Self as #ext_trait>::#parent_ident(self, #this_ident, #(#arg_names),*)
+                    }
+                }
+            }
+        }
+    }
     fn trampoline_sig(&self, ident: syn::Ident, ty: syn::Type) -> syn::Signature {
         let mut sig = self.external_sig();
         match sig.receiver().cloned() {
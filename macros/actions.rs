@@ -12,7 +12,10 @@ use quote::{quote, quote_spanned};
 use std::borrow::Cow;
 use syn::{parse_quote, parse_quote_spanned, spanned::Spanned};
 
-pub(crate) fn extend_actions(def: &mut gobject_core::ClassDefinition, errors: &Errors) {
+pub(crate) fn extend_actions(
+    def: &mut gobject_core::ClassDefinition,
+    errors: &Errors,
+) -> Vec<TokenStream> {
     let mut actions = Vec::new();
     for impl_ in def.inner.methods_items_mut() {
         if let Some(mode) = TypeMode::for_item_type(&*impl_.self_ty) {
@@ -20,14 +23,54 @@ pub(crate) fn extend_actions(def: &mut gobject_core::ClassDefinition, errors: &E
         }
     }
     if actions.is_empty() {
-        return;
+        return Vec::new();
     }
     validate_actions(&actions, errors);
     for action in &actions {
         action.override_public_methods(None, def, errors);
     }
     let go = &def.inner.crate_path;
+    let glib: syn::Path = parse_quote! { #go::glib };
+    let mut settings_keys = Vec::new();
+    for action in &actions {
+        let key = match &action.settings {
+            Some(key) => key,
+            None => continue,
+        };
+        if def.inner.settings_schema.is_none() {
+            errors.push_spanned(
+                key,
+                "`settings` requires `#[properties(settings_schema = \"...\")]`",
+            );
+        }
+        let duplicate = def
+            .inner
+            .properties
+            .iter()
+            .filter_map(|p| p.settings.as_ref())
+            .any(|s| s.key == key.value());
+        if duplicate {
+            errors.push_spanned(
+                key,
+                format!("Duplicate binding for settings key `{}`", key.value()),
+            );
+        }
+        if let Some(default) = action.default_state_variant(&glib, go) {
+            settings_keys.push(quote_spanned! { key.span() =>
+                #go::SettingsKey::with_default(#key, #default)
+            });
+        }
+    }
     let this_ident = syn::Ident::new("obj", Span::mixed_site());
+    let settings = def
+        .inner
+        .settings_schema
+        .as_ref()
+        .filter(|_| actions.iter().any(|action| action.settings.is_some()))
+        .map(|schema| {
+            let settings_ident = syn::Ident::new("settings", Span::mixed_site());
+            quote! { let #settings_ident = #go::gio::Settings::new(#schema); }
+        });
     let actions = actions.iter().map(|action| {
         let action = action.to_token_stream(&this_ident, true, go);
         quote! { #go::gio::prelude::ActionMapExt::add_action(#this_ident, &#action); }
@@ -37,10 +80,12 @@ pub(crate) fn extend_actions(def: &mut gobject_core::ClassDefinition, errors: &E
         parse_quote! {
             {
                 let #this_ident = unsafe { #this_ident.as_ref() };
+                #settings
                 #(#actions)*
             };
         },
     );
+    settings_keys
 }
 
 pub(crate) fn impl_group_actions(
@@ -53,6 +98,7 @@ pub(crate) fn impl_group_actions(
     #[darling(default)]
     struct ActionsAttrs {
         register: Option<syn::LitStr>,
+        settings_schema: Option<syn::LitStr>,
     }
 
     let attrs = util::parse_list::<ActionsAttrs>(attrs, errors);
@@ -74,6 +120,24 @@ pub(crate) fn impl_group_actions(
     let self_ident = syn::Ident::new("self", Span::mixed_site());
     let this_ident = syn::Ident::new("this", Span::mixed_site());
     let group_ident = syn::Ident::new("group", Span::mixed_site());
+    let settings = actions
+        .iter()
+        .any(|action| action.settings.is_some())
+        .then(|| {
+            let settings_ident = syn::Ident::new("settings", Span::mixed_site());
+            match &attrs.settings_schema {
+                Some(schema) => quote! { let #settings_ident = #go::gio::Settings::new(#schema); },
+                None => {
+                    for key in actions.iter().filter_map(|a| a.settings.as_ref()) {
+                        errors.push_spanned(
+                            key,
+                            "`settings` requires `#[group_actions(settings_schema = \"...\")]`",
+                        );
+                    }
+                    quote! {}
+                }
+            }
+        });
     let actions = actions.iter().map(|action| {
         let action = action.to_token_stream(&this_ident, false, go);
         quote! { #go::gio::prelude::ActionMapExt::add_action(#group_ident, &#action); }
@@ -83,6 +147,7 @@ pub(crate) fn impl_group_actions(
         impl #impl_generics #ty #where_clause {
             fn #register_func(&#self_ident, #group_ident: &impl #go::glib::IsA<#go::gio::ActionMap>) {
                 let #this_ident = #self_ident;
+                #settings
                 #(#actions)*
             }
         }
@@ -100,6 +165,14 @@ fn validate_actions(actions: &[Action], errors: &Errors) {
                 );
             }
         }
+        if let Some(key) = action.settings.as_ref() {
+            if action.state_type(&go).is_none() && action.default_state.is_none() {
+                errors.push_spanned(
+                    key,
+                    "Action bound to a settings key must have a state argument, return type, or `default` attribute"
+                );
+            }
+        }
     }
 }
 
@@ -121,6 +194,7 @@ struct ActionAttrs {
     parameter_to: Option<syn::Path>,
     parameter_from: Option<syn::Path>,
     parameter_with: Option<syn::Path>,
+    settings: Option<syn::LitStr>,
 }
 
 impl ActionAttrs {
@@ -820,6 +894,7 @@ struct Action {
     state_from: Option<syn::Path>,
     parameter_to: Option<syn::Path>,
     parameter_from: Option<syn::Path>,
+    settings: Option<syn::LitStr>,
 }
 
 impl Action {
@@ -870,6 +945,7 @@ impl Action {
                 state_from: None,
                 parameter_to: None,
                 parameter_from: None,
+                settings: None,
             };
             actions.push(action);
             actions.last_mut().unwrap()
@@ -1054,6 +1130,14 @@ impl Action {
                 action.disabled = true;
             }
         }
+
+        if let Some(key) = attr.settings {
+            if action.settings.is_some() {
+                errors.push_spanned(&key, "Duplicate `settings` attribute");
+            } else {
+                action.settings = Some(key);
+            }
+        }
     }
     fn parameter_convert_type(&self) -> Option<&syn::Type> {
         if !self.parameter_type.needs_convert() {
@@ -1197,23 +1281,7 @@ impl Action {
         ));
         Some(())
     }
-    fn to_token_stream(
-        &self,
-        this_ident: &syn::Ident,
-        is_object: bool,
-        go: &syn::Path,
-    ) -> TokenStream {
-        let glib: syn::Path = parse_quote! { #go::glib };
-        let gio: syn::Path = parse_quote! { #go::gio };
-        let action_ident = syn::Ident::new("action", Span::mixed_site());
-        let name = &self.name;
-        let parameter_type = self
-            .parameter_type
-            .type_expr(self.parameter_convert_type(), &glib);
-        let type_option = parameter_type
-            .as_ref()
-            .map(|ty| quote! { ::std::option::Option::Some(#ty) })
-            .unwrap_or_else(|| quote! { ::std::option::Option::None });
+    fn default_state_variant(&self, glib: &syn::Path, go: &syn::Path) -> Option<TokenStream> {
         let state_ty = self.state_type(go);
         let default_state = self
             .default_state
@@ -1228,7 +1296,7 @@ impl Action {
                     }
                 })
             });
-        let constructor = if let Some(expr) = default_state {
+        default_state.map(|expr| {
             let expr = state_ty
                 .as_ref()
                 .map(|state_ty| {
@@ -1239,11 +1307,39 @@ impl Action {
                     } }
                 })
                 .unwrap_or_else(|| expr);
-            let default_state = self
-                .state_to(&glib)
+            self.state_to(glib)
                 .map(|path| quote_spanned! { path.span() => #path(&#expr) })
-                .unwrap_or_else(|| quote! { #expr });
-            quote_spanned! { expr.span() =>
+                .unwrap_or_else(|| quote! { #expr })
+        })
+    }
+    fn to_token_stream(
+        &self,
+        this_ident: &syn::Ident,
+        is_object: bool,
+        go: &syn::Path,
+    ) -> TokenStream {
+        let glib: syn::Path = parse_quote! { #go::glib };
+        let gio: syn::Path = parse_quote! { #go::gio };
+        let action_ident = syn::Ident::new("action", Span::mixed_site());
+        let settings_ident = syn::Ident::new("settings", Span::mixed_site());
+        let name = &self.name;
+        let parameter_type = self
+            .parameter_type
+            .type_expr(self.parameter_convert_type(), &glib);
+        let type_option = parameter_type
+            .as_ref()
+            .map(|ty| quote! { ::std::option::Option::Some(#ty) })
+            .unwrap_or_else(|| quote! { ::std::option::Option::None });
+        let constructor = if let Some(key) = &self.settings {
+            quote_spanned! { key.span() =>
+                new_stateful(
+                    #name,
+                    #type_option,
+                    &#gio::prelude::SettingsExt::value(&#settings_ident, #key),
+                )
+            }
+        } else if let Some(default_state) = self.default_state_variant(&glib, go) {
+            quote_spanned! { default_state.span() =>
                 new_stateful(#name, #type_option, &#default_state)
             }
         } else {
@@ -1285,12 +1381,18 @@ impl Action {
                 #action_ident.set_enabled(false);
             }
         });
+        let bind_settings = self.settings.as_ref().map(|key| {
+            quote_spanned! { key.span() =>
+                #go::bind_action_state(&#settings_ident, #key, &#action_ident);
+            }
+        });
         quote_spanned! { self.span() =>
             {
                 let #action_ident = #gio::SimpleAction::#constructor;
                 #activate
                 #change_state
                 #set_state_hint
+                #bind_settings
                 #disable
                 #action_ident
             }
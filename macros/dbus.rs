@@ -0,0 +1,392 @@
+use darling::FromAttributes;
+use gobject_core::{
+    util::{self, Errors},
+    PropertyStorage, TypeContext, TypeMode,
+};
+use heck::ToUpperCamelCase;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{parse_quote, spanned::Spanned};
+
+#[derive(Debug, Default, FromAttributes)]
+#[darling(default, attributes(dbus))]
+struct DBusAttrs {
+    name: Option<syn::LitStr>,
+}
+
+impl DBusAttrs {
+    fn dbus_name(&self, name: &str) -> String {
+        self.name
+            .as_ref()
+            .map(|n| n.value())
+            .unwrap_or_else(|| name.to_upper_camel_case())
+    }
+}
+
+struct DBusMethod {
+    attrs: DBusAttrs,
+    mode: TypeMode,
+    sig: syn::Signature,
+}
+
+/// Returns the owned type read from a variant for an argument of type `ty`, and the expression
+/// passing `arg` back as `ty`.
+fn owned_arg(arg: &syn::Ident, ty: &syn::Type) -> (TokenStream, TokenStream) {
+    match ty {
+        syn::Type::Reference(syn::TypeReference { elem, .. }) => (
+            quote! { <#elem as ::std::borrow::ToOwned>::Owned },
+            quote! { &#arg },
+        ),
+        ty => (quote! { #ty }, quote! { #arg }),
+    }
+}
+
+fn arg_types(sig: &syn::Signature) -> impl Iterator<Item = &syn::Type> {
+    sig.inputs.iter().filter_map(|arg| match arg {
+        syn::FnArg::Typed(arg) => Some(arg.ty.as_ref()),
+        _ => None,
+    })
+}
+
+/// Returns the type inside `Result<T, E>`, if `ty` is a `Result`.
+fn result_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(p) => p.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_unit(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Tuple(t) if t.elems.is_empty())
+}
+
+pub(crate) fn extend_dbus(def: &mut gobject_core::ClassDefinition, errors: &Errors) {
+    let mut methods = Vec::new();
+    for impl_ in def.inner.methods_items_mut() {
+        let mode = match TypeMode::for_item_type(&impl_.self_ty) {
+            Some(mode) => mode,
+            None => continue,
+        };
+        for item in &mut impl_.items {
+            if let syn::ImplItem::Method(method) = item {
+                if let Some(attrs) = util::extract_attrs(&mut method.attrs, "dbus") {
+                    methods.push(DBusMethod {
+                        attrs: util::parse_attributes(&attrs, errors),
+                        mode,
+                        sig: method.sig.clone(),
+                    });
+                }
+            }
+        }
+    }
+    let mut properties = Vec::new();
+    for prop in &def.inner.properties {
+        let mut attrs = prop.field.attrs.clone();
+        if let Some(attrs) = util::extract_attrs(&mut attrs, "dbus") {
+            properties.push((util::parse_attributes::<DBusAttrs>(&attrs, errors), prop));
+        }
+    }
+    let storages = properties
+        .iter()
+        .map(|(_, p)| p.storage.clone())
+        .collect::<Vec<_>>();
+
+    let opts = match def.dbus.clone() {
+        Some(opts) => opts,
+        None => {
+            let span = properties
+                .iter()
+                .map(|(_, p)| p.span())
+                .chain(methods.iter().map(|m| m.sig.span()))
+                .next();
+            if let Some(span) = span {
+                errors.push(span, "`#[dbus]` requires the `dbus` option on the class");
+            }
+            strip_field_attrs(def, storages);
+            return;
+        }
+    };
+
+    let go = def.inner.crate_path.clone();
+    let glib = def.inner.glib();
+    let sub_ty = def.inner.type_(
+        TypeMode::Subclass,
+        TypeMode::Subclass,
+        TypeContext::External,
+    );
+    let name = &def.inner.name;
+    let wrapper_ty: syn::Type = parse_quote! { super::#name };
+    let self_ident = syn::Ident::new("self", Span::mixed_site());
+    let xml_ident = syn::Ident::new("xml", Span::mixed_site());
+    let params_ident = syn::Ident::new("parameters", Span::mixed_site());
+    let value_ident = syn::Ident::new("value", Span::mixed_site());
+    let values_ident = syn::Ident::new("values", Span::mixed_site());
+    let ret_ident = syn::Ident::new("ret", Span::mixed_site());
+
+    let mut xml = Vec::new();
+    let mut calls = Vec::new();
+    let mut signals = Vec::new();
+    for method in &methods {
+        let sig = &method.sig;
+        if let Some(signal) =
+            def.inner.signals.iter().find(|s| {
+                s.mode == method.mode && s.sig.as_ref().map(|s| &s.ident) == Some(&sig.ident)
+            })
+        {
+            if !matches!(sig.output, syn::ReturnType::Default) {
+                errors.push_spanned(&sig.output, "D-Bus signal must not have a return type");
+            }
+            let dbus_name = method.attrs.dbus_name(&signal.name);
+            let name = &signal.name;
+            let args = util::signature_args(sig)
+                .zip(arg_types(sig))
+                .map(|(arg, ty)| {
+                    let (owned, _) = owned_arg(arg, ty);
+                    let arg = arg.to_string();
+                    quote! {
+                        #xml_ident.push_str(&::std::format!(
+                            "<arg name=\"{}\" type=\"{}\"/>",
+                            #arg,
+                            <#owned as #glib::StaticVariantType>::static_variant_type().as_str(),
+                        ));
+                    }
+                });
+            xml.push(quote! {
+                #xml_ident.push_str(::std::concat!("<signal name=\"", #dbus_name, "\">"));
+                #(#args)*
+                #xml_ident.push_str("</signal>");
+            });
+            let values = arg_types(sig).enumerate().map(|(index, ty)| {
+                let (owned, _) = owned_arg(&sig.ident, ty);
+                let index = index + 1;
+                quote! { #values_ident[#index].get::<#owned>().unwrap() }
+            });
+            signals.push(quote! {
+                #go::DBusSignal {
+                    dbus_name: #dbus_name,
+                    name: #name,
+                    parameters: |#values_ident: &[#glib::Value]| -> #glib::Variant {
+                        #glib::ToVariant::to_variant(&(#(#values,)*))
+                    },
+                }
+            });
+            continue;
+        }
+        let pm = match def.inner.public_method(method.mode, &sig.ident) {
+            Some(pm) => pm,
+            None => {
+                errors.push_spanned(sig, "D-Bus method must be `#[public]` or `#[signal]`");
+                continue;
+            }
+        };
+        if let Some(asyncness) = &sig.asyncness {
+            errors.push_spanned(asyncness, "async D-Bus methods not supported");
+            continue;
+        }
+        if !sig.generics.params.is_empty() {
+            errors.push_spanned(&sig.generics, "generic D-Bus methods not supported");
+            continue;
+        }
+        if sig.receiver().is_none() {
+            errors.push_spanned(sig, "D-Bus method must take `self`");
+            continue;
+        }
+        if util::signature_args(sig).count() != arg_types(sig).count() {
+            errors.push_spanned(sig, "D-Bus method arguments must be identifiers");
+            continue;
+        }
+        let dbus_name = method.attrs.dbus_name(&pm.sig.ident.to_string());
+        let ident = &sig.ident;
+        let (owned, pass): (Vec<_>, Vec<_>) = util::signature_args(sig)
+            .zip(arg_types(sig))
+            .map(|(arg, ty)| owned_arg(arg, ty))
+            .unzip();
+        let arg_idents = util::signature_args(sig).collect::<Vec<_>>();
+        let arg_names = arg_idents.iter().map(|a| a.to_string());
+        let call = match method.mode {
+            TypeMode::Subclass => quote! {
+                #sub_ty::#ident(
+                    #glib::subclass::prelude::ObjectSubclassIsExt::imp(#self_ident),
+                    #(#pass),*
+                )
+            },
+            TypeMode::Wrapper => quote! { #wrapper_ty::#ident(#self_ident, #(#pass),*) },
+        };
+        let (out_ty, ret) = match &sig.output {
+            syn::ReturnType::Type(_, ty) if !is_unit(ty) => match result_type(ty) {
+                Some(ty) if is_unit(ty) => (
+                    None,
+                    quote! { #ret_ident.map(|()| #glib::ToVariant::to_variant(&())) },
+                ),
+                Some(ty) => (
+                    Some(ty),
+                    quote! { #ret_ident.map(|r| #glib::ToVariant::to_variant(&(r,))) },
+                ),
+                None => (
+                    Some(ty.as_ref()),
+                    quote! {
+                        ::std::result::Result::Ok(#glib::ToVariant::to_variant(&(#ret_ident,)))
+                    },
+                ),
+            },
+            _ => (
+                None,
+                quote! { ::std::result::Result::Ok(#glib::ToVariant::to_variant(&())) },
+            ),
+        };
+        let out_arg = out_ty.map(|ty| {
+            quote! {
+                #xml_ident.push_str(&::std::format!(
+                    "<arg type=\"{}\" direction=\"out\"/>",
+                    <#ty as #glib::StaticVariantType>::static_variant_type().as_str(),
+                ));
+            }
+        });
+        xml.push(quote! {
+            #xml_ident.push_str(::std::concat!("<method name=\"", #dbus_name, "\">"));
+            #(
+                #xml_ident.push_str(&::std::format!(
+                    "<arg name=\"{}\" type=\"{}\" direction=\"in\"/>",
+                    #arg_names,
+                    <#owned as #glib::StaticVariantType>::static_variant_type().as_str(),
+                ));
+            )*
+            #out_arg
+            #xml_ident.push_str("</method>");
+        });
+        calls.push(quote_spanned! { sig.span() =>
+            #dbus_name => {
+                let (#(#arg_idents,)*) = match <(#(#owned,)*) as #glib::FromVariant>::from_variant(
+                    #params_ident
+                ) {
+                    ::std::option::Option::Some(args) => args,
+                    ::std::option::Option::None => return ::std::option::Option::Some(
+                        ::std::result::Result::Err(#glib::Error::new(
+                            #go::gio::IOErrorEnum::InvalidArgument,
+                            "Invalid arguments",
+                        )),
+                    ),
+                };
+                let #ret_ident = #call;
+                ::std::option::Option::Some(#ret)
+            }
+        });
+    }
+
+    let mut getters = Vec::new();
+    let mut setters = Vec::new();
+    let mut notifies = Vec::new();
+    for (attrs, prop) in &properties {
+        let name = prop.name.to_string();
+        let dbus_name = attrs.dbus_name(&name);
+        let ty = prop.store_read_type(&go);
+        let get = prop.get.is_allowed();
+        let set = prop.set.is_allowed();
+        let access = match (get, set) {
+            (true, true) => "readwrite",
+            (true, false) => "read",
+            (false, _) => "write",
+        };
+        xml.push(quote! {
+            #xml_ident.push_str(&::std::format!(
+                "<property name=\"{}\" type=\"{}\" access=\"{}\"/>",
+                #dbus_name,
+                <#ty as #glib::StaticVariantType>::static_variant_type().as_str(),
+                #access,
+            ));
+        });
+        if get {
+            getters.push(quote! {
+                #dbus_name => ::std::option::Option::Some(#glib::ToVariant::to_variant(
+                    &#glib::prelude::ObjectExt::property::<#ty>(#self_ident, #name),
+                )),
+            });
+            if prop.notify {
+                notifies.push(quote! { (#dbus_name, #name) });
+            }
+        }
+        if set {
+            setters.push(quote! {
+                #dbus_name => match <#ty as #glib::FromVariant>::from_variant(#value_ident) {
+                    ::std::option::Option::Some(#value_ident) => {
+                        #glib::prelude::ObjectExt::set_property(#self_ident, #name, #value_ident);
+                        true
+                    }
+                    ::std::option::Option::None => false,
+                },
+            });
+        }
+    }
+    strip_field_attrs(def, storages);
+
+    let interface = &opts.name;
+    def.inner.ensure_items().push(syn::Item::Verbatim(quote! {
+        impl #go::DBusObject for #wrapper_ty {
+            const DBUS_INTERFACE: &'static str = #interface;
+            fn dbus_introspection_xml() -> ::std::string::String {
+                let mut #xml_ident = ::std::string::String::from(
+                    ::std::concat!("<node><interface name=\"", #interface, "\">"),
+                );
+                #(#xml)*
+                #xml_ident.push_str("</interface></node>");
+                #xml_ident
+            }
+            fn dbus_method_call(
+                &#self_ident,
+                method: &str,
+                #params_ident: &#glib::Variant,
+            ) -> ::std::option::Option<::std::result::Result<#glib::Variant, #glib::Error>> {
+                match method {
+                    #(#calls)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+            fn dbus_property(&#self_ident, name: &str) -> ::std::option::Option<#glib::Variant> {
+                match name {
+                    #(#getters)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+            fn dbus_set_property(&#self_ident, name: &str, #value_ident: &#glib::Variant) -> bool {
+                match name {
+                    #(#setters)*
+                    _ => false,
+                }
+            }
+            fn dbus_properties() -> &'static [(&'static str, &'static str)] {
+                &[#(#notifies),*]
+            }
+            fn dbus_signals() -> ::std::vec::Vec<#go::DBusSignal> {
+                ::std::vec![#(#signals),*]
+            }
+        }
+    }));
+}
+
+fn strip_field_attrs(def: &mut gobject_core::ClassDefinition, storages: Vec<PropertyStorage>) {
+    if let Some(item) = def.inner.properties_item_mut() {
+        for storage in storages {
+            let field = match &storage {
+                PropertyStorage::NamedField(ident) => item
+                    .fields
+                    .iter_mut()
+                    .find(|f| f.ident.as_ref() == Some(ident)),
+                PropertyStorage::UnnamedField(id) => item.fields.iter_mut().nth(*id),
+                _ => None,
+            };
+            if let Some(f) = field {
+                util::extract_attrs(&mut f.attrs, "dbus");
+            }
+        }
+    }
+}
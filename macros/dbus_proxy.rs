@@ -0,0 +1,603 @@
+use gobject_core::util::{self, Errors};
+use heck::{ToKebabCase, ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse_quote;
+
+#[derive(Debug)]
+enum DBusType {
+    Basic(u8),
+    Variant,
+    Array(Box<DBusType>),
+    Dict(Box<DBusType>, Box<DBusType>),
+    Struct(Vec<DBusType>),
+}
+
+impl DBusType {
+    fn parse(signature: &str) -> Option<Self> {
+        let mut bytes = signature.as_bytes();
+        let ty = Self::parse_one(&mut bytes)?;
+        bytes.is_empty().then_some(ty)
+    }
+    fn parse_one(bytes: &mut &[u8]) -> Option<Self> {
+        let (&c, rest) = bytes.split_first()?;
+        *bytes = rest;
+        Some(match c {
+            b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b'h' | b's' | b'o'
+            | b'g' => Self::Basic(c),
+            b'v' => Self::Variant,
+            b'a' if bytes.first() == Some(&b'{') => {
+                *bytes = &bytes[1..];
+                let key = Self::parse_one(bytes)?;
+                let value = Self::parse_one(bytes)?;
+                *bytes = bytes.strip_prefix(b"}")?;
+                Self::Dict(Box::new(key), Box::new(value))
+            }
+            b'a' => Self::Array(Box::new(Self::parse_one(bytes)?)),
+            b'(' => {
+                let mut fields = Vec::new();
+                while bytes.first() != Some(&b')') {
+                    fields.push(Self::parse_one(bytes)?);
+                }
+                *bytes = &bytes[1..];
+                Self::Struct(fields)
+            }
+            _ => return None,
+        })
+    }
+    /// The owned type used in method calls.
+    fn rust_type(&self, glib: &syn::Path) -> TokenStream {
+        match self {
+            Self::Basic(b'y') => quote! { u8 },
+            Self::Basic(b'b') => quote! { bool },
+            Self::Basic(b'n') => quote! { i16 },
+            Self::Basic(b'q') => quote! { u16 },
+            Self::Basic(b'i') => quote! { i32 },
+            Self::Basic(b'u') => quote! { u32 },
+            Self::Basic(b'x') => quote! { i64 },
+            Self::Basic(b't') => quote! { u64 },
+            Self::Basic(b'd') => quote! { f64 },
+            Self::Basic(b'h') => quote! { #glib::variant::Handle },
+            Self::Basic(b'o') => quote! { #glib::variant::ObjectPath },
+            Self::Basic(b'g') => quote! { #glib::variant::Signature },
+            Self::Basic(_) => quote! { ::std::string::String },
+            Self::Variant => quote! { #glib::Variant },
+            Self::Array(ty) => {
+                let ty = ty.rust_type(glib);
+                quote! { ::std::vec::Vec<#ty> }
+            }
+            Self::Dict(key, value) => {
+                let key = key.rust_type(glib);
+                let value = value.rust_type(glib);
+                quote! { ::std::collections::HashMap<#key, #value> }
+            }
+            Self::Struct(fields) => {
+                let fields = fields.iter().map(|f| f.rust_type(glib));
+                quote! { (#(#fields,)*) }
+            }
+        }
+    }
+    /// The type of properties and signal arguments, for types that can be stored in a
+    /// `glib::Value`. Other types are kept as a `glib::Variant`.
+    fn value_type(&self) -> Option<TokenStream> {
+        Some(match self {
+            Self::Basic(b'y') => quote! { u8 },
+            Self::Basic(b'b') => quote! { bool },
+            Self::Basic(b'i') => quote! { i32 },
+            Self::Basic(b'u') => quote! { u32 },
+            Self::Basic(b'x') => quote! { i64 },
+            Self::Basic(b't') => quote! { u64 },
+            Self::Basic(b'd') => quote! { f64 },
+            Self::Basic(b's' | b'o' | b'g') => quote! { ::std::string::String },
+            _ => return None,
+        })
+    }
+    fn is_string(&self) -> bool {
+        matches!(self, Self::Basic(b's' | b'o' | b'g'))
+    }
+    /// Converts the variant `expr` to an `Option` of the value type.
+    fn value_from_variant(&self, expr: TokenStream) -> TokenStream {
+        if self.is_string() {
+            quote! { #expr.str().map(::std::borrow::ToOwned::to_owned) }
+        } else if let Some(ty) = self.value_type() {
+            quote! { #expr.get::<#ty>() }
+        } else {
+            quote! { ::std::option::Option::Some(#expr) }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Arg {
+    name: Option<String>,
+    ty: DBusType,
+    out: bool,
+}
+
+#[derive(Debug, Default)]
+struct Member {
+    name: String,
+    args: Vec<Arg>,
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    signature: String,
+    ty: DBusType,
+    read: bool,
+    write: bool,
+}
+
+#[derive(Debug, Default)]
+struct Interface {
+    name: String,
+    methods: Vec<Member>,
+    signals: Vec<Member>,
+    properties: Vec<Property>,
+}
+
+fn parse_interfaces(xml: &str, span: Span, errors: &Errors) -> Vec<Interface> {
+    use quick_xml::events::{BytesStart, Event};
+
+    fn attr(e: &BytesStart, name: &[u8]) -> Option<String> {
+        e.attributes().find_map(|a| {
+            let a = a.ok()?;
+            (a.key == name)
+                .then(|| String::from_utf8(a.unescaped_value().ok()?.into_owned()).ok())
+                .flatten()
+        })
+    }
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut interfaces = Vec::<Interface>::new();
+    let mut signal = false;
+    loop {
+        let event = reader.read_event(&mut buf);
+        let e = match &event {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => e,
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                errors.push(
+                    span,
+                    format!(
+                        "Failed reading D-Bus XML at position {}: {:?}",
+                        reader.buffer_position(),
+                        e
+                    ),
+                );
+                break;
+            }
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+        let name = attr(e, b"name").unwrap_or_default();
+        match (e.name(), interfaces.last_mut()) {
+            (b"interface", _) => interfaces.push(Interface {
+                name,
+                ..Default::default()
+            }),
+            (b"method", Some(iface)) => {
+                signal = false;
+                iface.methods.push(Member {
+                    name,
+                    ..Default::default()
+                });
+            }
+            (b"signal", Some(iface)) => {
+                signal = true;
+                iface.signals.push(Member {
+                    name,
+                    ..Default::default()
+                });
+            }
+            (b"arg", Some(iface)) => {
+                let member = match signal {
+                    true => iface.signals.last_mut(),
+                    false => iface.methods.last_mut(),
+                };
+                let signature = attr(e, b"type").unwrap_or_default();
+                match (member, DBusType::parse(&signature)) {
+                    (Some(member), Some(ty)) => member.args.push(Arg {
+                        name: attr(e, b"name"),
+                        ty,
+                        out: !signal && attr(e, b"direction").as_deref() == Some("out"),
+                    }),
+                    (_, None) => errors.push(
+                        span,
+                        format!("Invalid D-Bus type `{}` in `{}`", signature, iface.name),
+                    ),
+                    _ => {}
+                }
+            }
+            (b"property", Some(iface)) => {
+                let signature = attr(e, b"type").unwrap_or_default();
+                let access = attr(e, b"access").unwrap_or_default();
+                match DBusType::parse(&signature) {
+                    Some(ty) => iface.properties.push(Property {
+                        name,
+                        signature,
+                        ty,
+                        read: access.contains("read"),
+                        write: access.contains("write"),
+                    }),
+                    None => errors.push(
+                        span,
+                        format!("Invalid D-Bus type `{}` in `{}`", signature, iface.name),
+                    ),
+                }
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    interfaces
+}
+
+/// Returns a valid identifier for a D-Bus name, appending `_` to keywords.
+fn ident(name: &str) -> syn::Ident {
+    let name = name.to_snake_case();
+    syn::parse_str::<syn::Ident>(&name).unwrap_or_else(|_| format_ident!("{}_", name))
+}
+
+pub(crate) fn proxy_from_xml(input: TokenStream, go: &syn::Path, errors: &Errors) -> TokenStream {
+    let file = match util::parse::<syn::LitStr>(input, errors) {
+        Some(file) => file,
+        None => return Default::default(),
+    };
+    let path = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+        .join(file.value());
+    let xml = match std::fs::read_to_string(&path) {
+        Ok(xml) => xml,
+        Err(e) => {
+            errors.push(
+                file.span(),
+                format!("Failed to read `{}`: {}", path.display(), e),
+            );
+            return Default::default();
+        }
+    };
+    let path = path.to_string_lossy();
+    let interfaces = parse_interfaces(&xml, file.span(), errors);
+    let proxies = interfaces
+        .iter()
+        .filter(|iface| !iface.name.starts_with("org.freedesktop.DBus."))
+        .map(|iface| proxy(iface, &path, go));
+    quote! { #(#proxies)* }
+}
+
+fn proxy(iface: &Interface, path: &str, go: &syn::Path) -> TokenStream {
+    let glib: syn::Path = parse_quote! { #go::glib };
+    let gio: syn::Path = parse_quote! { #go::gio };
+    let short_name = iface.name.rsplit('.').next().unwrap_or_default();
+    let name = format_ident!("{}Proxy", short_name.to_upper_camel_case());
+    let mod_name = format_ident!("{}", name.to_string().to_snake_case());
+    let interface = &iface.name;
+    let self_ident = syn::Ident::new("self", Span::mixed_site());
+    let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+    let proxy_ident = syn::Ident::new("proxy", Span::mixed_site());
+    let value_ident = syn::Ident::new("value", Span::mixed_site());
+    let values_ident = syn::Ident::new("values", Span::mixed_site());
+    let parameters_ident = syn::Ident::new("parameters", Span::mixed_site());
+    let ret_ident = syn::Ident::new("ret", Span::mixed_site());
+    let reply_ident = syn::Ident::new("reply", Span::mixed_site());
+
+    let mut fields = Vec::new();
+    let mut accessors = Vec::new();
+    let mut notifies = Vec::new();
+    for prop in &iface.properties {
+        let dbus_name = &prop.name;
+        let field = ident(dbus_name);
+        let prop_name = dbus_name.to_kebab_case();
+        let (ty, getter) = match prop.ty.value_type() {
+            Some(ty) => {
+                let conv = prop.ty.value_from_variant(quote! { v });
+                (ty, quote! { .and_then(|v| #conv).unwrap_or_default() })
+            }
+            None => (quote! { ::std::option::Option<#glib::Variant> }, quote! {}),
+        };
+        let get = prop.read.then(|| quote! { get, });
+        let set = prop.write.then(|| quote! { set, });
+        let builder = prop.ty.value_type().is_none().then(|| {
+            let defaults = format!(
+                "[{}::VariantTy::new({:?}).unwrap()]",
+                util::path_to_string(&glib),
+                prop.signature
+            );
+            quote! { builder_defaults = #defaults, }
+        });
+        fields.push(quote! {
+            #[property(computed, #get #set explicit_notify, #builder)]
+            #field: ::std::marker::PhantomData<#ty>,
+        });
+        if prop.read {
+            accessors.push(quote! {
+                #[public]
+                fn #field(&#self_ident) -> #ty {
+                    #self_ident
+                        .proxy
+                        .get()
+                        .and_then(|p| #gio::prelude::DBusProxyExt::cached_property(p, #dbus_name))
+                        #getter
+                }
+            });
+            notifies.push(quote! {
+                #dbus_name => #glib::prelude::ObjectExt::notify(&#obj_ident, #prop_name),
+            });
+        }
+        if prop.write {
+            let setter = format_ident!("set_{}", field);
+            let to_variant = match &prop.ty {
+                DBusType::Basic(b'o') => quote! {
+                    match #glib::variant::ObjectPath::try_from(#value_ident.as_str()) {
+                        ::std::result::Result::Ok(v) => #glib::ToVariant::to_variant(&v),
+                        ::std::result::Result::Err(_) => return,
+                    }
+                },
+                DBusType::Basic(b'g') => quote! {
+                    match #glib::variant::Signature::try_from(#value_ident.as_str()) {
+                        ::std::result::Result::Ok(v) => #glib::ToVariant::to_variant(&v),
+                        ::std::result::Result::Err(_) => return,
+                    }
+                },
+                ty if ty.value_type().is_some() => {
+                    quote! { #glib::ToVariant::to_variant(&#value_ident) }
+                }
+                _ => quote! {
+                    match #value_ident {
+                        ::std::option::Option::Some(v) => v,
+                        ::std::option::Option::None => return,
+                    }
+                },
+            };
+            accessors.push(quote! {
+                fn #setter(#obj_ident: &super::#name, #value_ident: #ty) {
+                    let #value_ident = #to_variant;
+                    #gio::prelude::DBusProxyExt::call(
+                        #obj_ident.proxy(),
+                        "org.freedesktop.DBus.Properties.Set",
+                        ::std::option::Option::Some(&#glib::ToVariant::to_variant(
+                            &(#interface, #dbus_name, #value_ident),
+                        )),
+                        #gio::DBusCallFlags::NONE,
+                        -1,
+                        #gio::Cancellable::NONE,
+                        |_| {},
+                    );
+                }
+            });
+        }
+    }
+
+    let mut signals = Vec::new();
+    let mut emits = Vec::new();
+    for signal in &iface.signals {
+        let dbus_name = &signal.name;
+        let signal_ident = ident(dbus_name);
+        let emit = format_ident!("emit_{}", signal_ident);
+        let args = signal.args.iter().enumerate().map(|(index, arg)| {
+            let name = arg
+                .name
+                .as_deref()
+                .map(ident)
+                .unwrap_or_else(|| format_ident!("arg{}", index));
+            let ty = arg
+                .ty
+                .value_type()
+                .unwrap_or_else(|| quote! { #glib::Variant });
+            quote! { #name: #ty }
+        });
+        signals.push(quote! {
+            #[signal]
+            fn #signal_ident(&#self_ident, #(#args),*) {}
+        });
+        let signature = format!(
+            "({})",
+            signal
+                .args
+                .iter()
+                .map(|a| signature_of(&a.ty))
+                .collect::<String>()
+        );
+        let values = signal.args.iter().enumerate().map(|(index, arg)| {
+            let child = quote! { #parameters_ident.child_value(#index) };
+            let conv = arg.ty.value_from_variant(child);
+            quote! { #conv.unwrap() }
+        });
+        emits.push(quote! {
+            #dbus_name if #parameters_ident.is_type(#glib::VariantTy::new(#signature).unwrap()) => {
+                #obj_ident.#emit(#(#values),*);
+            }
+        });
+    }
+
+    let mut methods = Vec::new();
+    for method in &iface.methods {
+        let dbus_name = &method.name;
+        let method_ident = ident(dbus_name);
+        let in_args = method.args.iter().filter(|a| !a.out).collect::<Vec<_>>();
+        let out_types = method
+            .args
+            .iter()
+            .filter(|a| a.out)
+            .map(|a| a.ty.rust_type(&glib))
+            .collect::<Vec<_>>();
+        let arg_names = in_args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                arg.name
+                    .as_deref()
+                    .map(ident)
+                    .unwrap_or_else(|| format_ident!("arg{}", index))
+            })
+            .collect::<Vec<_>>();
+        let arg_types = in_args.iter().map(|arg| match &arg.ty {
+            DBusType::Basic(b's') => quote! { &str },
+            ty => ty.rust_type(&glib),
+        });
+        let (output, ret) = match out_types.len() {
+            0 => (quote! { () }, quote! { #ret_ident.map(|_| ()) }),
+            1 => {
+                let ty = &out_types[0];
+                (
+                    ty.clone(),
+                    quote! { #reply_ident(#ret_ident?).map(|(v,)| v) },
+                )
+            }
+            _ => (
+                quote! { (#(#out_types,)*) },
+                quote! { #reply_ident(#ret_ident?) },
+            ),
+        };
+        let reply = (!out_types.is_empty()).then(|| {
+            quote! {
+                let #reply_ident = |v: #glib::Variant| {
+                    v.get::<(#(#out_types,)*)>().ok_or_else(|| {
+                        #glib::Error::new(#gio::IOErrorEnum::InvalidData, "Unexpected reply type")
+                    })
+                };
+            }
+        });
+        let doc = format!("Calls the `{}` D-Bus method.", dbus_name);
+        methods.push(quote! {
+            #[doc = #doc]
+            pub async fn #method_ident(
+                &#self_ident,
+                #(#arg_names: #arg_types),*
+            ) -> ::std::result::Result<#output, #glib::Error> {
+                #reply
+                let #ret_ident = #gio::prelude::DBusProxyExt::call_future(
+                    #self_ident.proxy(),
+                    #dbus_name,
+                    ::std::option::Option::Some(&#glib::ToVariant::to_variant(&(#(#arg_names,)*))),
+                    #gio::DBusCallFlags::NONE,
+                    -1,
+                )
+                .await;
+                #ret
+            }
+        });
+    }
+
+    let doc = format!("Proxy for the `{}` D-Bus interface.", interface);
+    quote! {
+        #[#go::class(final)]
+        mod #mod_name {
+            #[doc = #doc]
+            #[derive(Default)]
+            pub struct #name {
+                pub(super) proxy: #glib::once_cell::unsync::OnceCell<#gio::DBusProxy>,
+                #(#fields)*
+            }
+            impl #name {
+                #(#accessors)*
+                #(#signals)*
+            }
+        }
+
+        impl #name {
+            /// The name of the D-Bus interface.
+            pub const INTERFACE: &'static str = #interface;
+            /// Returns the introspection data for the D-Bus interface.
+            pub fn interface_info() -> #gio::DBusInterfaceInfo {
+                #gio::DBusNodeInfo::for_xml(::std::include_str!(#path))
+                    .unwrap()
+                    .lookup_interface(Self::INTERFACE)
+                    .unwrap()
+            }
+            /// Creates a proxy for the object at `object_path` owned by `name` on `connection`.
+            ///
+            /// `name` must be `None` for peer-to-peer connections.
+            pub async fn new(
+                connection: &#gio::DBusConnection,
+                name: ::std::option::Option<&str>,
+                object_path: &str,
+            ) -> ::std::result::Result<Self, #glib::Error> {
+                let #proxy_ident = #gio::DBusProxy::new_future(
+                    connection,
+                    #gio::DBusProxyFlags::NONE,
+                    ::std::option::Option::Some(&Self::interface_info()),
+                    name,
+                    object_path,
+                    Self::INTERFACE,
+                )
+                .await?;
+                ::std::result::Result::Ok(Self::from_proxy(#proxy_ident))
+            }
+            /// Wraps an existing proxy for the interface.
+            pub fn from_proxy(#proxy_ident: #gio::DBusProxy) -> Self {
+                let #obj_ident = #glib::Object::new::<Self>(&[]).unwrap();
+                let weak = #glib::prelude::ObjectExt::downgrade(&#obj_ident);
+                #glib::prelude::ObjectExt::connect_local(
+                    &#proxy_ident,
+                    "g-properties-changed",
+                    false,
+                    #glib::clone!(@strong weak => move |#values_ident| {
+                        let #obj_ident = weak.upgrade()?;
+                        let changed = #values_ident[1].get::<#glib::Variant>().ok()?;
+                        let mut names = changed
+                            .iter()
+                            .filter_map(|e| e.child_value(0).get::<::std::string::String>())
+                            .collect::<::std::vec::Vec<_>>();
+                        names.extend(
+                            #values_ident[2]
+                                .get::<::std::vec::Vec<::std::string::String>>()
+                                .unwrap_or_default(),
+                        );
+                        for name in names {
+                            match name.as_str() {
+                                #(#notifies)*
+                                _ => {}
+                            }
+                        }
+                        ::std::option::Option::None
+                    }),
+                );
+                #glib::prelude::ObjectExt::connect_local(
+                    &#proxy_ident,
+                    "g-signal",
+                    false,
+                    move |#values_ident| {
+                        let #obj_ident = weak.upgrade()?;
+                        let name = #values_ident[2].get::<::std::string::String>().ok()?;
+                        let #parameters_ident = #values_ident[3].get::<#glib::Variant>().ok()?;
+                        match name.as_str() {
+                            #(#emits)*
+                            _ => {}
+                        }
+                        ::std::option::Option::None
+                    },
+                );
+                #glib::subclass::prelude::ObjectSubclassIsExt::imp(&#obj_ident)
+                    .proxy
+                    .set(#proxy_ident)
+                    .unwrap();
+                #obj_ident
+            }
+            /// Returns the underlying proxy.
+            pub fn proxy(&#self_ident) -> &#gio::DBusProxy {
+                #glib::subclass::prelude::ObjectSubclassIsExt::imp(#self_ident)
+                    .proxy
+                    .get()
+                    .expect("proxy not created with `new` or `from_proxy`")
+            }
+            #(#methods)*
+        }
+    }
+}
+
+fn signature_of(ty: &DBusType) -> String {
+    match ty {
+        DBusType::Basic(c) => char::from(*c).to_string(),
+        DBusType::Variant => String::from("v"),
+        DBusType::Array(ty) => format!("a{}", signature_of(ty)),
+        DBusType::Dict(key, value) => format!("a{{{}{}}}", signature_of(key), signature_of(value)),
+        DBusType::Struct(fields) => {
+            format!("({})", fields.iter().map(signature_of).collect::<String>())
+        }
+    }
+}
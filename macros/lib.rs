@@ -4,6 +4,10 @@ use quote::ToTokens;
 
 #[cfg(any(feature = "gtk4", feature = "gio"))]
 mod actions;
+#[cfg(any(feature = "gtk4", feature = "gio"))]
+mod dbus;
+#[cfg(any(feature = "gtk4", feature = "gio"))]
+mod dbus_proxy;
 #[cfg(feature = "gtk4")]
 mod gtk4_actions;
 #[cfg(feature = "gtk4")]
@@ -12,6 +16,8 @@ mod gtk4_templates;
 mod initable;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(any(feature = "gtk4", feature = "gio"))]
+mod settings;
 #[cfg(feature = "variant")]
 mod variant;
 
@@ -53,9 +59,17 @@ pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
                 syn::parse_quote! { super::#ident }
             });
             #[cfg(any(feature = "gtk4", feature = "gio"))]
-            actions::extend_actions(&mut class, &errors);
+            let action_keys = actions::extend_actions(&mut class, &errors);
             #[cfg(any(feature = "gtk4", feature = "gio"))]
             initable::extend_initables(&mut class, &errors);
+            #[cfg(any(feature = "gtk4", feature = "gio"))]
+            dbus::extend_dbus(&mut class, &errors);
+            #[cfg(any(feature = "gtk4", feature = "gio"))]
+            settings::extend_settings(&mut class, action_keys);
+            #[cfg(not(any(feature = "gtk4", feature = "gio")))]
+            if let Some(schema) = &class.inner.settings_schema {
+                errors.push_spanned(schema, "`settings_schema` requires the `use_gio` feature");
+            }
             #[cfg(feature = "variant")]
             variant::extend_variant(
                 &mut class.inner,
@@ -76,8 +90,21 @@ pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
                 &errors,
             );
 
+            if !class.inner.is_generic() {
+                write_gir(
+                    &class.gtype_name(),
+                    class.ns.as_ref(),
+                    |ns| class.to_gir(ns),
+                    &errors,
+                );
+            }
+
             class.add_private_items();
-            class.to_token_stream()
+            #[allow(unused_mut)]
+            let mut tokens = class.to_token_stream();
+            #[cfg(feature = "mock")]
+            tokens.extend(class.mock_definition());
+            tokens
         })
         .unwrap_or_default();
     append_errors(tokens, errors)
@@ -113,6 +140,12 @@ pub fn interface(attr: TokenStream, item: TokenStream) -> TokenStream {
                 iface.ns.as_ref(),
                 &errors,
             );
+            write_gir(
+                &iface.gtype_name(),
+                iface.ns.as_ref(),
+                |ns| iface.to_gir(ns),
+                &errors,
+            );
             iface.add_private_items(&errors);
             iface.to_token_stream()
         })
@@ -120,6 +153,91 @@ pub fn interface(attr: TokenStream, item: TokenStream) -> TokenStream {
     append_errors(tokens, errors)
 }
 
+#[proc_macro_attribute]
+pub fn boxed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    use gobject_core::{BoxedDefinition, BoxedOptions};
+
+    let errors = Errors::new();
+    let opts = BoxedOptions::parse(attr.into(), &errors);
+    let item = util::parse::<syn::ItemStruct>(item.into(), &errors);
+    let tokens = item
+        .map(|item| {
+            let go = crate_path();
+            #[allow(unused_mut)]
+            let mut def = BoxedDefinition::parse(item, opts, go, &errors);
+            #[cfg(feature = "variant")]
+            let variant = variant::extend_boxed(&mut def, &errors);
+            #[cfg(feature = "serde")]
+            serde::extend_boxed(&mut def, &errors);
+            #[allow(unused_mut)]
+            let mut tokens = def.to_token_stream();
+            #[cfg(feature = "variant")]
+            tokens.extend(variant);
+            tokens
+        })
+        .unwrap_or_default();
+    append_errors(tokens, errors)
+}
+
+#[proc_macro_attribute]
+pub fn enum_(attr: TokenStream, item: TokenStream) -> TokenStream {
+    use gobject_core::{EnumDefinition, EnumOptions};
+
+    let errors = Errors::new();
+    let opts = EnumOptions::parse(attr.into(), &errors);
+    let item = util::parse::<syn::ItemEnum>(item.into(), &errors);
+    let tokens = item
+        .map(|item| {
+            let go = crate_path();
+            #[allow(unused_mut)]
+            let mut def = EnumDefinition::parse(item, opts, go, &errors);
+            #[cfg(feature = "serde")]
+            serde::extend_enum(&mut def);
+            #[allow(unused_mut)]
+            let mut tokens = def.to_token_stream();
+            #[cfg(feature = "variant")]
+            {
+                let go = &def.crate_path;
+                let adapter = syn::parse_quote! { #go::variant::glib::enum_string };
+                tokens.extend(variant::impl_adapter(&def.item.ident, &adapter, go));
+            }
+            tokens
+        })
+        .unwrap_or_default();
+    append_errors(tokens, errors)
+}
+
+#[proc_macro_attribute]
+pub fn flags(attr: TokenStream, item: TokenStream) -> TokenStream {
+    use gobject_core::{FlagsDefinition, FlagsOptions};
+
+    let errors = Errors::new();
+    let opts = FlagsOptions::parse(attr.into(), &errors);
+    let item = util::parse::<syn::ItemEnum>(item.into(), &errors);
+    let tokens = item
+        .map(|item| {
+            let go = crate_path();
+            let def = FlagsDefinition::parse(item, opts, go, &errors);
+            #[allow(unused_mut)]
+            let mut tokens = def.to_token_stream();
+            #[cfg(feature = "serde")]
+            tokens.extend(serde::impl_flags(&def));
+            #[cfg(feature = "variant")]
+            {
+                let go = &def.crate_path;
+                let adapter = if def.numeric {
+                    syn::parse_quote! { #go::variant::glib::flags }
+                } else {
+                    syn::parse_quote! { #go::variant::glib::flags_string }
+                };
+                tokens.extend(variant::impl_adapter(&def.ident, &adapter, go));
+            }
+            tokens
+        })
+        .unwrap_or_default();
+    append_errors(tokens, errors)
+}
+
 #[cfg(feature = "variant")]
 #[proc_macro]
 pub fn variant_cast(input: TokenStream) -> TokenStream {
@@ -151,6 +269,15 @@ pub fn group_actions(attr: TokenStream, item: TokenStream) -> TokenStream {
     append_errors(tokens, errors)
 }
 
+#[cfg(any(feature = "gtk4", feature = "gio"))]
+#[proc_macro]
+pub fn dbus_proxy(input: TokenStream) -> TokenStream {
+    let errors = Errors::new();
+    let go = crate_path();
+    let output = dbus_proxy::proxy_from_xml(input.into(), &go, &errors);
+    append_errors(output, errors)
+}
+
 #[cfg(feature = "gtk4")]
 #[proc_macro_attribute]
 pub fn gtk4_widget(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -178,8 +305,10 @@ pub fn gtk4_widget(attr: TokenStream, item: TokenStream) -> TokenStream {
                     #go::gtk4::subclass::prelude::WidgetImpl
                 });
             }
-            actions::extend_actions(&mut class, &errors);
+            let action_keys = actions::extend_actions(&mut class, &errors);
             initable::extend_initables(&mut class, &errors);
+            dbus::extend_dbus(&mut class, &errors);
+            settings::extend_settings(&mut class, action_keys);
             gtk4_templates::extend_template(&mut class, &errors);
             gtk4_actions::extend_widget_actions(&mut class, &errors);
             class.add_private_items();
@@ -189,6 +318,33 @@ pub fn gtk4_widget(attr: TokenStream, item: TokenStream) -> TokenStream {
     append_errors(tokens, errors)
 }
 
+// Writes `<TypeName>.gir` into `$GOBJECT_GIR_DIR` when it is set. Nothing is written otherwise,
+// so expanding the macro has no side effects unless opted into.
+fn write_gir(
+    gtype_name: &str,
+    ns: Option<&syn::Ident>,
+    generate: impl FnOnce(&gobject_core::GirNamespace) -> String,
+    errors: &Errors,
+) {
+    let mut path = match std::env::var_os("GOBJECT_GIR_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => return,
+    };
+    path.push(format!("{}.gir", gtype_name));
+    let gir = generate(&gobject_core::GirNamespace::from_env(ns));
+    let result = path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| std::fs::write(&path, gir));
+    if let Err(e) = result {
+        errors.push(
+            proc_macro2::Span::call_site(),
+            format!("Failed to write GIR to `{}`: {}", path.display(), e),
+        );
+    }
+}
+
 #[inline]
 fn append_errors(mut tokens: proc_macro2::TokenStream, errors: Errors) -> TokenStream {
     if let Some(errors) = errors.into_compile_errors() {
@@ -3,7 +3,8 @@ use darling::{
     FromAttributes, FromMeta,
 };
 use gobject_core::{
-    util, PropertyOverride, PropertyPermission, PropertyStorage, TypeBase, TypeDefinition, TypeMode,
+    util, BoxedDefinition, EnumDefinition, FlagsDefinition, PropertyOverride, PropertyPermission,
+    PropertyStorage, TypeBase, TypeDefinition, TypeMode,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
@@ -459,6 +460,153 @@ pub(crate) fn extend_serde(
 }
 
 #[inline]
+pub(crate) fn extend_enum(def: &mut EnumDefinition) {
+    let go = &def.crate_path;
+    let gtype_name = def.gtype_name();
+    let item = &mut def.item;
+    let derives = quote! { #go::serde::Serialize, #go::serde::Deserialize };
+    add_item_attrs(&mut item.attrs, derives, &gtype_name, go);
+    for (variant, value) in item.variants.iter_mut().zip(def.values.iter()) {
+        let mut is_named = false;
+        let named = variant
+            .attrs
+            .iter()
+            .any(|a| has_name(a, &value.nick, &mut is_named));
+        if !named && !is_named {
+            let nick = &value.nick;
+            variant
+                .attrs
+                .push(parse_quote! { #[serde(rename = #nick)] });
+        }
+    }
+}
+
+pub(crate) fn extend_boxed(def: &mut BoxedDefinition, errors: &util::Errors) {
+    let attr = match util::extract_attrs(&mut def.item.attrs, "gobject_serde") {
+        Some(attrs) => {
+            let span = attrs
+                .iter()
+                .map(|a| a.span())
+                .reduce(|a, b| a.join(b).unwrap_or(a))
+                .unwrap_or_else(Span::call_site);
+            let attrs = util::parse_attributes::<SerdeAttrs>(&attrs, errors);
+            if attrs.serialize.is_none() && attrs.deserialize.is_none() {
+                errors.push(
+                    span,
+                    "Must have at least one of these attributes: `serialize`, `deserialize`",
+                );
+            }
+            if attrs.skip_parent.is_some() {
+                errors.push(
+                    attrs.skip_parent.span(),
+                    "`skip_parent` not allowed on boxed type",
+                );
+            }
+            if let Some(path) = attrs.child_types.first() {
+                errors.push_spanned(path, "`child_types` not allowed on boxed type");
+            }
+            attrs
+        }
+        None => return,
+    };
+
+    let go = &def.crate_path;
+    let gtype_name = def.gtype_name();
+    let ser = attr
+        .serialize
+        .is_some()
+        .then(|| quote! { #go::serde::Serialize });
+    let de = attr
+        .deserialize
+        .is_some()
+        .then(|| quote! { #go::serde::Deserialize });
+    let derives = ser.into_iter().chain(de);
+    add_item_attrs(
+        &mut def.item.attrs,
+        quote! { #(#derives),* },
+        &gtype_name,
+        go,
+    );
+}
+
+fn add_item_attrs(
+    attrs: &mut Vec<syn::Attribute>,
+    derives: TokenStream,
+    gtype_name: &str,
+    go: &syn::Path,
+) {
+    attrs.insert(0, parse_quote! { #[derive(#derives)] });
+    if !attrs
+        .iter()
+        .any(|a| a.path.is_ident("serde") && has_meta(a, "crate"))
+    {
+        let crate_ = (quote! { #go::serde }).to_string();
+        attrs.push(parse_quote! { #[serde(crate = #crate_)] });
+    }
+    if !attrs
+        .iter()
+        .any(|a| a.path.is_ident("serde") && has_meta(a, "rename"))
+    {
+        attrs.push(parse_quote! { #[serde(rename = #gtype_name)] });
+    }
+}
+
+pub(crate) fn impl_flags(def: &FlagsDefinition) -> TokenStream {
+    let go = &def.crate_path;
+    let glib = def.glib();
+    let ident = &def.ident;
+    let (ser, de) = if def.numeric {
+        (
+            quote! {
+                serializer.serialize_u32(self.bits())
+            },
+            quote! {
+                let bits = <::std::primitive::u32 as #go::serde::Deserialize>::deserialize(deserializer)?;
+                Self::from_bits(bits).ok_or_else(|| {
+                    <D::Error as #go::serde::de::Error>::custom(::std::format!(
+                        "Invalid value `{}` for flags `{}`",
+                        bits,
+                        <Self as #glib::StaticType>::static_type().name(),
+                    ))
+                })
+            },
+        )
+    } else {
+        (
+            quote! {
+                let class = #glib::FlagsClass::new(<Self as #glib::StaticType>::static_type()).unwrap();
+                serializer.serialize_str(&class.to_nick_string(self.bits()))
+            },
+            quote! {
+                let s = <::std::string::String as #go::serde::Deserialize>::deserialize(deserializer)?;
+                let class = #glib::FlagsClass::new(<Self as #glib::StaticType>::static_type()).unwrap();
+                let bits = class
+                    .from_nick_string(&s)
+                    .map_err(<D::Error as #go::serde::de::Error>::custom)?;
+                ::std::result::Result::Ok(Self::from_bits_truncate(bits))
+            },
+        )
+    };
+    quote! {
+        impl #go::serde::Serialize for #ident {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #go::serde::Serializer,
+            {
+                #ser
+            }
+        }
+        impl<'de> #go::serde::Deserialize<'de> for #ident {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: #go::serde::Deserializer<'de>,
+            {
+                #de
+            }
+        }
+    }
+}
+
 fn construct_obj_call(def: &TypeDefinition, go: &syn::Path, errors: &util::Errors) -> TokenStream {
     #[cfg(feature = "gio")]
     {
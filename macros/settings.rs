@@ -0,0 +1,133 @@
+use gobject_core::{PropertyType, TypeMode};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{parse_quote, spanned::Spanned};
+
+pub(crate) fn extend_settings(
+    def: &mut gobject_core::ClassDefinition,
+    action_keys: Vec<TokenStream>,
+) {
+    let schema = match &def.inner.settings_schema {
+        Some(schema) => schema.clone(),
+        None => return,
+    };
+    let go = def.inner.crate_path.clone();
+    let glib = def.inner.glib();
+    let self_ident = syn::Ident::new("self", Span::mixed_site());
+    let obj_ident = syn::Ident::new("obj", Span::mixed_site());
+    let settings_ident = syn::Ident::new("settings", Span::mixed_site());
+    let class_ident = syn::Ident::new("class", Span::mixed_site());
+    let value_ident = syn::Ident::new("value", Span::mixed_site());
+
+    let mut binds = Vec::new();
+    let mut keys = Vec::new();
+    for prop in &def.inner.properties {
+        let settings = match &prop.settings {
+            Some(settings) => settings,
+            None => continue,
+        };
+        let name = prop.name.to_string();
+        let key = &settings.key;
+        let ty = prop.store_type(&go);
+        let get = settings.get.then(|| quote! { .get() });
+        let set = settings.set.then(|| quote! { .set() });
+        let no_sensitivity = settings
+            .no_sensitivity
+            .then(|| quote! { .no_sensitivity() });
+        let pspec = quote! { &#class_ident.find_property(#name).unwrap() };
+        let (mapping, key_info) = match &settings.with {
+            Some(with) => {
+                let default = match &settings.default {
+                    Some(default) => quote! { ::std::option::Option::Some(#default) },
+                    None => quote! { ::std::option::Option::None },
+                };
+                (
+                    Some(quote_spanned! { with.span() =>
+                        .mapping(|variant, _| {
+                            #with::from_variant(variant).map(|v| #glib::ToValue::to_value(&v))
+                        })
+                        .set_mapping(|#value_ident, _| {
+                            #value_ident.get::<#ty>().ok().map(|v| #with::to_variant(&v))
+                        })
+                    }),
+                    quote_spanned! { with.span() =>
+                        #go::SettingsKey::with_mapping(
+                            #key,
+                            #pspec,
+                            #with::static_variant_type(),
+                            #default,
+                            |#value_ident| {
+                                #with::to_variant(&#value_ident.get::<#ty>().unwrap_or_default())
+                            },
+                        )
+                    },
+                )
+            }
+            None => {
+                let param_spec = match prop.special_type {
+                    PropertyType::Enum => quote! { #glib::ParamSpecEnum },
+                    PropertyType::Flags => quote! { #glib::ParamSpecFlags },
+                    _ => quote! { <#ty as #go::ParamSpecBuildable>::ParamSpec },
+                };
+                let key_info = match &settings.default {
+                    Some(default) => quote_spanned! { prop.field.ty.span() =>
+                        #go::SettingsKey::new::<#param_spec>(#key, #pspec, #default)
+                    },
+                    None => quote_spanned! { prop.field.ty.span() =>
+                        #go::SettingsKey::from_property::<#param_spec>(#key, #pspec)
+                    },
+                };
+                (None, key_info)
+            }
+        };
+        binds.push(quote_spanned! { prop.span() =>
+            #go::bind_settings(&#settings_ident, #key, #obj_ident, #name)
+                #get
+                #set
+                #no_sensitivity
+                #mapping
+                .build();
+        });
+        keys.push(key_info);
+    }
+
+    if !binds.is_empty() {
+        let parent_constructed = (!def.inner.has_method(TypeMode::Subclass, "constructed")
+            && !def.inner.has_custom_stmts("constructed"))
+        .then(|| {
+            quote! {
+                #glib::subclass::object::ObjectImplExt::parent_constructed(#self_ident, #obj_ident);
+            }
+        });
+        def.inner.add_custom_stmt(
+            "constructed",
+            parse_quote! {{
+                #parent_constructed
+                let #settings_ident = #go::gio::Settings::new(#schema);
+                #(#binds)*
+            };},
+        );
+    }
+
+    let class = (!keys.is_empty()).then(|| {
+        quote! {
+            let #class_ident = #glib::Class::<#glib::Object>::from_type(
+                <Self as #glib::StaticType>::static_type(),
+            )
+            .unwrap();
+        }
+    });
+    keys.extend(action_keys);
+    let name = &def.inner.name;
+    let (impl_generics, type_generics, where_clause) = def.inner.generics.split_for_impl();
+    let impl_ = quote! {
+        impl #impl_generics #go::SettingsObject for super::#name #type_generics #where_clause {
+            const SETTINGS_SCHEMA: &'static str = #schema;
+            fn settings_keys() -> ::std::vec::Vec<#go::SettingsKey> {
+                #class
+                ::std::vec![#(#keys),*]
+            }
+        }
+    };
+    def.inner.ensure_items().push(syn::Item::Verbatim(impl_));
+}
@@ -3,11 +3,13 @@ use darling::{
     FromAttributes, FromMeta,
 };
 use gobject_core::{
-    util, validations, PropertyOverride, PropertyPermission, PropertyStorage, TypeBase,
-    TypeDefinition,
+    util, validations, BoxedDefinition, PropertyOverride, PropertyPermission, PropertyStorage,
+    TypeBase, TypeDefinition,
 };
+use heck::ToKebabCase;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
 use syn::{parse_quote, spanned::Spanned};
 
 #[derive(Debug, Default, FromAttributes)]
@@ -68,21 +70,7 @@ pub(crate) fn extend_variant(
     let attr = def
         .properties_item_mut()
         .and_then(|item| util::extract_attrs(&mut item.attrs, "variant"))
-        .map(|attrs| {
-            let span = attrs
-                .iter()
-                .map(|a| a.span())
-                .reduce(|a, b| a.join(b).unwrap_or(a))
-                .unwrap_or_else(Span::call_site);
-            let attrs = util::parse_attributes::<VariantAttrs>(&attrs, errors);
-            if attrs.to.is_none() && attrs.from.is_none() {
-                errors.push(
-                    span,
-                    "Must have at least one of these attributes: `to`, `from`",
-                );
-            }
-            attrs
-        })
+        .map(|attrs| parse_type_attrs(attrs, errors))
         .unwrap_or_default();
     let to = attr.to.is_some();
     let from = attr.from.is_some();
@@ -126,14 +114,7 @@ pub(crate) fn extend_variant(
             if matches!(&prop.override_, Some(PropertyOverride::Class(_))) {
                 return None;
             }
-            let mut attrs = prop.field.attrs.clone();
-            let attrs = util::extract_attrs(&mut attrs, "variant")
-                .map(|attrs| {
-                    let attrs = util::parse_attributes::<VariantFieldAttrs>(&attrs, errors);
-                    attrs.validate(errors);
-                    attrs
-                })
-                .unwrap_or_default();
+            let attrs = parse_field_attrs(&mut prop.field.attrs.clone(), errors);
             attrs.skip.is_none().then(|| (attrs, prop))
         })
         .collect::<Vec<_>>();
@@ -177,8 +158,10 @@ pub(crate) fn extend_variant(
         quote! { ::std::borrow::Cow::Borrowed(#glib::VariantTy::VARDICT) }
     } else {
         let builder_ident = syn::Ident::new("builder", Span::mixed_site());
-        let parent_type = (!skip_parent).then(|| parent_type).flatten().map(|ty| quote! {
-            #builder_ident.append(<#ty as #go::ParentStaticVariantType>::parent_static_variant_type().as_str());
+        let parent_type = (!skip_parent).then(|| parent_type).flatten().map(|ty| {
+            quote! {
+                <#ty as #go::ParentStaticVariantType>::parent_static_variant_type().as_str()
+            }
         });
         let prop_types = props.iter().filter_map(|(attr, prop)| {
             let prop = *prop;
@@ -188,42 +171,18 @@ pub(crate) fn extend_variant(
             if !prop.set.is_allowed() || attr.skip_from.is_some() {
                 return None;
             }
-            let ty = if let Some(type_str) = attr.variant_type_str.as_ref() {
-                quote_spanned! { type_str.span() =>
-                    #glib::VariantTy::new(#type_str).unwrap().as_str()
-                }
-            } else if let Some(path) = attr.variant_type.as_ref() {
-                let ty_ident = syn::Ident::new("ty", Span::mixed_site());
-                quote_spanned! { path.span() => ({
-                    let #ty_ident: ::std::borrow::Cow<'static, #glib::VariantTy> = #path();
-                    #ty_ident
-                }).as_str() }
-            } else if let Some(with) = attr.with.as_ref() {
-                let ty_ident = syn::Ident::new("ty", Span::mixed_site());
-                quote_spanned! { with.span() => ({
-                    let #ty_ident: ::std::borrow::Cow<'static, #glib::VariantTy> = #with::static_variant_type();
-                    #ty_ident
-                }).as_str() }
-            } else {
-                let ty = prop.store_type(go);
-                quote_spanned! { prop.span() =>
-                    <#ty as #glib::StaticVariantType>::static_variant_type().as_str()
-                }
-            };
-            Some(quote_spanned! { prop.span() =>
-                #builder_ident.append(#ty);
-            })
+            Some(field_variant_type(
+                attr,
+                prop.store_type(go),
+                prop.span(),
+                &glib,
+            ))
         });
-        quote! {
-            let mut #builder_ident = <#glib::GStringBuilder as ::std::default::Default>::default();
-            #builder_ident.append_c('(');
-            #parent_type
-            #(#prop_types)*
-            #builder_ident.append_c(')');
-            ::std::borrow::Cow::Owned(#glib::VariantType::from_string(
-                #builder_ident.into_string()
-            ).unwrap())
-        }
+        tuple_variant_type(
+            &glib,
+            &builder_ident,
+            parent_type.into_iter().chain(prop_types),
+        )
     };
     let parent_static_type = (!final_).then(|| {
         let type_head = def.trait_head(&wrapper_ty, quote! { #go::ParentStaticVariantType });
@@ -252,133 +211,112 @@ pub(crate) fn extend_variant(
         #parent_static_type
     };
 
-    let to_variant =
-        to.then(|| {
-            let self_ident = syn::Ident::new("self", Span::mixed_site());
-            let parent_field = (!skip_parent)
-                .then(|| parent_type)
-                .flatten()
-                .map(|ty| {
+    let to_variant = to.then(|| {
+        let self_ident = syn::Ident::new("self", Span::mixed_site());
+        let parent_field = (!skip_parent)
+            .then(|| parent_type)
+            .flatten()
+            .map(|ty| {
+                quote! {
+                    #go::ToParentVariant::to_parent_variant(
+                        #glib::Cast::upcast_ref::<#ty>(#self_ident)
+                    )
+                }
+            })
+            .into_iter();
+        let write_fields = props.iter().filter_map(|(attr, prop)| {
+            let prop = *prop;
+            if !prop.get.is_allowed() || attr.skip_to.is_some() {
+                return None;
+            }
+            let name = prop.getter_name();
+            let getter = match &prop.get {
+                PropertyPermission::Allow | PropertyPermission::AllowCustomDefault => {
+                    let path = ext_trait
+                        .map(|t| quote! { #t })
+                        .unwrap_or_else(|| quote! { #wrapper_ty });
+                    quote! { #path::#name(#self_ident) }
+                }
+                PropertyPermission::AllowNoMethod => {
+                    let pname = prop.name.to_string();
                     quote! {
-                        #go::ToParentVariant::to_parent_variant(
-                            #glib::Cast::upcast_ref::<#ty>(#self_ident)
-                        )
+                        #glib::prelude::ObjectExt::property(#self_ident, #pname)
                     }
-                })
-                .into_iter();
-            let write_fields = props.iter().filter_map(|(attr, prop)| {
+                }
+                PropertyPermission::AllowCustom(p) => quote! { #p(#self_ident) },
+                _ => unreachable!(),
+            };
+            Some(field_to_variant(attr, getter, &glib))
+        });
+
+        let fields = parent_field.chain(write_fields);
+        let create_variant = if attr.dict.is_some() {
+            let parent_name = parent_name.as_ref().map(|p| p.to_string());
+            let field_names = props.iter().filter_map(|(attr, prop)| {
                 let prop = *prop;
                 if !prop.get.is_allowed() || attr.skip_to.is_some() {
                     return None;
                 }
-                let name = prop.getter_name();
-                let getter = match &prop.get {
-                    PropertyPermission::Allow | PropertyPermission::AllowCustomDefault => {
-                        let path = ext_trait
-                            .map(|t| quote! { #t })
-                            .unwrap_or_else(|| quote! { #wrapper_ty });
-                        quote! { #path::#name(#self_ident) }
-                    }
-                    PropertyPermission::AllowNoMethod => {
-                        let pname = prop.name.to_string();
-                        quote! {
-                            #glib::prelude::ObjectExt::property(#self_ident, #pname)
-                        }
-                    }
-                    PropertyPermission::AllowCustom(p) => quote! { #p(#self_ident) },
-                    _ => unreachable!(),
-                };
-                Some(if let Some(with) = &attr.with {
-                    quote_spanned! { with.span() =>
-                        #with::to_variant(&#getter)
-                    }
-                } else if let Some(to_variant) = &attr.to {
-                    quote_spanned! { to_variant.span() =>
-                        #to_variant(&#getter)
-                    }
-                } else {
-                    quote! { #glib::ToVariant::to_variant(&#getter) }
-                })
+                Some(prop.name.to_string())
             });
+            dict_variant(
+                &glib,
+                parent_name.into_iter().chain(field_names).zip(fields),
+            )
+        } else {
+            quote! { #glib::Variant::tuple_from_iter([#(#fields),*]) }
+        };
 
-            let fields = parent_field.chain(write_fields);
-            let create_variant =
-                if attr.dict.is_some() {
-                    let dict_ident = syn::Ident::new("dict", Span::mixed_site());
-                    let parent_name = parent_name.as_ref().map(|p| p.to_string());
-                    let field_names = props.iter().filter_map(|(attr, prop)| {
-                        let prop = *prop;
-                        if !prop.get.is_allowed() || attr.skip_to.is_some() {
-                            return None;
-                        }
-                        Some(prop.name.to_string())
-                    });
-                    let fields = parent_name.into_iter().chain(field_names).zip(fields).map(
-                        |(name, field)| {
-                            quote! {
-                                #dict_ident.insert_value(#name, &#field);
-                            }
-                        },
-                    );
-                    quote! {
-                        let #dict_ident = #glib::VariantDict::new(::std::option::Option::None);
-                        #(#fields)*
-                        unsafe { #dict_ident.end_unsafe() }
+        if final_ {
+            let head = def.trait_head(&wrapper_ty, quote! { #glib::ToVariant });
+            quote! {
+                #head {
+                    fn to_variant(&#self_ident) -> #glib::Variant {
+                        #create_variant
                     }
-                } else {
-                    quote! { #glib::Variant::tuple_from_iter([#(#fields),*]) }
-                };
-
-            if final_ {
+                }
+            }
+        } else {
+            let to_variant = if !child_types.is_empty() {
                 let head = def.trait_head(&wrapper_ty, quote! { #glib::ToVariant });
-                quote! {
+                let casts = serialize_child_types(
+                    &*child_types,
+                    &wrapper_ty,
+                    def.base == TypeBase::Class && !abstract_,
+                    go,
+                );
+                Some(quote! {
                     #head {
                         fn to_variant(&#self_ident) -> #glib::Variant {
-                            #create_variant
+                            #casts
                         }
                     }
-                }
-            } else {
-                let to_variant = if !child_types.is_empty() {
-                    let head = def.trait_head(&wrapper_ty, quote! { #glib::ToVariant });
-                    let casts = serialize_child_types(
-                        &*child_types,
-                        &wrapper_ty,
-                        def.base == TypeBase::Class && !abstract_,
-                        go,
-                    );
-                    Some(quote! {
-                        #head {
-                            fn to_variant(&#self_ident) -> #glib::Variant {
-                                #casts
-                            }
-                        }
-                    })
-                } else if !abstract_ {
-                    let head = def.trait_head(&wrapper_ty, quote! { #glib::ToVariant });
-                    Some(quote! {
-                        #head {
-                            #[inline]
-                            fn to_variant(&#self_ident) -> #glib::Variant {
-                                #go::ToParentVariant::to_parent_variant(#self_ident)
-                            }
-                        }
-                    })
-                } else {
-                    None
-                };
-                let head = def.trait_head(&wrapper_ty, quote! { #go::ToParentVariant });
-                quote! {
-                    #to_variant
-                    #[doc(hidden)]
+                })
+            } else if !abstract_ {
+                let head = def.trait_head(&wrapper_ty, quote! { #glib::ToVariant });
+                Some(quote! {
                     #head {
-                        fn to_parent_variant(&#self_ident) -> #glib::Variant {
-                            #create_variant
+                        #[inline]
+                        fn to_variant(&#self_ident) -> #glib::Variant {
+                            #go::ToParentVariant::to_parent_variant(#self_ident)
                         }
                     }
+                })
+            } else {
+                None
+            };
+            let head = def.trait_head(&wrapper_ty, quote! { #go::ToParentVariant });
+            quote! {
+                #to_variant
+                #[doc(hidden)]
+                #head {
+                    fn to_parent_variant(&#self_ident) -> #glib::Variant {
+                        #create_variant
+                    }
                 }
             }
-        });
+        }
+    });
 
     let from_variant = from.then(|| {
         let obj_ident = syn::Ident::new("obj", Span::mixed_site());
@@ -416,13 +354,7 @@ pub(crate) fn extend_variant(
             }
             let name = prop.name.to_string();
             let ty = prop.store_write_type(go);
-            let convert = if let Some(with) = &attrs.with {
-                quote_spanned! { with.span() => #with::from_variant }
-            } else if let Some(from_variant) = &attrs.from {
-                quote_spanned! { from_variant.span() => #from_variant }
-            } else {
-                quote! { #glib::FromVariant::from_variant }
-            };
+            let convert = field_from_variant(attrs, &ty, &glib);
             Some(if attr.dict.is_some() {
                 quote! {
                     if #name_ident == #name {
@@ -546,6 +478,277 @@ pub(crate) fn extend_variant(
     }));
 }
 
+pub(crate) fn extend_boxed(def: &mut BoxedDefinition, errors: &util::Errors) -> TokenStream {
+    let attr = match util::extract_attrs(&mut def.item.attrs, "variant") {
+        Some(attrs) => {
+            let attrs = parse_type_attrs(attrs, errors);
+            validations::disallow(
+                "boxed type",
+                &[("skip_parent", validations::check_flag(&attrs.skip_parent))],
+                errors,
+            );
+            if let Some(path) = attrs.child_types.first() {
+                errors.push_spanned(path, "`child_types` not allowed on boxed type");
+            }
+            attrs
+        }
+        None => return Default::default(),
+    };
+    let to = attr.to.is_some();
+    let from = attr.from.is_some();
+    let dict = attr.dict.is_some();
+
+    let fields = def
+        .item
+        .fields
+        .iter_mut()
+        .enumerate()
+        .map(|(index, field)| {
+            let attrs = parse_field_attrs(&mut field.attrs, errors);
+            let (member, name) = match &field.ident {
+                Some(ident) => (
+                    syn::Member::Named(ident.clone()),
+                    ident.unraw().to_string().to_kebab_case(),
+                ),
+                None => (syn::Member::Unnamed(index.into()), index.to_string()),
+            };
+            (attrs, member, name, field.ty.clone())
+        })
+        .collect::<Vec<_>>();
+
+    if !dict && to && from {
+        for (attrs, member, _, _) in &fields {
+            if attrs.skip.is_none() && attrs.skip_to.is_some() != attrs.skip_from.is_some() {
+                errors.push_spanned(
+                    member,
+                    "Field for variant differs in readability/writability. \
+                    Try using #[variant(skip)] on the field, \
+                    or #[variant(dict)] on the struct",
+                );
+            }
+        }
+    }
+
+    let glib = def.glib();
+    let ident = &def.item.ident;
+    let variant_ident = syn::Ident::new("variant", Span::mixed_site());
+
+    let has_to = |attrs: &VariantFieldAttrs| attrs.skip.is_none() && attrs.skip_to.is_none();
+    let has_from = |attrs: &VariantFieldAttrs| attrs.skip.is_none() && attrs.skip_from.is_none();
+    let in_tuple = |attrs: &VariantFieldAttrs| {
+        attrs.skip.is_none()
+            && (!to || attrs.skip_to.is_none())
+            && (!from || attrs.skip_from.is_none())
+    };
+
+    let static_variant_type = if dict {
+        quote! { ::std::borrow::Cow::Borrowed(#glib::VariantTy::VARDICT) }
+    } else {
+        let builder_ident = syn::Ident::new("builder", Span::mixed_site());
+        let field_types = fields
+            .iter()
+            .filter(|f| in_tuple(&f.0))
+            .map(|(attrs, _, _, ty)| field_variant_type(attrs, ty, ty.span(), &glib));
+        tuple_variant_type(&glib, &builder_ident, field_types)
+    };
+
+    let to_variant = to.then(|| {
+        let write_fields = fields
+            .iter()
+            .filter(|f| if dict { has_to(&f.0) } else { in_tuple(&f.0) })
+            .map(|(attrs, member, name, _)| {
+                let value = field_to_variant(attrs, quote! { self.#member }, &glib);
+                (name.clone(), value)
+            });
+        let create_variant = if dict {
+            dict_variant(&glib, write_fields)
+        } else {
+            let values = write_fields.map(|(_, value)| value);
+            quote! { #glib::Variant::tuple_from_iter([#(#values),*]) }
+        };
+        quote! {
+            impl #glib::ToVariant for #ident {
+                fn to_variant(&self) -> #glib::Variant {
+                    #create_variant
+                }
+            }
+        }
+    });
+
+    let from_variant = from.then(|| {
+        let dict_ident = syn::Ident::new("dict", Span::mixed_site());
+        let value_ident = syn::Ident::new("value", Span::mixed_site());
+        let mut index = 0usize;
+        let read_fields = fields.iter().map(|(attrs, member, name, ty)| {
+            let included = if dict { has_from(attrs) } else { in_tuple(attrs) };
+            if !included {
+                return quote! { #member: ::std::default::Default::default() };
+            }
+            let convert = field_from_variant(attrs, ty, &glib);
+            if dict {
+                quote! {
+                    #member: match #dict_ident.lookup_value(#name, ::std::option::Option::None) {
+                        ::std::option::Option::Some(#value_ident) => #convert(&#value_ident)?,
+                        ::std::option::Option::None => ::std::default::Default::default(),
+                    }
+                }
+            } else {
+                let child = index;
+                index += 1;
+                quote! { #member: #convert(&#variant_ident.try_child_value(#child)?)? }
+            }
+        }).collect::<Vec<_>>();
+        let check = if dict {
+            quote! {
+                if !#variant_ident.is_type(#glib::VariantTy::VARDICT) {
+                    return ::std::option::Option::None;
+                }
+                let #dict_ident = #glib::VariantDict::new(::std::option::Option::Some(#variant_ident));
+            }
+        } else {
+            quote! {
+                if !#variant_ident.is_type(&<Self as #glib::StaticVariantType>::static_variant_type()) {
+                    return ::std::option::Option::None;
+                }
+            }
+        };
+        quote! {
+            impl #glib::FromVariant for #ident {
+                fn from_variant(#variant_ident: &#glib::Variant) -> ::std::option::Option<Self> {
+                    #check
+                    ::std::option::Option::Some(Self {
+                        #(#read_fields),*
+                    })
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #glib::StaticVariantType for #ident {
+            fn static_variant_type() -> ::std::borrow::Cow<'static, #glib::VariantTy> {
+                #static_variant_type
+            }
+        }
+        #to_variant
+        #from_variant
+    }
+}
+
+fn parse_type_attrs(attrs: Vec<syn::Attribute>, errors: &util::Errors) -> VariantAttrs {
+    let span = attrs
+        .iter()
+        .map(|a| a.span())
+        .reduce(|a, b| a.join(b).unwrap_or(a))
+        .unwrap_or_else(Span::call_site);
+    let attrs = util::parse_attributes::<VariantAttrs>(&attrs, errors);
+    if attrs.to.is_none() && attrs.from.is_none() {
+        errors.push(
+            span,
+            "Must have at least one of these attributes: `to`, `from`",
+        );
+    }
+    attrs
+}
+
+fn parse_field_attrs(attrs: &mut Vec<syn::Attribute>, errors: &util::Errors) -> VariantFieldAttrs {
+    util::extract_attrs(attrs, "variant")
+        .map(|attrs| {
+            let attrs = util::parse_attributes::<VariantFieldAttrs>(&attrs, errors);
+            attrs.validate(errors);
+            attrs
+        })
+        .unwrap_or_default()
+}
+
+fn tuple_variant_type(
+    glib: &syn::Path,
+    builder_ident: &syn::Ident,
+    types: impl IntoIterator<Item = TokenStream>,
+) -> TokenStream {
+    let types = types.into_iter();
+    quote! {
+        let mut #builder_ident = <#glib::GStringBuilder as ::std::default::Default>::default();
+        #builder_ident.append_c('(');
+        #(#builder_ident.append(#types);)*
+        #builder_ident.append_c(')');
+        ::std::borrow::Cow::Owned(#glib::VariantType::from_string(
+            #builder_ident.into_string()
+        ).unwrap())
+    }
+}
+
+fn field_variant_type(
+    attrs: &VariantFieldAttrs,
+    ty: impl quote::ToTokens,
+    span: Span,
+    glib: &syn::Path,
+) -> TokenStream {
+    let ty_ident = syn::Ident::new("ty", Span::mixed_site());
+    if let Some(type_str) = attrs.variant_type_str.as_ref() {
+        quote_spanned! { type_str.span() =>
+            #glib::VariantTy::new(#type_str).unwrap().as_str()
+        }
+    } else if let Some(path) = attrs.variant_type.as_ref() {
+        quote_spanned! { path.span() => ({
+            let #ty_ident: ::std::borrow::Cow<'static, #glib::VariantTy> = #path();
+            #ty_ident
+        }).as_str() }
+    } else if let Some(with) = attrs.with.as_ref() {
+        quote_spanned! { with.span() => ({
+            let #ty_ident: ::std::borrow::Cow<'static, #glib::VariantTy> = #with::static_variant_type();
+            #ty_ident
+        }).as_str() }
+    } else {
+        quote_spanned! { span =>
+            <#ty as #glib::StaticVariantType>::static_variant_type().as_str()
+        }
+    }
+}
+
+fn field_to_variant(
+    attrs: &VariantFieldAttrs,
+    value: TokenStream,
+    glib: &syn::Path,
+) -> TokenStream {
+    if let Some(with) = &attrs.with {
+        quote_spanned! { with.span() => #with::to_variant(&#value) }
+    } else if let Some(to_variant) = &attrs.to {
+        quote_spanned! { to_variant.span() => #to_variant(&#value) }
+    } else {
+        quote! { #glib::ToVariant::to_variant(&#value) }
+    }
+}
+
+fn field_from_variant(
+    attrs: &VariantFieldAttrs,
+    ty: impl quote::ToTokens,
+    glib: &syn::Path,
+) -> TokenStream {
+    if let Some(with) = &attrs.with {
+        quote_spanned! { with.span() => #with::from_variant }
+    } else if let Some(from_variant) = &attrs.from {
+        quote_spanned! { from_variant.span() => #from_variant }
+    } else {
+        quote! { <#ty as #glib::FromVariant>::from_variant }
+    }
+}
+
+fn dict_variant(
+    glib: &syn::Path,
+    entries: impl IntoIterator<Item = (String, TokenStream)>,
+) -> TokenStream {
+    let dict_ident = syn::Ident::new("dict", Span::mixed_site());
+    let inserts = entries.into_iter().map(|(name, value)| {
+        quote! { #dict_ident.insert_value(#name, &#value); }
+    });
+    quote! {
+        let #dict_ident = #glib::VariantDict::new(::std::option::Option::None);
+        #(#inserts)*
+        unsafe { #dict_ident.end_unsafe() }
+    }
+}
+
 #[inline]
 fn construct_obj_call(
     _def: &TypeDefinition,
@@ -744,3 +947,24 @@ pub(crate) fn downcast_enum(
         #from_variant
     }
 }
+
+pub(crate) fn impl_adapter(ty: &syn::Ident, adapter: &syn::Path, go: &syn::Path) -> TokenStream {
+    let glib: syn::Path = parse_quote! { #go::glib };
+    quote! {
+        impl #glib::StaticVariantType for #ty {
+            fn static_variant_type() -> ::std::borrow::Cow<'static, #glib::VariantTy> {
+                #adapter::static_variant_type()
+            }
+        }
+        impl #glib::ToVariant for #ty {
+            fn to_variant(&self) -> #glib::Variant {
+                #adapter::to_variant(self)
+            }
+        }
+        impl #glib::FromVariant for #ty {
+            fn from_variant(variant: &#glib::Variant) -> ::std::option::Option<Self> {
+                #adapter::from_variant(variant)
+            }
+        }
+    }
+}
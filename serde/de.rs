@@ -0,0 +1,215 @@
+use crate::Error;
+use glib::{Variant, VariantClass};
+use serde::de::{
+    self, value::StringDeserializer, DeserializeOwned, DeserializeSeed, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+/// Deserializes a `T` from a [`glib::Variant`].
+///
+/// The variant is read by its own type, so any variant with the layout described in
+/// [`SerdeVariantType`](crate::SerdeVariantType) can be read, including ones built by other
+/// libraries.
+pub fn from_variant<T: DeserializeOwned>(variant: &Variant) -> Result<T, Error> {
+    T::deserialize(Deserializer(variant.clone()))
+}
+
+struct Deserializer(Variant);
+
+impl Deserializer {
+    fn str(&self) -> Result<String, Error> {
+        self.0
+            .str()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| Error::mismatch(self.0.type_().as_str(), "s"))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let v = &self.0;
+        match v.classify() {
+            VariantClass::Boolean => visitor.visit_bool(v.get().unwrap()),
+            VariantClass::Byte => visitor.visit_u8(v.get().unwrap()),
+            VariantClass::Int16 => visitor.visit_i16(v.get().unwrap()),
+            VariantClass::Uint16 => visitor.visit_u16(v.get().unwrap()),
+            VariantClass::Int32 => visitor.visit_i32(v.get().unwrap()),
+            VariantClass::Uint32 => visitor.visit_u32(v.get().unwrap()),
+            VariantClass::Int64 => visitor.visit_i64(v.get().unwrap()),
+            VariantClass::Uint64 => visitor.visit_u64(v.get().unwrap()),
+            VariantClass::Double => visitor.visit_f64(v.get().unwrap()),
+            VariantClass::String | VariantClass::ObjectPath | VariantClass::Signature => {
+                visitor.visit_string(self.str()?)
+            }
+            VariantClass::Variant => Self(v.as_variant().unwrap()).deserialize_any(visitor),
+            VariantClass::Maybe => match v.as_maybe() {
+                Some(v) => visitor.visit_some(Self(v)),
+                None => visitor.visit_none(),
+            },
+            VariantClass::Array if v.type_().element().is_dict_entry() => {
+                visitor.visit_map(MapDeserializer {
+                    len: v.n_children(),
+                    variant: self.0,
+                    index: 0,
+                })
+            }
+            VariantClass::Array => visitor.visit_seq(SeqDeserializer::new(self.0)),
+            VariantClass::Tuple if v.n_children() == 0 => visitor.visit_unit(),
+            VariantClass::Tuple => visitor.visit_seq(SeqDeserializer::new(self.0)),
+            _ => Err(Error::Unsupported(format!("variant type '{}'", v.type_()))),
+        }
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.classify() {
+            VariantClass::Maybe => match self.0.as_maybe() {
+                Some(v) => visitor.visit_some(Self(v)),
+                None => visitor.visit_none(),
+            },
+            _ => Err(Error::mismatch(self.0.type_().as_str(), "m*")),
+        }
+    }
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.fixed_array::<u8>() {
+            Ok(bytes) => visitor.visit_bytes(bytes),
+            Err(_) => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0.type_().as_str() {
+            "s" => {
+                let variant: StringDeserializer<Error> = self.str()?.into_deserializer();
+                visitor.visit_enum(variant)
+            }
+            "(sv)" => visitor.visit_enum(EnumDeserializer {
+                variant: Self(self.0.child_value(0)).str()?,
+                payload: self.0.child_value(1).as_variant().unwrap(),
+            }),
+            _ => Err(Error::mismatch(self.0.type_().as_str(), "(sv)")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqDeserializer {
+    variant: Variant,
+    index: usize,
+    len: usize,
+}
+
+impl SeqDeserializer {
+    fn new(variant: Variant) -> Self {
+        Self {
+            len: variant.n_children(),
+            variant,
+            index: 0,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let child = self.variant.child_value(self.index);
+        self.index += 1;
+        seed.deserialize(Deserializer(child)).map(Some)
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+struct MapDeserializer {
+    variant: Variant,
+    index: usize,
+    len: usize,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let key = self.variant.child_value(self.index).child_value(0);
+        seed.deserialize(Deserializer(key)).map(Some)
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self.variant.child_value(self.index).child_value(1);
+        self.index += 1;
+        seed.deserialize(Deserializer(value))
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    payload: Variant,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = Deserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer), Error> {
+        let variant: StringDeserializer<Error> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, Deserializer(self.payload)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Deserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
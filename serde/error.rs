@@ -0,0 +1,81 @@
+use glib::variant::VariantTypeMismatchError;
+use std::{fmt::Display, num::TryFromIntError};
+
+/// An error from converting between Rust values and [`glib::Variant`] with [`to_variant`],
+/// [`from_variant`] or [`SerdeVariantType`].
+///
+/// [`to_variant`]: crate::to_variant
+/// [`from_variant`]: crate::from_variant
+/// [`SerdeVariantType`]: crate::SerdeVariantType
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A value did not have the variant type it was written to or read from.
+    Mismatch(VariantTypeMismatchError),
+    /// An integer did not fit in the variant type it was written to.
+    Int(TryFromIntError),
+    /// The Rust type has no static variant type.
+    Unsupported(String),
+    /// An error raised by a `Serialize` or `Deserialize` implementation.
+    Custom(String),
+}
+
+impl Error {
+    pub(crate) fn mismatch(actual: &str, expected: &str) -> Self {
+        Self::Mismatch(VariantTypeMismatchError::new(
+            glib::VariantTy::new(actual).unwrap().to_owned(),
+            glib::VariantTy::new(expected).unwrap().to_owned(),
+        ))
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mismatch(e) => e.fmt(f),
+            Self::Int(e) => e.fmt(f),
+            Self::Unsupported(e) => write!(f, "Type not supported: {}", e),
+            Self::Custom(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<VariantTypeMismatchError> for Error {
+    fn from(e: VariantTypeMismatchError) -> Self {
+        Self::Mismatch(e)
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(e: TryFromIntError) -> Self {
+        Self::Int(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Mismatch(e) => Some(e),
+            Self::Int(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Error::Custom(msg.to_string())
+    }
+}
@@ -22,6 +22,16 @@ macro_rules! declare_optional {
     };
 }
 
+mod de;
+mod error;
+mod ser;
+mod trace;
+
+pub use de::from_variant;
+pub use error::Error;
+pub use ser::to_variant;
+pub use trace::{trace_variant_type, SerdeVariantType};
+
 pub mod glib;
 
 #[cfg(feature = "use_cairo")]
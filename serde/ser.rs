@@ -0,0 +1,473 @@
+use crate::{Error, SerdeVariantType};
+use glib::{ffi, once_cell::sync::Lazy, translate::*, ToVariant, Variant, VariantTy, VariantType};
+use serde::ser::{self, Serialize};
+use std::{any::TypeId, collections::HashMap, sync::Mutex};
+
+/// Serializes `value` into a [`glib::Variant`].
+///
+/// The variant has the type given by [`SerdeVariantType`], so empty sequences and `None` values
+/// are written with their full type. The type is looked up once per Rust type and cached. Enum
+/// payloads are boxed in a `v`, so their types are taken from the data instead.
+pub fn to_variant<T: Serialize + SerdeVariantType>(value: &T) -> Result<Variant, Error> {
+    let ty = variant_type::<T>()?;
+    value.serialize(Serializer { ty: Some(&ty) })
+}
+
+fn variant_type<T: SerdeVariantType>() -> Result<VariantType, Error> {
+    static TYPES: Lazy<Mutex<HashMap<TypeId, VariantType>>> = Lazy::new(Default::default);
+
+    let id = TypeId::of::<T>();
+    if let Some(ty) = TYPES.lock().unwrap().get(&id) {
+        return Ok(ty.clone());
+    }
+    let ty = T::serde_variant_type()?;
+    if !ty.is_definite() {
+        return Err(Error::Unsupported(format!(
+            "variant type '{}' is not definite",
+            ty
+        )));
+    }
+    TYPES.lock().unwrap().insert(id, ty.clone());
+    Ok(ty)
+}
+
+#[derive(Clone, Copy)]
+struct Serializer<'t> {
+    /// The type to write, or `None` to take it from the data.
+    ty: Option<&'t VariantTy>,
+}
+
+impl<'t> Serializer<'t> {
+    fn check(&self, actual: &str) -> Result<(), Error> {
+        match self.ty {
+            Some(ty) if ty.as_str() != actual => Err(Error::mismatch(actual, ty.as_str())),
+            _ => Ok(()),
+        }
+    }
+    fn integer(self, v: i128, natural: &str) -> Result<Variant, Error> {
+        Ok(match self.ty.map_or(natural, VariantTy::as_str) {
+            "y" => u8::try_from(v)?.to_variant(),
+            "n" => i16::try_from(v)?.to_variant(),
+            "q" => u16::try_from(v)?.to_variant(),
+            "i" => i32::try_from(v)?.to_variant(),
+            "u" => u32::try_from(v)?.to_variant(),
+            "x" => i64::try_from(v)?.to_variant(),
+            "t" => u64::try_from(v)?.to_variant(),
+            "d" => (v as f64).to_variant(),
+            ty => return Err(Error::mismatch(natural, ty)),
+        })
+    }
+    /// Returns the type of the elements of an array or maybe, checked with `is`.
+    fn element(
+        self,
+        is: fn(&VariantTy) -> bool,
+        expected: &str,
+    ) -> Result<Option<&'t VariantTy>, Error> {
+        match self.ty {
+            Some(ty) if is(ty) => Ok(Some(ty.element())),
+            Some(ty) => Err(Error::mismatch(expected, ty.as_str())),
+            None => Ok(None),
+        }
+    }
+    fn tuple(
+        self,
+        len: usize,
+        variant: Option<&'static str>,
+    ) -> Result<TupleSerializer<'t>, Error> {
+        let next = match self.ty {
+            Some(ty) if ty.is_tuple() && ty.n_items() == len => ty.first(),
+            Some(ty) => return Err(Error::mismatch("r", ty.as_str())),
+            None => None,
+        };
+        Ok(TupleSerializer {
+            typed: self.ty.is_some(),
+            next,
+            items: Vec::with_capacity(len),
+            variant,
+        })
+    }
+    fn enum_payload(self, len: usize, variant: &'static str) -> Result<TupleSerializer<'t>, Error> {
+        self.check("(sv)")?;
+        Serializer { ty: None }.tuple(len, Some(variant))
+    }
+}
+
+fn enum_variant(variant: &str, payload: &Variant) -> Variant {
+    Variant::tuple_from_iter([variant.to_variant(), Variant::from_variant(payload)])
+}
+
+/// Builds an array of items whose types were taken from the data. Items are boxed into `av` when
+/// the array is empty or their types differ.
+fn untyped_array(items: Vec<Variant>) -> Variant {
+    match items.first().map(|item| item.type_().to_owned()) {
+        Some(ty) if items.iter().all(|item| item.type_() == &*ty) => {
+            Variant::array_from_iter_with_type(&ty, items)
+        }
+        _ => Variant::array_from_iter_with_type(
+            VariantTy::VARIANT,
+            items.iter().map(Variant::from_variant),
+        ),
+    }
+}
+
+/// Builds a dictionary of entries whose types were taken from the data. Values are boxed into
+/// `v` when their types differ, and an empty dictionary is written as `a{sv}`.
+fn untyped_dict(items: Vec<(Variant, Variant)>) -> Result<Variant, Error> {
+    let boxed = items.windows(2).any(|w| w[0].1.type_() != w[1].1.type_());
+    let mut ty = VariantType::new("{sv}").unwrap();
+    let mut entries = Vec::with_capacity(items.len());
+    for (index, (key, value)) in items.iter().enumerate() {
+        if !key.type_().is_basic() {
+            return Err(Error::Unsupported(format!(
+                "dictionary key of type '{}'",
+                key.type_()
+            )));
+        }
+        let entry = if boxed {
+            Variant::from_dict_entry(key, &Variant::from_variant(value))
+        } else {
+            Variant::from_dict_entry(key, value)
+        };
+        if index == 0 {
+            ty = entry.type_().to_owned();
+        } else if entry.type_() != &*ty {
+            return Err(Error::mismatch(entry.type_().as_str(), ty.as_str()));
+        }
+        entries.push(entry);
+    }
+    Ok(Variant::array_from_iter_with_type(&ty, entries))
+}
+
+impl<'t> ser::Serializer for Serializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer<'t>;
+    type SerializeTuple = TupleSerializer<'t>;
+    type SerializeTupleStruct = TupleSerializer<'t>;
+    type SerializeTupleVariant = TupleSerializer<'t>;
+    type SerializeMap = MapSerializer<'t>;
+    type SerializeStruct = TupleSerializer<'t>;
+    type SerializeStructVariant = TupleSerializer<'t>;
+
+    fn serialize_bool(self, v: bool) -> Result<Variant, Error> {
+        self.check("b")?;
+        Ok(v.to_variant())
+    }
+    fn serialize_i8(self, v: i8) -> Result<Variant, Error> {
+        self.integer(v.into(), "n")
+    }
+    fn serialize_i16(self, v: i16) -> Result<Variant, Error> {
+        self.integer(v.into(), "n")
+    }
+    fn serialize_i32(self, v: i32) -> Result<Variant, Error> {
+        self.integer(v.into(), "i")
+    }
+    fn serialize_i64(self, v: i64) -> Result<Variant, Error> {
+        self.integer(v.into(), "x")
+    }
+    fn serialize_u8(self, v: u8) -> Result<Variant, Error> {
+        self.integer(v.into(), "y")
+    }
+    fn serialize_u16(self, v: u16) -> Result<Variant, Error> {
+        self.integer(v.into(), "q")
+    }
+    fn serialize_u32(self, v: u32) -> Result<Variant, Error> {
+        self.integer(v.into(), "u")
+    }
+    fn serialize_u64(self, v: u64) -> Result<Variant, Error> {
+        self.integer(v.into(), "t")
+    }
+    fn serialize_f32(self, v: f32) -> Result<Variant, Error> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_f64(self, v: f64) -> Result<Variant, Error> {
+        self.check("d")?;
+        Ok(v.to_variant())
+    }
+    fn serialize_char(self, v: char) -> Result<Variant, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+    fn serialize_str(self, v: &str) -> Result<Variant, Error> {
+        unsafe {
+            match self.ty.map_or("s", VariantTy::as_str) {
+                "s" => Ok(v.to_variant()),
+                "o" if ffi::g_variant_is_object_path(v.to_glib_none().0) != 0 => Ok(
+                    from_glib_none(ffi::g_variant_new_object_path(v.to_glib_none().0)),
+                ),
+                "g" if ffi::g_variant_is_signature(v.to_glib_none().0) != 0 => Ok(from_glib_none(
+                    ffi::g_variant_new_signature(v.to_glib_none().0),
+                )),
+                ty => Err(Error::mismatch("s", ty)),
+            }
+        }
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Variant, Error> {
+        self.check("ay")?;
+        Ok(Variant::array_from_fixed_array(v))
+    }
+    fn serialize_none(self) -> Result<Variant, Error> {
+        let element = self.element(VariantTy::is_maybe, "m*")?;
+        Ok(Variant::from_none(element.unwrap_or(VariantTy::VARIANT)))
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Variant, Error> {
+        let ty = self.element(VariantTy::is_maybe, "m*")?;
+        let value = value.serialize(Serializer { ty })?;
+        Ok(Variant::from_some(&value))
+    }
+    fn serialize_unit(self) -> Result<Variant, Error> {
+        self.check("()")?;
+        Ok(().to_variant())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Variant, Error> {
+        self.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Variant, Error> {
+        match self.ty.map_or("s", VariantTy::as_str) {
+            "s" => Ok(variant.to_variant()),
+            "(sv)" => Ok(enum_variant(variant, &().to_variant())),
+            ty => Err(Error::mismatch("(sv)", ty)),
+        }
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Variant, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Variant, Error> {
+        self.check("(sv)")?;
+        let payload = value.serialize(Serializer { ty: None })?;
+        Ok(enum_variant(variant, &payload))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer<'t>, Error> {
+        Ok(SeqSerializer {
+            element: self.element(VariantTy::is_array, "a*")?,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<TupleSerializer<'t>, Error> {
+        self.tuple(len, None)
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'t>, Error> {
+        self.tuple(len, None)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'t>, Error> {
+        self.enum_payload(len, variant)
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer<'t>, Error> {
+        let entry = match self.ty {
+            Some(ty) if ty.is_array() && ty.element().is_dict_entry() => Some(ty.element()),
+            Some(ty) => return Err(Error::mismatch("a{?*}", ty.as_str())),
+            None => None,
+        };
+        Ok(MapSerializer {
+            entry,
+            pending: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'t>, Error> {
+        self.tuple(len, None)
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'t>, Error> {
+        self.enum_payload(len, variant)
+    }
+}
+
+struct SeqSerializer<'t> {
+    element: Option<&'t VariantTy>,
+    items: Vec<Variant>,
+}
+
+impl<'t> ser::SerializeSeq for SeqSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let ty = self.element;
+        self.items.push(value.serialize(Serializer { ty })?);
+        Ok(())
+    }
+    fn end(self) -> Result<Variant, Error> {
+        Ok(match self.element {
+            Some(ty) => Variant::array_from_iter_with_type(ty, self.items),
+            None => untyped_array(self.items),
+        })
+    }
+}
+
+struct TupleSerializer<'t> {
+    typed: bool,
+    next: Option<&'t VariantTy>,
+    items: Vec<Variant>,
+    variant: Option<&'static str>,
+}
+
+impl<'t> TupleSerializer<'t> {
+    fn add<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        if !self.typed {
+            self.items.push(value.serialize(Serializer { ty: None })?);
+            return Ok(());
+        }
+        let ty = self
+            .next
+            .ok_or_else(|| Error::Custom("too many tuple items".into()))?;
+        self.items
+            .push(value.serialize(Serializer { ty: Some(ty) })?);
+        self.next = ty.next();
+        Ok(())
+    }
+    fn finish(self) -> Result<Variant, Error> {
+        if self.next.is_some() {
+            return Err(Error::Custom("too few tuple items".into()));
+        }
+        let tuple = Variant::tuple_from_iter(self.items);
+        Ok(match self.variant {
+            Some(variant) => enum_variant(variant, &tuple),
+            None => tuple,
+        })
+    }
+}
+
+impl<'t> ser::SerializeTuple for TupleSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.add(value)
+    }
+    fn end(self) -> Result<Variant, Error> {
+        self.finish()
+    }
+}
+
+impl<'t> ser::SerializeTupleStruct for TupleSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.add(value)
+    }
+    fn end(self) -> Result<Variant, Error> {
+        self.finish()
+    }
+}
+
+impl<'t> ser::SerializeTupleVariant for TupleSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.add(value)
+    }
+    fn end(self) -> Result<Variant, Error> {
+        self.finish()
+    }
+}
+
+impl<'t> ser::SerializeStruct for TupleSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.add(value)
+    }
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        Err(Error::Custom(format!("field `{}` can't be skipped", key)))
+    }
+    fn end(self) -> Result<Variant, Error> {
+        self.finish()
+    }
+}
+
+impl<'t> ser::SerializeStructVariant for TupleSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.add(value)
+    }
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        Err(Error::Custom(format!("field `{}` can't be skipped", key)))
+    }
+    fn end(self) -> Result<Variant, Error> {
+        self.finish()
+    }
+}
+
+struct MapSerializer<'t> {
+    entry: Option<&'t VariantTy>,
+    pending: Option<Variant>,
+    items: Vec<(Variant, Variant)>,
+}
+
+impl<'t> ser::SerializeMap for MapSerializer<'t> {
+    type Ok = Variant;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let ty = self.entry.map(VariantTy::key);
+        self.pending = Some(key.serialize(Serializer { ty })?);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .pending
+            .take()
+            .ok_or_else(|| Error::Custom("map value without a key".into()))?;
+        let ty = self.entry.map(VariantTy::value);
+        self.items.push((key, value.serialize(Serializer { ty })?));
+        Ok(())
+    }
+    fn end(self) -> Result<Variant, Error> {
+        let entry = match self.entry {
+            Some(entry) => entry,
+            None => return untyped_dict(self.items),
+        };
+        let items = self
+            .items
+            .iter()
+            .map(|(key, value)| Variant::from_dict_entry(key, value));
+        Ok(Variant::array_from_iter_with_type(entry, items))
+    }
+}
@@ -0,0 +1,411 @@
+use crate::Error;
+use glib::VariantType;
+use serde::de::{
+    value::U32Deserializer, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use std::collections::HashMap;
+
+const MAX_DEPTH: usize = 64;
+const MAX_PASSES: usize = 256;
+
+/// The static [`glib::VariantType`] that [`to_variant`](crate::to_variant) writes a type as.
+///
+/// Types that derive `Deserialize` can implement this with [`trace_variant_type`]. Types whose
+/// `Deserialize` implementation can't be traced, such as ones that validate their input or don't
+/// implement it at all, return the type written out instead.
+///
+/// ```
+/// use gobject_serde::{Error, SerdeVariantType};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl SerdeVariantType for Point {
+///     fn serde_variant_type() -> Result<glib::VariantType, Error> {
+///         gobject_serde::trace_variant_type::<Self>()
+///     }
+/// }
+///
+/// let variant = gobject_serde::to_variant(&Point { x: 1, y: 2 }).unwrap();
+/// assert_eq!(variant.type_().as_str(), "(ii)");
+/// ```
+///
+/// Rust types map to variant types as follows:
+///
+/// - `bool`, `u8`, `i16`, `u16`, `i32`, `u32`, `i64`, `u64` and `f64` map to `b`, `y`, `n`, `q`,
+///   `i`, `u`, `x`, `t` and `d`. `i8` is widened to `n` and `f32` to `d`.
+/// - `char` and strings map to `s`. Byte buffers map to `ay`.
+/// - `Option<T>` maps to `mT`.
+/// - Sequences map to arrays and maps to arrays of dictionary entries.
+/// - `()`, unit structs, tuples, tuple structs and structs map to tuples.
+/// - Newtype structs are transparent.
+/// - Enums that only have unit variants map to `s`, holding the variant name. Other enums map to
+///   `(sv)`, holding the variant name and a variant containing the payload, or `()` for unit
+///   variants.
+pub trait SerdeVariantType: 'static {
+    fn serde_variant_type() -> Result<VariantType, Error>;
+}
+
+/// Derives the variant type of `T` by tracing its [`Deserialize`](serde::Deserialize)
+/// implementation with placeholder values.
+///
+/// Tracing fails for types that call `deserialize_any`, such as untagged enums or flattened
+/// structs, for recursive structs, and for types that reject the placeholder values.
+pub fn trace_variant_type<T: DeserializeOwned>() -> Result<VariantType, Error> {
+    trace::<T>()
+}
+
+#[derive(Debug)]
+struct EnumInfo {
+    payloads: Vec<Payload>,
+    unit_only: Option<bool>,
+    cursor: usize,
+}
+
+impl EnumInfo {
+    fn variant_type(&self) -> &'static str {
+        match self.unit_only {
+            Some(true) => "s",
+            _ => "(sv)",
+        }
+    }
+    fn is_traced(&self) -> bool {
+        !self.payloads.iter().any(|p| matches!(p, Payload::Untraced))
+    }
+}
+
+#[derive(Debug)]
+enum Payload {
+    Untraced,
+    Unit,
+    Data,
+}
+
+#[derive(Default)]
+struct State {
+    out: String,
+    enums: HashMap<&'static str, EnumInfo>,
+    stack: Vec<&'static str>,
+    depth: usize,
+}
+
+/// Traces `T`, returning its variant type.
+///
+/// Each pass takes one variant of every enum it reaches, so passes are repeated until every
+/// variant has been seen and it is known which enums only have unit variants. A last pass then
+/// writes the type. Payloads are boxed in a `v`, so their own types don't matter.
+fn trace<T: DeserializeOwned>() -> Result<VariantType, Error> {
+    let mut state = State::default();
+    state.explore::<T>()?;
+    for info in state.enums.values_mut() {
+        info.unit_only = Some(info.payloads.iter().all(|p| matches!(p, Payload::Unit)));
+    }
+    state.pass::<T>()?;
+    VariantType::new(&state.out)
+        .map_err(|_| Error::Unsupported(format!("invalid variant type '{}'", state.out)))
+}
+
+impl State {
+    fn pass<T: DeserializeOwned>(&mut self) -> Result<(), Error> {
+        self.out.clear();
+        self.stack.clear();
+        self.depth = 0;
+        T::deserialize(Tracer(self)).map(|_| ())
+    }
+    fn explore<T: DeserializeOwned>(&mut self) -> Result<(), Error> {
+        for _ in 0..MAX_PASSES {
+            self.pass::<T>()?;
+            if self.enums.values().all(EnumInfo::is_traced) {
+                return Ok(());
+            }
+        }
+        Err(Error::Unsupported(
+            "could not reach every enum variant".into(),
+        ))
+    }
+    fn nested<R>(&mut self, f: impl FnOnce(Tracer<'_>) -> Result<R, Error>) -> Result<R, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::Unsupported(
+                "recursive types have no static type".into(),
+            ));
+        }
+        self.depth += 1;
+        let ret = f(Tracer(self));
+        self.depth -= 1;
+        ret
+    }
+    /// Picks the next variant of `name` to trace, preferring ones not seen yet. Enums that are
+    /// already being traced prefer unit variants, to stop recursive enums from looping.
+    fn choose_variant(&mut self, name: &'static str) -> usize {
+        let recursive = self.stack.contains(&name);
+        let info = self.enums.get_mut(name).unwrap();
+        let untraced = info
+            .payloads
+            .iter()
+            .position(|p| matches!(p, Payload::Untraced));
+        let unit = info
+            .payloads
+            .iter()
+            .position(|p| matches!(p, Payload::Unit));
+        match (untraced, unit) {
+            (_, Some(unit)) if recursive => unit,
+            (Some(index), _) => index,
+            _ => {
+                info.cursor = (info.cursor + 1) % info.payloads.len();
+                info.cursor
+            }
+        }
+    }
+}
+
+struct Tracer<'s>(&'s mut State);
+
+impl<'s> Tracer<'s> {
+    fn visit_tuple<'de, V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.0.out.push('(');
+        let value = visitor.visit_seq(SeqTracer {
+            state: &mut *self.0,
+            remaining: len,
+        })?;
+        self.0.out.push(')');
+        Ok(value)
+    }
+}
+
+macro_rules! trace_primitive {
+    ($de:lifetime, $($method:ident => $ty:literal, $visit:ident($($value:expr)?);)*) => {
+        $(
+            fn $method<V: Visitor<$de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.0.out.push_str($ty);
+                visitor.$visit($($value)?)
+            }
+        )*
+    };
+}
+
+impl<'s, 'de> Deserializer<'de> for Tracer<'s> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::Unsupported(
+            "self-describing types have no static type".into(),
+        ))
+    }
+    trace_primitive! {
+        'de,
+        deserialize_bool => "b", visit_bool(false);
+        deserialize_i8 => "n", visit_i8(0);
+        deserialize_i16 => "n", visit_i16(0);
+        deserialize_i32 => "i", visit_i32(0);
+        deserialize_i64 => "x", visit_i64(0);
+        deserialize_u8 => "y", visit_u8(0);
+        deserialize_u16 => "q", visit_u16(0);
+        deserialize_u32 => "u", visit_u32(0);
+        deserialize_u64 => "t", visit_u64(0);
+        deserialize_f32 => "d", visit_f32(0.);
+        deserialize_f64 => "d", visit_f64(0.);
+        deserialize_char => "s", visit_char('\0');
+        deserialize_str => "s", visit_str("");
+        deserialize_string => "s", visit_str("");
+        deserialize_bytes => "ay", visit_bytes(&[]);
+        deserialize_byte_buf => "ay", visit_bytes(&[]);
+        deserialize_unit => "()", visit_unit();
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.0.out.push('m');
+        self.0.nested(|tracer| visitor.visit_some(tracer))
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.nested(|tracer| visitor.visit_newtype_struct(tracer))
+    }
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.0.out.push('a');
+        visitor.visit_seq(SeqTracer {
+            state: self.0,
+            remaining: 1,
+        })
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.visit_tuple(len, visitor)
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.visit_tuple(len, visitor)
+    }
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.0.out.push_str("a{");
+        let value = visitor.visit_map(MapTracer {
+            state: &mut *self.0,
+            remaining: 1,
+        })?;
+        self.0.out.push('}');
+        Ok(value)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.visit_tuple(fields.len(), visitor)
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if variants.is_empty() {
+            return Err(Error::Unsupported(format!(
+                "enum `{}` has no variants",
+                name
+            )));
+        }
+        let state = self.0;
+        let info = state.enums.entry(name).or_insert_with(|| EnumInfo {
+            payloads: variants.iter().map(|_| Payload::Untraced).collect(),
+            unit_only: None,
+            cursor: 0,
+        });
+        state.out.push_str(info.variant_type());
+        let index = state.choose_variant(name);
+        let start = state.out.len();
+        state.stack.push(name);
+        let value = visitor.visit_enum(EnumTracer {
+            state: &mut *state,
+            name,
+            index,
+        });
+        state.stack.pop();
+        state.out.truncate(start);
+        value
+    }
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(0)
+    }
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqTracer<'s> {
+    state: &'s mut State,
+    remaining: usize,
+}
+
+impl<'s, 'de> SeqAccess<'de> for SeqTracer<'s> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.state
+            .nested(|tracer| seed.deserialize(tracer))
+            .map(Some)
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct MapTracer<'s> {
+    state: &'s mut State,
+    remaining: usize,
+}
+
+impl<'s, 'de> MapAccess<'de> for MapTracer<'s> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.state
+            .nested(|tracer| seed.deserialize(tracer))
+            .map(Some)
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        self.state.nested(|tracer| seed.deserialize(tracer))
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct EnumTracer<'s> {
+    state: &'s mut State,
+    name: &'static str,
+    index: usize,
+}
+
+impl<'s> EnumTracer<'s> {
+    fn record(&mut self, payload: Payload) {
+        let info = self.state.enums.get_mut(self.name).unwrap();
+        info.payloads[self.index] = payload;
+    }
+    fn data<R>(mut self, f: impl FnOnce(Tracer<'_>) -> Result<R, Error>) -> Result<R, Error> {
+        let value = self.state.nested(f)?;
+        self.record(Payload::Data);
+        Ok(value)
+    }
+}
+
+impl<'s, 'de> EnumAccess<'de> for EnumTracer<'s> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let index: U32Deserializer<Error> = (self.index as u32).into_deserializer();
+        Ok((seed.deserialize(index)?, self))
+    }
+}
+
+impl<'s, 'de> VariantAccess<'de> for EnumTracer<'s> {
+    type Error = Error;
+
+    fn unit_variant(mut self) -> Result<(), Error> {
+        self.record(Payload::Unit);
+        Ok(())
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        self.data(|tracer| seed.deserialize(tracer))
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.data(|tracer| tracer.visit_tuple(len, visitor))
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.data(|tracer| tracer.visit_tuple(fields.len(), visitor))
+    }
+}
@@ -0,0 +1,114 @@
+use glib::prelude::*;
+use std::cell::RefCell;
+
+/// Calls `build` to create a binding from the object stored in `source_property` of `obj`,
+/// replacing the binding each time that property changes.
+#[doc(hidden)]
+pub fn bind_property_from<O, F>(obj: &O, source_property: &'static str, build: F)
+where
+    O: IsA<glib::Object>,
+    F: Fn(&O, &glib::Object) -> glib::Binding + 'static,
+{
+    let binding = RefCell::new(None::<glib::Binding>);
+    let update = move |obj: &O| {
+        if let Some(binding) = binding.take() {
+            binding.unbind();
+        }
+        let source = obj.property::<Option<glib::Object>>(source_property);
+        binding.replace(source.map(|source| build(obj, &source)));
+    };
+    update(obj);
+    obj.connect_notify_local(Some(source_property), move |obj, _| update(obj));
+}
+
+/// Re-emits `source_signal` from the object stored in `source_property` of `obj` as `signal` on
+/// `obj`, moving the connection to the new object each time that property changes.
+#[doc(hidden)]
+pub fn forward_signal_from<O>(
+    obj: &O,
+    source_property: &'static str,
+    source_signal: &'static str,
+    signal: &'static str,
+) where
+    O: IsA<glib::Object>,
+{
+    let signal_id = glib::subclass::SignalId::lookup(signal, obj.type_())
+        .unwrap_or_else(|| panic!("Signal `{}` not registered", signal));
+    let query = signal_id.query();
+    let detailed = query.flags().contains(glib::SignalFlags::DETAILED);
+    let guard = RefCell::new(None::<crate::SignalGuard<glib::Object>>);
+    let update = move |obj: &O| {
+        guard.take();
+        let source = obj.property::<Option<glib::Object>>(source_property);
+        let source = match source {
+            Some(source) => source,
+            None => return,
+        };
+        if !check_forward_signature(&source, source_signal, &query) {
+            return;
+        }
+        let target = obj.upcast_ref::<glib::Object>().downgrade();
+        let handler_id = source.connect_local(source_signal, false, move |args| {
+            let target = target.upgrade()?;
+            let detail = if detailed {
+                let source = args[0].get::<glib::Object>().ok()?;
+                unsafe {
+                    let hint = glib::gobject_ffi::g_signal_get_invocation_hint(source.as_ptr());
+                    (!hint.is_null() && (*hint).detail != 0)
+                        .then(|| glib::translate::from_glib((*hint).detail))
+                }
+            } else {
+                None
+            };
+            match detail {
+                Some(detail) => target.emit_with_details_and_values(signal_id, detail, &args[1..]),
+                None => target.emit_with_values(signal_id, &args[1..]),
+            }
+        });
+        guard.replace(Some(crate::SignalGuard::new(&source, handler_id)));
+    };
+    update(obj);
+    obj.connect_notify_local(Some(source_property), move |obj, _| update(obj));
+}
+
+/// Checks that the arguments of `source_signal` on `source` can be passed to the signal of
+/// `target`, and its return value passed back. Logs a critical warning if not.
+fn check_forward_signature(
+    source: &glib::Object,
+    source_signal: &str,
+    target: &glib::subclass::SignalQuery,
+) -> bool {
+    let query = match glib::subclass::SignalId::lookup(source_signal, source.type_()) {
+        Some(signal_id) => signal_id.query(),
+        None => {
+            glib::g_critical!(
+                crate::LOG_DOMAIN,
+                "Signal `{}` not found on `{}`",
+                source_signal,
+                source.type_()
+            );
+            return false;
+        }
+    };
+    let compatible = query.param_types().len() == target.param_types().len()
+        && query
+            .param_types()
+            .iter()
+            .zip(target.param_types())
+            .all(|(s, t)| s.type_().is_a(t.type_()))
+        && target
+            .return_type()
+            .type_()
+            .is_a(query.return_type().type_());
+    if !compatible {
+        glib::g_critical!(
+            crate::LOG_DOMAIN,
+            "Signal `{}` of `{}` can't be forwarded to `{}` of `{}`: signatures don't match",
+            source_signal,
+            source.type_(),
+            target.signal_name(),
+            target.type_()
+        );
+    }
+    compatible
+}
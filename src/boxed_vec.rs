@@ -0,0 +1,132 @@
+use glib::{
+    gobject_ffi,
+    once_cell::sync::OnceCell,
+    translate::{FromGlib, ToGlibPtr, ToGlibPtrMut},
+    value::{FromValue, GenericValueTypeOrNoneChecker, ToValue, ValueType, ValueTypeChecker},
+    StaticType, Type, Value,
+};
+
+/// A [`Vec`] stored in a [`glib::Value`] as a boxed type.
+///
+/// Signals use this for `Vec<T>` and `&[T]` arguments that have no GLib type of their own. A boxed
+/// type is registered for each `T` the first time it is used. `&[T]` arguments are copied into a
+/// new vector when emitted, and signal handlers borrow that vector from the value.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoxedVec<T>(pub Vec<T>);
+
+type SliceError<T> = <GenericValueTypeOrNoneChecker<BoxedVec<T>> as ValueTypeChecker>::Error;
+
+impl<T: Clone + 'static> BoxedVec<T> {
+    /// Borrows the slice stored in `value`.
+    pub fn slice_from_value(value: &Value) -> Result<&[T], SliceError<T>> {
+        GenericValueTypeOrNoneChecker::<Self>::check(value)?;
+        unsafe {
+            let ptr = gobject_ffi::g_value_get_boxed(value.to_glib_none().0) as *const Vec<T>;
+            Ok((*ptr).as_slice())
+        }
+    }
+    /// Copies `slice` into a new value.
+    pub fn slice_to_value(slice: &[T]) -> Value {
+        Self(slice.to_vec()).into()
+    }
+}
+
+impl<T> From<Vec<T>> for BoxedVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self(vec)
+    }
+}
+
+impl<T> From<BoxedVec<T>> for Vec<T> {
+    fn from(vec: BoxedVec<T>) -> Self {
+        vec.0
+    }
+}
+
+impl<T> std::ops::Deref for BoxedVec<T> {
+    type Target = Vec<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for BoxedVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+struct BoxedVecType(OnceCell<Type>);
+
+fn register_boxed_vec<T: Clone + 'static>() -> Type {
+    let base = std::any::type_name::<T>()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let mut name = format!("GobjectVec-{}", base);
+    let mut index = 0;
+    while Type::from_name(&name).is_some() {
+        index += 1;
+        name = format!("GobjectVec-{}-{}", base, index);
+    }
+    unsafe {
+        Type::from_glib(gobject_ffi::g_boxed_type_register_static(
+            name.to_glib_none().0,
+            Some(boxed_vec_copy::<T>),
+            Some(boxed_vec_free::<T>),
+        ))
+    }
+}
+
+unsafe extern "C" fn boxed_vec_copy<T: Clone>(
+    ptr: glib::ffi::gconstpointer,
+) -> glib::ffi::gpointer {
+    let vec = &*(ptr as *const Vec<T>);
+    Box::into_raw(Box::new(vec.clone())) as glib::ffi::gpointer
+}
+
+unsafe extern "C" fn boxed_vec_free<T>(ptr: glib::ffi::gpointer) {
+    drop(Box::from_raw(ptr as *mut Vec<T>));
+}
+
+impl<T: Clone + 'static> StaticType for BoxedVec<T> {
+    fn static_type() -> Type {
+        let cell = crate::generic_static::<T, BoxedVecType>(|| BoxedVecType(OnceCell::new()));
+        *cell.0.get_or_init(register_boxed_vec::<T>)
+    }
+}
+
+impl<T: Clone + 'static> ValueType for BoxedVec<T> {
+    type Type = Self;
+}
+
+unsafe impl<'a, T: Clone + 'static> FromValue<'a> for BoxedVec<T> {
+    type Checker = GenericValueTypeOrNoneChecker<Self>;
+
+    unsafe fn from_value(value: &'a Value) -> Self {
+        let ptr = gobject_ffi::g_value_get_boxed(value.to_glib_none().0) as *const Vec<T>;
+        Self((*ptr).clone())
+    }
+}
+
+impl<T: Clone + 'static> ToValue for BoxedVec<T> {
+    fn to_value(&self) -> Value {
+        Self::slice_to_value(&self.0)
+    }
+    fn value_type(&self) -> Type {
+        Self::static_type()
+    }
+}
+
+impl<T: Clone + 'static> From<BoxedVec<T>> for Value {
+    fn from(vec: BoxedVec<T>) -> Self {
+        unsafe {
+            let mut value = Value::from_type(BoxedVec::<T>::static_type());
+            gobject_ffi::g_value_take_boxed(
+                value.to_glib_none_mut().0,
+                Box::into_raw(Box::new(vec.0)) as glib::ffi::gconstpointer,
+            );
+            value
+        }
+    }
+}
@@ -151,3 +151,36 @@ where
 {
     type ParamSpec = T::ParamSpec;
 }
+
+/// Builder shim used as the [`ParamSpecBuildable::ParamSpec`] of types registered with
+/// [`enum_`](crate::enum_).
+#[doc(hidden)]
+pub struct ParamSpecEnumType<T>(std::marker::PhantomData<T>);
+
+impl<T: glib::StaticType> ParamSpecEnumType<T> {
+    pub fn builder(name: &str) -> glib::ParamSpecEnumBuilder<'_> {
+        glib::ParamSpecEnum::builder(name, T::static_type())
+    }
+}
+
+/// Builder shim used as the [`ParamSpecBuildable::ParamSpec`] of types registered with
+/// [`flags`](crate::flags).
+#[doc(hidden)]
+pub struct ParamSpecFlagsType<T>(std::marker::PhantomData<T>);
+
+impl<T: glib::StaticType> ParamSpecFlagsType<T> {
+    pub fn builder(name: &str) -> glib::ParamSpecFlagsBuilder<'_> {
+        glib::ParamSpecFlags::builder(name, T::static_type())
+    }
+}
+
+/// Builder shim used as the [`ParamSpecBuildable::ParamSpec`] of types registered with
+/// [`boxed`](crate::boxed).
+#[doc(hidden)]
+pub struct ParamSpecBoxedType<T>(std::marker::PhantomData<T>);
+
+impl<T: glib::StaticType> ParamSpecBoxedType<T> {
+    pub fn builder(name: &str) -> glib::ParamSpecBoxedBuilder<'_> {
+        glib::ParamSpecBoxed::builder(name, T::static_type())
+    }
+}
@@ -2,16 +2,22 @@ use crate::{
     OnceCell, ParamSpecBuildable, ParamStore, ParamStoreBorrow, ParamStoreBorrowMut,
     ParamStoreRead, ParamStoreWrite, ParamStoreWriteChanged,
 };
+use arc_swap::ArcSwap;
 use glib::{
     clone::Downgrade,
     value::{
         FromValue, ValueType, ValueTypeChecker, ValueTypeMismatchOrNoneError, ValueTypeOptional,
     },
-    ObjectType, ToValue, Value, WeakRef,
+    ObjectType, StaticType, ToValue, Value, WeakRef,
 };
 use std::{
     cell::RefCell,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
 };
 
 /// A cell holding an `Option<T>`. This should only be used with boxed/object properties using the
@@ -513,3 +519,406 @@ where
         old != value
     }
 }
+
+/// A thread-safe version of [`ConstructCell`] for use in `sync` classes, holding an `Option<T>`
+/// behind a `RwLock`. The [`crate::ParamStoreRead`] implementation will panic if the cell is not
+/// written to at least once.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct SyncConstructCell<T>(RwLock<Option<T>>);
+
+impl<T> SyncConstructCell<T> {
+    pub fn new() -> Self {
+        Self(RwLock::new(None))
+    }
+}
+impl<T> Default for SyncConstructCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> From<T> for SyncConstructCell<T> {
+    fn from(t: T) -> Self {
+        Self(RwLock::new(Some(t)))
+    }
+}
+impl<T> Deref for SyncConstructCell<T> {
+    type Target = RwLock<Option<T>>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Read guard returned from the [`crate::ParamStoreBorrow`] implementation of
+/// [`SyncConstructCell`].
+#[derive(Debug)]
+pub struct SyncConstructRef<'a, T>(RwLockReadGuard<'a, Option<T>>);
+
+impl<'a, T> Deref for SyncConstructRef<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.0
+            .as_ref()
+            .expect("SyncConstructCell borrowed before write")
+    }
+}
+
+/// Write guard returned from the [`crate::ParamStoreBorrowMut`] implementation of
+/// [`SyncConstructCell`].
+#[derive(Debug)]
+pub struct SyncConstructRefMut<'a, T>(RwLockWriteGuard<'a, Option<T>>);
+
+impl<'a, T> Deref for SyncConstructRefMut<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.0
+            .as_ref()
+            .expect("SyncConstructCell borrowed before write")
+    }
+}
+impl<'a, T> DerefMut for SyncConstructRefMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+            .as_mut()
+            .expect("SyncConstructCell borrowed before write")
+    }
+}
+
+impl<T: ParamSpecBuildable> ParamSpecBuildable for SyncConstructCell<T> {
+    type ParamSpec = T::ParamSpec;
+}
+impl<T: ValueType> ParamStore for SyncConstructCell<T> {
+    type Type = T;
+}
+impl<T> ParamStoreRead for SyncConstructCell<T>
+where
+    T: ValueType + Clone,
+{
+    type ReadType = T;
+    fn get_owned(&self) -> Self::ReadType {
+        self.borrow().clone()
+    }
+    fn get_value(&self) -> Value {
+        self.get_owned().to_value()
+    }
+}
+impl<'a, T> ParamStoreBorrow<'a> for SyncConstructCell<T>
+where
+    T: 'a,
+{
+    type BorrowType = SyncConstructRef<'a, T>;
+
+    fn borrow(&'a self) -> Self::BorrowType {
+        SyncConstructRef(self.read().unwrap())
+    }
+}
+impl<'a, T> ParamStoreWrite<'a> for SyncConstructCell<T>
+where
+    T: ValueType,
+{
+    type WriteType = T;
+    fn set_owned(&'a self, value: Self::WriteType) {
+        *self.write().unwrap() = Some(value);
+    }
+}
+impl<'a, T> ParamStoreWriteChanged<'a> for SyncConstructCell<T>
+where
+    T: ValueType + PartialEq,
+{
+    fn set_owned_checked(&'a self, value: Self::WriteType) -> bool {
+        let mut storage = self.write().unwrap();
+        let old = std::mem::replace(storage.deref_mut(), Some(value));
+        old != *storage
+    }
+}
+impl<'a, T> ParamStoreBorrowMut<'a> for SyncConstructCell<T>
+where
+    T: 'a,
+{
+    type BorrowMutType = SyncConstructRefMut<'a, T>;
+
+    fn borrow_mut(&'a self) -> Self::BorrowMutType {
+        SyncConstructRefMut(self.write().unwrap())
+    }
+}
+
+/// A thread-safe version of [`WeakCell`] for use in `sync` classes. Another thread can drop the
+/// last strong reference at any time, so unlike [`WeakCell`], the
+/// [`crate::ParamStoreRead::get_value`] implementation returns a NULL `glib::Value` if upgrading
+/// the weak ref fails. [`crate::ParamStoreRead::get_owned`] will still panic. Writes through the
+/// property setters are serialized so change detection can't race, but calling `set` on the
+/// dereferenced [`WeakRef`] bypasses that.
+#[derive(Debug)]
+pub struct SyncWeakCell<T: ObjectType + Send + Sync>(WeakRef<T>, Mutex<()>);
+
+impl<T: ObjectType + Send + Sync> SyncWeakCell<T> {
+    pub fn new() -> Self {
+        Self(WeakRef::new(), Mutex::new(()))
+    }
+}
+impl<T: ObjectType + Send + Sync> Default for SyncWeakCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: ObjectType + Send + Sync> From<T> for SyncWeakCell<T> {
+    fn from(obj: T) -> Self {
+        let weak = Self::new();
+        weak.set(Some(&obj));
+        weak
+    }
+}
+impl<T: ObjectType + Send + Sync> Deref for SyncWeakCell<T> {
+    type Target = WeakRef<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ParamSpecBuildable + ObjectType + Send + Sync> ParamSpecBuildable for SyncWeakCell<T> {
+    type ParamSpec = T::ParamSpec;
+}
+impl<T: ObjectType + Send + Sync> ParamStore for SyncWeakCell<T> {
+    type Type = T;
+}
+impl<T> ParamStoreRead for SyncWeakCell<T>
+where
+    T: ObjectType + Send + Sync,
+{
+    type ReadType = T;
+    fn get_owned(&self) -> Self::ReadType {
+        self.upgrade().expect("Failed to upgrade WeakRef")
+    }
+    fn get_value(&self) -> Value {
+        match self.upgrade() {
+            Some(obj) => obj.to_value(),
+            None => Value::from_type(T::static_type()),
+        }
+    }
+}
+impl<'a, T> ParamStoreWrite<'a> for SyncWeakCell<T>
+where
+    T: ObjectType + Send + Sync,
+{
+    type WriteType = T;
+    fn set_owned(&'a self, value: Self::WriteType) {
+        let _lock = self.1.lock().unwrap();
+        self.set(Some(&value));
+    }
+}
+impl<'a, T> ParamStoreWriteChanged<'a> for SyncWeakCell<T>
+where
+    T: ObjectType + Send + Sync + PartialEq,
+{
+    fn set_owned_checked(&'a self, value: Self::WriteType) -> bool {
+        // the upgrade and the store must happen together, or a concurrent write in between
+        // would go unnoticed
+        let _lock = self.1.lock().unwrap();
+        let old = self.upgrade();
+        self.set(Some(&value));
+        old.as_ref() != Some(&value)
+    }
+}
+
+/// A value that can be stored in an [`AtomicCell`] by converting it to and from a `u64`.
+pub trait AtomicValue: ValueType + Copy + PartialEq {
+    fn into_bits(self) -> u64;
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! atomic_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl AtomicValue for $ty {
+                #[inline]
+                fn into_bits(self) -> u64 {
+                    self as u64
+                }
+                #[inline]
+                fn from_bits(bits: u64) -> Self {
+                    bits as $ty
+                }
+            }
+        )*
+    };
+}
+
+atomic_value_int!(i8, i32, i64, u8, u32, u64);
+
+impl AtomicValue for bool {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        self as u64
+    }
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+}
+impl AtomicValue for f32 {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        self.to_bits() as u64
+    }
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+impl AtomicValue for f64 {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        self.to_bits()
+    }
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+}
+impl AtomicValue for char {
+    #[inline]
+    fn into_bits(self) -> u64 {
+        self as u64
+    }
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        char::from_u32(bits as u32).expect("invalid char in AtomicCell")
+    }
+}
+
+/// A lock-free cell for primitive values in `sync` classes, for types that have no atomic
+/// equivalent in `std` like `f32`, `f64` and `char`. Other `Copy` types can be stored by
+/// implementing [`AtomicValue`].
+#[repr(transparent)]
+pub struct AtomicCell<T: AtomicValue>(AtomicU64, PhantomData<T>);
+
+impl<T: AtomicValue> AtomicCell<T> {
+    pub fn new(value: T) -> Self {
+        Self(AtomicU64::new(value.into_bits()), PhantomData)
+    }
+    pub fn load(&self) -> T {
+        T::from_bits(self.0.load(Ordering::Acquire))
+    }
+    pub fn store(&self, value: T) {
+        self.0.store(value.into_bits(), Ordering::Release);
+    }
+    pub fn swap(&self, value: T) -> T {
+        T::from_bits(self.0.swap(value.into_bits(), Ordering::AcqRel))
+    }
+}
+impl<T: AtomicValue + Default> Default for AtomicCell<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+impl<T: AtomicValue> From<T> for AtomicCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T: AtomicValue + std::fmt::Debug> std::fmt::Debug for AtomicCell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AtomicCell").field(&self.load()).finish()
+    }
+}
+
+impl<T: ParamSpecBuildable + AtomicValue> ParamSpecBuildable for AtomicCell<T> {
+    type ParamSpec = T::ParamSpec;
+}
+impl<T: AtomicValue> ParamStore for AtomicCell<T> {
+    type Type = T;
+}
+impl<T: AtomicValue> ParamStoreRead for AtomicCell<T> {
+    type ReadType = T;
+    fn get_owned(&self) -> Self::ReadType {
+        self.load()
+    }
+}
+impl<'a, T: AtomicValue> ParamStoreWrite<'a> for AtomicCell<T> {
+    type WriteType = T;
+    fn set_owned(&'a self, value: Self::WriteType) {
+        self.store(value);
+    }
+}
+impl<'a, T: AtomicValue> ParamStoreWriteChanged<'a> for AtomicCell<T> {
+    fn set_owned_checked(&'a self, value: Self::WriteType) -> bool {
+        self.swap(value) != value
+    }
+}
+
+/// A cell holding an `Arc<T>` that can be read without locking, for boxed or string properties
+/// in `sync` classes. Writes replace the whole value, so readers holding a borrow keep seeing the
+/// previous value until they drop it.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct ArcCell<T>(ArcSwap<T>);
+
+impl<T> ArcCell<T> {
+    pub fn new(value: T) -> Self {
+        Self(ArcSwap::from_pointee(value))
+    }
+}
+impl<T: Default> Default for ArcCell<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+impl<T> From<T> for ArcCell<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T> Deref for ArcCell<T> {
+    type Target = ArcSwap<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ParamSpecBuildable> ParamSpecBuildable for ArcCell<T> {
+    type ParamSpec = T::ParamSpec;
+}
+impl<T: ValueType> ParamStore for ArcCell<T> {
+    type Type = T;
+}
+impl<T> ParamStoreRead for ArcCell<T>
+where
+    T: ValueType + Clone,
+{
+    type ReadType = T;
+    fn get_owned(&self) -> Self::ReadType {
+        T::clone(&self.load())
+    }
+    fn get_value(&self) -> Value {
+        self.load().to_value()
+    }
+}
+impl<'a, T> ParamStoreBorrow<'a> for ArcCell<T>
+where
+    T: 'a,
+{
+    type BorrowType = arc_swap::Guard<Arc<T>>;
+
+    fn borrow(&'a self) -> Self::BorrowType {
+        self.load()
+    }
+}
+impl<'a, T> ParamStoreWrite<'a> for ArcCell<T>
+where
+    T: ValueType,
+{
+    type WriteType = T;
+    fn set_owned(&'a self, value: Self::WriteType) {
+        self.store(Arc::new(value));
+    }
+}
+impl<'a, T> ParamStoreWriteChanged<'a> for ArcCell<T>
+where
+    T: ValueType + PartialEq,
+{
+    fn set_owned_checked(&'a self, value: Self::WriteType) -> bool {
+        let value = Arc::new(value);
+        let old = self.swap(value.clone());
+        old != value
+    }
+}
@@ -0,0 +1,182 @@
+use crate::SignalGuard;
+use gio::prelude::*;
+use glib::{translate::*, Object, Value, Variant};
+use std::collections::HashMap;
+
+/// A class exported on D-Bus.
+///
+/// Implemented by classes with the `dbus(name = "...")` option, exporting the properties,
+/// public methods and signals marked with `#[dbus]`.
+pub trait DBusObject: IsA<Object> {
+    /// The name of the D-Bus interface.
+    const DBUS_INTERFACE: &'static str;
+    /// Returns the D-Bus introspection XML describing the interface.
+    fn dbus_introspection_xml() -> String;
+    #[doc(hidden)]
+    fn dbus_method_call(
+        &self,
+        method: &str,
+        parameters: &Variant,
+    ) -> Option<Result<Variant, glib::Error>>;
+    #[doc(hidden)]
+    fn dbus_property(&self, name: &str) -> Option<Variant>;
+    #[doc(hidden)]
+    fn dbus_set_property(&self, name: &str, value: &Variant) -> bool;
+    /// Pairs of D-Bus property names and object property names.
+    #[doc(hidden)]
+    fn dbus_properties() -> &'static [(&'static str, &'static str)];
+    #[doc(hidden)]
+    fn dbus_signals() -> Vec<DBusSignal>;
+    /// Exports the object on `connection` at `object_path`.
+    ///
+    /// The object stays exported until the returned [`DBusRegistration`] is dropped.
+    fn dbus_register(
+        &self,
+        connection: &gio::DBusConnection,
+        object_path: &str,
+    ) -> Result<DBusRegistration, glib::Error> {
+        DBusRegistration::new(self, connection, object_path)
+    }
+}
+
+/// A signal forwarded to D-Bus by a [`DBusObject`].
+#[doc(hidden)]
+pub struct DBusSignal {
+    pub dbus_name: &'static str,
+    pub name: &'static str,
+    /// Converts the emission values, including the instance, to the D-Bus signal parameters.
+    pub parameters: fn(&[Value]) -> Variant,
+}
+
+/// An object exported on a D-Bus connection with [`DBusObject::dbus_register`].
+///
+/// Unregisters the object and stops forwarding property changes and signals when dropped.
+#[must_use = "the object is unregistered when the registration is dropped"]
+pub struct DBusRegistration {
+    connection: gio::DBusConnection,
+    id: u32,
+    _guards: Vec<SignalGuard<Object>>,
+}
+
+impl DBusRegistration {
+    fn new<O: DBusObject>(
+        obj: &O,
+        connection: &gio::DBusConnection,
+        object_path: &str,
+    ) -> Result<Self, glib::Error> {
+        let node = gio::DBusNodeInfo::for_xml(&O::dbus_introspection_xml())?;
+        let info = node.lookup_interface(O::DBUS_INTERFACE).unwrap();
+        let weak = glib::SendWeakRef::from(obj.downgrade());
+        // The sender is NULL on peer-to-peer connections, so the closures are built here
+        // instead of going through `DBusConnection::register_object`.
+        let method_call = glib::Closure::new(glib::clone!(@strong weak => move |args| {
+            let method = args[4].get::<&str>().unwrap();
+            let parameters = args[5].get::<Variant>().unwrap();
+            let invocation = args[6].get::<gio::DBusMethodInvocation>().unwrap();
+            match weak.upgrade().and_then(|obj| obj.dbus_method_call(method, &parameters)) {
+                Some(Ok(ret)) => invocation.return_value(Some(&ret)),
+                Some(Err(e)) => invocation.return_gerror(e),
+                None => invocation.return_dbus_error(
+                    "org.freedesktop.DBus.Error.UnknownMethod",
+                    &format!("Unknown method `{}`", method),
+                ),
+            }
+            None
+        }));
+        let get_property = glib::Closure::new(glib::clone!(@strong weak => move |args| {
+            let name = args[4].get::<&str>().unwrap();
+            let value = weak
+                .upgrade()
+                .and_then(|obj| obj.dbus_property(name))
+                .unwrap_or_else(|| ().to_variant());
+            Some(value.to_value())
+        }));
+        let set_property = glib::Closure::new(move |args| {
+            let name = args[4].get::<&str>().unwrap();
+            let value = args[5].get::<Variant>().unwrap();
+            let ret = weak
+                .upgrade()
+                .map(|obj| obj.dbus_set_property(name, &value))
+                .unwrap_or(false);
+            Some(ret.to_value())
+        });
+        let id = unsafe {
+            let mut error = std::ptr::null_mut();
+            let id = gio::ffi::g_dbus_connection_register_object_with_closures(
+                connection.to_glib_none().0,
+                object_path.to_glib_none().0,
+                info.to_glib_none().0,
+                method_call.to_glib_none().0,
+                get_property.to_glib_none().0,
+                set_property.to_glib_none().0,
+                &mut error,
+            );
+            if !error.is_null() {
+                return Err(from_glib_full(error));
+            }
+            id
+        };
+        let mut guards = Vec::new();
+        for &(dbus_name, name) in O::dbus_properties() {
+            let connection = connection.clone();
+            let object_path = object_path.to_owned();
+            let handler = obj.connect_notify_local(Some(name), move |obj, _| {
+                let value = match obj.dbus_property(dbus_name) {
+                    Some(value) => value,
+                    None => return,
+                };
+                let changed = HashMap::from([(dbus_name, value)]);
+                let parameters = (O::DBUS_INTERFACE, changed, Vec::<String>::new()).to_variant();
+                connection
+                    .emit_signal(
+                        None,
+                        &object_path,
+                        "org.freedesktop.DBus.Properties",
+                        "PropertiesChanged",
+                        Some(&parameters),
+                    )
+                    .ok();
+            });
+            guards.push(SignalGuard::new(obj, handler).upcast());
+        }
+        for signal in O::dbus_signals() {
+            let connection = connection.clone();
+            let object_path = object_path.to_owned();
+            let handler = obj.connect_local(signal.name, false, move |values| {
+                connection
+                    .emit_signal(
+                        None,
+                        &object_path,
+                        O::DBUS_INTERFACE,
+                        signal.dbus_name,
+                        Some(&(signal.parameters)(values)),
+                    )
+                    .ok();
+                None
+            });
+            guards.push(SignalGuard::new(obj, handler).upcast());
+        }
+        Ok(Self {
+            connection: connection.clone(),
+            id,
+            _guards: guards,
+        })
+    }
+    /// Returns the connection the object is exported on.
+    pub fn connection(&self) -> &gio::DBusConnection {
+        &self.connection
+    }
+    /// Unregisters the object now. Equivalent to dropping the registration.
+    pub fn unregister(self) {}
+}
+
+impl Drop for DBusRegistration {
+    fn drop(&mut self) {
+        unsafe {
+            gio::ffi::g_dbus_connection_unregister_object(
+                self.connection.to_glib_none().0,
+                self.id,
+            );
+        }
+    }
+}
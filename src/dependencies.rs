@@ -0,0 +1,86 @@
+use glib::{
+    gobject_ffi, once_cell::sync::Lazy, prelude::*, subclass::types::ClassStruct, translate::*,
+};
+use std::{collections::HashMap, ffi::CStr, sync::Mutex};
+
+type DispatchFn =
+    unsafe extern "C" fn(*mut gobject_ffi::GObject, u32, *mut *mut gobject_ffi::GParamSpec);
+
+/// Pairs of a property name and the names of the computed properties derived from it.
+type Dependents = &'static [(&'static str, &'static [&'static str])];
+
+struct ClassDependents {
+    dependents: Dependents,
+    /// The `dispatch_properties_changed` replaced in this class, if it was the first one in its
+    /// hierarchy to be installed.
+    parent: Option<Option<DispatchFn>>,
+}
+
+static CLASSES: Lazy<Mutex<HashMap<glib::Type, ClassDependents>>> = Lazy::new(Default::default);
+
+/// Overrides `dispatch_properties_changed` in `class` so every notification of a property also
+/// notifies the computed properties depending on it, whichever way it was emitted.
+#[doc(hidden)]
+pub fn install_dependents<C: ClassStruct>(class: &mut C, dependents: Dependents) {
+    unsafe {
+        let class = class as *mut C as *mut gobject_ffi::GObjectClass;
+        let type_: glib::Type = from_glib((*class).g_type_class.g_type);
+        let mut classes = CLASSES.lock().unwrap();
+        let mut inherited = false;
+        let mut ancestor = type_.parent();
+        while let Some(parent) = ancestor {
+            inherited |= classes.contains_key(&parent);
+            ancestor = parent.parent();
+        }
+        let parent = (!inherited).then(|| (*class).dispatch_properties_changed);
+        classes.insert(type_, ClassDependents { dependents, parent });
+        if !inherited {
+            (*class).dispatch_properties_changed = Some(dispatch_properties_changed);
+        }
+    }
+}
+
+unsafe extern "C" fn dispatch_properties_changed(
+    obj: *mut gobject_ffi::GObject,
+    n_pspecs: u32,
+    pspecs: *mut *mut gobject_ffi::GParamSpec,
+) {
+    let object: Borrowed<glib::Object> = from_glib_borrow(obj);
+    let mut changed = std::slice::from_raw_parts(pspecs, n_pspecs as usize).to_vec();
+    let mut names = changed
+        .iter()
+        .map(|pspec| CStr::from_ptr((**pspec).name).to_str().unwrap())
+        .collect::<Vec<_>>();
+    let parent = {
+        let classes = CLASSES.lock().unwrap();
+        let mut parent = None;
+        let mut type_ = Some(object.type_());
+        while let Some(current) = type_ {
+            if let Some(class) = classes.get(&current) {
+                // dependents are transitive already, so only the notified names are looked up
+                for (source, dependents) in class.dependents {
+                    if names[..n_pspecs as usize].contains(source) {
+                        for dependent in *dependents {
+                            if !names.contains(dependent) {
+                                names.push(dependent);
+                            }
+                        }
+                    }
+                }
+                if let Some(dispatch) = class.parent {
+                    parent = dispatch;
+                }
+            }
+            type_ = current.parent();
+        }
+        parent
+    };
+    let extra = names[n_pspecs as usize..]
+        .iter()
+        .filter_map(|name| object.find_property(name))
+        .collect::<Vec<_>>();
+    changed.extend(extra.iter().map(|pspec| pspec.as_ptr()));
+    if let Some(parent) = parent {
+        parent(obj, changed.len() as u32, changed.as_mut_ptr());
+    }
+}
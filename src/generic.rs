@@ -0,0 +1,66 @@
+use glib::{
+    once_cell::sync::Lazy,
+    subclass::types::{ObjectSubclass, TypeData},
+};
+use std::{
+    any::{Any, TypeId},
+    cell::UnsafeCell,
+    collections::HashMap,
+    ptr::NonNull,
+    sync::{Once, RwLock},
+};
+
+type StaticMap = HashMap<(TypeId, TypeId), &'static (dyn Any + Send + Sync)>;
+
+static GENERIC_STATICS: Lazy<RwLock<StaticMap>> = Lazy::new(Default::default);
+
+/// Returns a value of type `V` that is unique to the type `K`, calling `init` to create it on
+/// first access.
+///
+/// Generic functions can't declare statics that differ per instantiation, so classes registered
+/// with `instantiate` use this in their place. Values are leaked and live for the rest of the
+/// program.
+pub fn generic_static<K: 'static, V: Send + Sync + 'static>(
+    init: impl FnOnce() -> V,
+) -> &'static V {
+    let key = (TypeId::of::<K>(), TypeId::of::<V>());
+    let value = GENERIC_STATICS.read().unwrap().get(&key).copied();
+    let value = value.unwrap_or_else(|| {
+        // initialize outside the lock, since `init` might need to access other statics
+        let value = init();
+        *GENERIC_STATICS
+            .write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Box::leak(Box::new(value)))
+    });
+    value.downcast_ref().unwrap()
+}
+
+#[doc(hidden)]
+pub struct GenericTypeData {
+    data: UnsafeCell<TypeData>,
+    once: Once,
+}
+
+unsafe impl Send for GenericTypeData {}
+unsafe impl Sync for GenericTypeData {}
+
+impl GenericTypeData {
+    fn get<T: ObjectSubclass>() -> &'static Self {
+        generic_static::<T, Self>(|| Self {
+            data: UnsafeCell::new(glib::subclass::types::INIT_TYPE_DATA),
+            once: Once::new(),
+        })
+    }
+    pub fn type_data<T: ObjectSubclass>() -> NonNull<TypeData> {
+        unsafe { NonNull::new_unchecked(Self::get::<T>().data.get()) }
+    }
+    pub fn type_<T: ObjectSubclass>() -> glib::Type {
+        let data = Self::get::<T>();
+        data.once.call_once(|| {
+            glib::subclass::register_type::<T>();
+        });
+        unsafe { (*data.data.get()).type_() }
+    }
+}
@@ -0,0 +1,301 @@
+use glib::{prelude::*, subclass::prelude::*};
+use std::time::{Duration, Instant};
+
+const HISTORY_KEY: &str = "gobject-history";
+
+mod imp {
+    use glib::{once_cell::sync::Lazy, prelude::*, subclass::prelude::*};
+    use std::{
+        cell::{Cell, RefCell},
+        time::{Duration, Instant},
+    };
+
+    pub(super) struct Change {
+        pub object: glib::WeakRef<glib::Object>,
+        pub pspec: glib::ParamSpec,
+        pub old: glib::Value,
+        pub new: glib::Value,
+        pub time: Instant,
+    }
+
+    #[derive(Default)]
+    pub struct History {
+        pub(super) undo: RefCell<Vec<Vec<Change>>>,
+        pub(super) redo: RefCell<Vec<Vec<Change>>>,
+        pub(super) group_depth: Cell<u32>,
+        pub(super) group_open: Cell<bool>,
+        pub(super) mergeable: Cell<bool>,
+        pub(super) applying: Cell<bool>,
+        pub(super) merge_window: Cell<Duration>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for History {
+        const NAME: &'static str = "GobjectHistory";
+        type Type = super::History;
+    }
+
+    impl ObjectImpl for History {
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![
+                    glib::ParamSpecBoolean::builder("can-undo")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("can-redo")
+                        .flags(glib::ParamFlags::READABLE)
+                        .build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+        fn property(&self, obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "can-undo" => obj.can_undo().to_value(),
+                "can-redo" => obj.can_redo().to_value(),
+                _ => unimplemented!(),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Records changes to the properties of objects declared with `#[properties(history)]` so
+    /// they can be undone and redone.
+    ///
+    /// Changes are recorded for objects passed to [`History::attach`]. Each change is one undo
+    /// step unless it is made inside a group, or it changes the same property as the previous
+    /// step within the merge window.
+    pub struct History(ObjectSubclass<imp::History>);
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    pub fn new() -> Self {
+        glib::Object::new(&[]).expect("Failed to create History")
+    }
+    /// Returns the history `object` is attached to.
+    pub fn for_object(object: &impl IsA<glib::Object>) -> Option<Self> {
+        unsafe {
+            object
+                .as_ref()
+                .data::<Self>(HISTORY_KEY)
+                .map(|history| history.as_ref().clone())
+        }
+    }
+    /// Starts recording property changes of `object` into this history, replacing any history
+    /// it was previously attached to.
+    pub fn attach(&self, object: &impl IsA<glib::Object>) {
+        unsafe {
+            object.as_ref().set_data(HISTORY_KEY, self.clone());
+        }
+    }
+    /// Stops recording property changes of `object`. Changes that were already recorded can still
+    /// be undone.
+    pub fn detach(&self, object: &impl IsA<glib::Object>) {
+        if Self::for_object(object).as_ref() == Some(self) {
+            unsafe {
+                object.as_ref().steal_data::<Self>(HISTORY_KEY);
+            }
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.imp().undo.borrow().is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.imp().redo.borrow().is_empty()
+    }
+    /// Reverts the most recent undo step. Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        let imp = self.imp();
+        let group = match imp.undo.borrow_mut().pop() {
+            Some(group) => group,
+            None => return false,
+        };
+        let state = self.state();
+        self.apply(group.iter().rev().map(|c| (c, &c.old)));
+        imp.redo.borrow_mut().push(group);
+        self.notify_state(state);
+        true
+    }
+    /// Reapplies the most recently undone step. Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        let imp = self.imp();
+        let group = match imp.redo.borrow_mut().pop() {
+            Some(group) => group,
+            None => return false,
+        };
+        let state = self.state();
+        self.apply(group.iter().map(|c| (c, &c.new)));
+        imp.undo.borrow_mut().push(group);
+        self.notify_state(state);
+        true
+    }
+    /// Starts a group of changes that are undone and redone together. Groups can be nested, and
+    /// the outermost group ends with the matching call to [`History::end_group`].
+    pub fn begin_group(&self) {
+        let imp = self.imp();
+        if imp.group_depth.get() == 0 {
+            imp.group_open.set(false);
+            imp.mergeable.set(false);
+        }
+        imp.group_depth.set(imp.group_depth.get() + 1);
+    }
+    pub fn end_group(&self) {
+        let imp = self.imp();
+        let depth = imp.group_depth.get();
+        assert!(depth > 0, "History::end_group called without begin_group");
+        imp.group_depth.set(depth - 1);
+        if depth == 1 {
+            imp.group_open.set(false);
+        }
+    }
+    /// Calls `func` inside a group, so all changes it makes are undone in one step.
+    pub fn group<R>(&self, func: impl FnOnce() -> R) -> R {
+        self.begin_group();
+        let ret = func();
+        self.end_group();
+        ret
+    }
+    /// Returns how long after a change another change to the same property is merged into it.
+    pub fn merge_window(&self) -> Duration {
+        self.imp().merge_window.get()
+    }
+    /// Sets how long after a change another change to the same property is merged into it
+    /// instead of creating a new undo step. Defaults to zero, which disables merging.
+    pub fn set_merge_window(&self, window: Duration) {
+        self.imp().merge_window.set(window);
+    }
+    /// Removes all undo and redo steps.
+    pub fn clear(&self) {
+        let imp = self.imp();
+        let state = self.state();
+        imp.undo.borrow_mut().clear();
+        imp.redo.borrow_mut().clear();
+        imp.group_open.set(false);
+        imp.mergeable.set(false);
+        self.notify_state(state);
+    }
+    fn apply<'a>(&self, changes: impl Iterator<Item = (&'a imp::Change, &'a glib::Value)>) {
+        let imp = self.imp();
+        imp.group_open.set(false);
+        imp.mergeable.set(false);
+        imp.applying.set(true);
+        for (change, value) in changes {
+            if let Some(object) = change.object.upgrade() {
+                object.set_property_from_value(change.pspec.name(), value);
+            }
+        }
+        imp.applying.set(false);
+    }
+    fn record(
+        &self,
+        object: &glib::Object,
+        pspec: &glib::ParamSpec,
+        old: glib::Value,
+        new: glib::Value,
+    ) {
+        let imp = self.imp();
+        let unchanged = crate::param_values_eq(pspec, &old, &new);
+        if imp.applying.get() || unchanged {
+            return;
+        }
+        let state = self.state();
+        imp.redo.borrow_mut().clear();
+        let now = Instant::now();
+        let window = self.merge_window();
+        let in_group = imp.group_depth.get() > 0;
+        {
+            let mut undo = imp.undo.borrow_mut();
+            let open = if in_group {
+                imp.group_open.get()
+            } else {
+                imp.mergeable.get()
+            };
+            let last = undo.last_mut().filter(|_| open);
+            let existing = last.as_ref().and_then(|group| {
+                group.iter().position(|c| {
+                    c.pspec == *pspec
+                        && c.object.upgrade().as_ref() == Some(object)
+                        && (in_group || (!window.is_zero() && now.duration_since(c.time) <= window))
+                })
+            });
+            match (last, existing) {
+                (Some(group), Some(index)) => {
+                    group[index].new = new;
+                    group[index].time = now;
+                }
+                (last, _) => {
+                    let change = imp::Change {
+                        object: object.downgrade(),
+                        pspec: pspec.clone(),
+                        old,
+                        new,
+                        time: now,
+                    };
+                    match last.filter(|_| in_group) {
+                        Some(group) => group.push(change),
+                        None => undo.push(vec![change]),
+                    }
+                }
+            }
+        }
+        if in_group {
+            imp.group_open.set(true);
+        } else {
+            imp.mergeable.set(true);
+        }
+        self.notify_state(state);
+    }
+    fn state(&self) -> (bool, bool) {
+        (self.can_undo(), self.can_redo())
+    }
+    fn notify_state(&self, (could_undo, could_redo): (bool, bool)) {
+        if could_undo != self.can_undo() {
+            self.notify("can-undo");
+        }
+        if could_redo != self.can_redo() {
+            self.notify("can-redo");
+        }
+    }
+}
+
+/// Records a property change into the history of an object when dropped.
+#[doc(hidden)]
+pub struct HistoryGuard {
+    history: History,
+    object: glib::Object,
+    pspec: glib::ParamSpec,
+    old: Option<glib::Value>,
+}
+
+impl HistoryGuard {
+    pub fn new(object: &impl IsA<glib::Object>, pspec: &glib::ParamSpec) -> Option<Self> {
+        let history = History::for_object(object)?;
+        if history.imp().applying.get() {
+            return None;
+        }
+        let object = object.as_ref().clone();
+        let old = object.property_value(pspec.name());
+        Some(Self {
+            history,
+            object,
+            pspec: pspec.clone(),
+            old: Some(old),
+        })
+    }
+}
+
+impl Drop for HistoryGuard {
+    fn drop(&mut self) {
+        if let Some(old) = self.old.take() {
+            let new = self.object.property_value(self.pspec.name());
+            self.history.record(&self.object, &self.pspec, old, new);
+        }
+    }
+}
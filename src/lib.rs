@@ -23,6 +23,719 @@
 //! # }
 //! ```
 //!
+//! ### Property validation
+//!
+//! `#[property(validate = "path")]` passes every new value through a function before it is
+//! stored. The function takes the object and the value and returns `Ok` with the value to store,
+//! which may be clamped or otherwise changed, or an error to reject it. Values rejected when set
+//! through GObject are logged as warnings, and a `try_set_*` method is generated that returns the
+//! error instead. The function runs once per new value.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Volume {
+//!         #[property(get, set, validate = "clamp_level")]
+//!         level: std::cell::Cell<u32>,
+//!     }
+//!     fn clamp_level(_obj: &super::Volume, level: u32) -> Result<u32, String> {
+//!         match level {
+//!             0..=1000 => Ok(level.min(100)),
+//!             _ => Err(format!("level {} out of range", level)),
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let obj: Volume = glib::Object::new(&[]).unwrap();
+//!     obj.set_level(150);
+//!     assert_eq!(obj.level(), 100);
+//!     assert!(obj.try_set_level(5000).is_err());
+//!     assert_eq!(obj.level(), 100);
+//! # }
+//! ```
+//!
+//! ### Property bindings
+//!
+//! `#[property(bind(source = "self.model", property = "title"))]` keeps a property in sync with
+//! a property of the object stored in another property of the same object. The binding is created
+//! in `constructed` and replaced whenever the source property changes. Add `bidirectional` to
+//! also copy changes back to the source, `invert_boolean` to invert boolean values, and
+//! `transform_to`/`transform_from` to pass functions to the binding builder. `property` defaults
+//! to the name of the bound property. The source is tracked with handlers that aren't `Send`, so
+//! `bind` is not allowed on `sync` classes.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Title {
+//!         #[property(get, set)]
+//!         text: std::cell::RefCell<String>,
+//!     }
+//! }
+//!
+//! #[gobject::class(final)]
+//! mod label {
+//!     #[derive(Default)]
+//!     pub struct Label {
+//!         #[property(get, set, object)]
+//!         model: std::cell::RefCell<Option<super::Title>>,
+//!         #[property(get, set, bind(source = "self.model", property = "text", bidirectional))]
+//!         label: std::cell::RefCell<String>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let title: Title = glib::Object::new(&[("text", &"Hello")]).unwrap();
+//!     let label: Label = glib::Object::new(&[("model", &title)]).unwrap();
+//!     assert_eq!(label.label(), "Hello");
+//!     label.set_label("Goodbye".into());
+//!     assert_eq!(title.text(), "Goodbye");
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! #[gobject::class(final, sync)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Title {
+//!         #[property(get, set)]
+//!         text: std::sync::Mutex<String>,
+//!     }
+//! }
+//!
+//! #[gobject::class(final, sync)]
+//! mod label {
+//!     #[derive(Default)]
+//!     pub struct Label {
+//!         #[property(get, set, object)]
+//!         model: std::sync::Mutex<Option<super::Title>>,
+//!         #[property(get, set, bind(source = "self.model", property = "text"))]
+//!         label: std::sync::Mutex<String>,
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### Computed properties
+//!
+//! A `computed` property has no storage and is read and written through methods on the class. List
+//! the properties it is derived from in `depends_on` to notify it whenever one of them is set or
+//! notified, however the notification is emitted. Dependencies are followed transitively, cycles
+//! are rejected at compile time, and changes made while notifications are frozen produce a single
+//! notification.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Rect {
+//!         #[property(get, set)]
+//!         width: std::cell::Cell<u32>,
+//!         #[property(get, set)]
+//!         height: std::cell::Cell<u32>,
+//!         #[property(computed, get, depends_on("width", "height"))]
+//!         area: std::marker::PhantomData<u32>,
+//!     }
+//!     impl Rect {
+//!         #[public]
+//!         fn area(&self) -> u32 {
+//!             self.width.get() * self.height.get()
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let rect: Rect = glib::Object::new(&[]).unwrap();
+//!     let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+//!     rect.connect_area_notify({
+//!         let notified = notified.clone();
+//!         move |_| notified.set(notified.get() + 1)
+//!     });
+//!     rect.set_properties(&[("width", &2u32), ("height", &3u32)]);
+//!     assert_eq!(notified.get(), 1);
+//!     assert_eq!(rect.area(), 6);
+//! # }
+//! ```
+//!
+//! ### Undo history
+//!
+//! With `#[properties(history)]`, every change made through the generated setters or
+//! `set_property` is recorded into the [`History`] the object is attached to. Changes can be
+//! grouped into one undo step with [`History::group`], and repeated changes to the same property
+//! are merged when they happen within [`History::set_merge_window`] of each other. A history is
+//! only used from one thread, so `history` is not allowed on `sync` classes.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[properties(history)]
+//!     #[derive(Default)]
+//!     pub struct Document {
+//!         #[property(get, set)]
+//!         title: std::cell::RefCell<String>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let history = gobject::History::new();
+//!     let doc: Document = glib::Object::new(&[]).unwrap();
+//!     history.attach(&doc);
+//!     doc.set_title("Draft".into());
+//!     history.undo();
+//!     assert_eq!(doc.title(), "");
+//!     history.redo();
+//!     assert_eq!(doc.title(), "Draft");
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! #[gobject::class(final, sync)]
+//! mod obj {
+//!     #[properties(history)]
+//!     #[derive(Default)]
+//!     pub struct Document {
+//!         #[property(get, set)]
+//!         title: std::sync::Mutex<String>,
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### Transactions
+//!
+//! Adding `#[properties(transactions)]` generates `batch` and `edit` methods for changing several
+//! properties at once. Notifications are held until the closure returns, so each changed property
+//! is notified only once. If the closure passed to `edit` returns an error, the previous values are
+//! written back through the property setters and restored properties are not notified.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[properties(transactions)]
+//!     #[derive(Default)]
+//!     pub struct Point {
+//!         #[property(get, set)]
+//!         x: std::cell::Cell<i32>,
+//!         #[property(get, set)]
+//!         y: std::cell::Cell<i32>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let point: Point = glib::Object::new(&[]).unwrap();
+//!     let res = point.edit(|point| {
+//!         point.set_x(4);
+//!         point.set_y(-1);
+//!         if point.y() < 0 {
+//!             return Err("y must not be negative");
+//!         }
+//!         Ok(())
+//!     });
+//!     assert!(res.is_err());
+//!     assert_eq!(point.x(), 0);
+//! # }
+//! ```
+//!
+//! ### Async signals
+//!
+//! Signal handlers can be `async fn`. Closures passed to the generated `connect_` method then
+//! return a future, and `emit_<name>_future` emits the signal and returns a future that awaits each
+//! handler in order, combining their results with the accumulator or keeping the last one. Handler
+//! futures run one after another, not concurrently. The signal is still a regular GSignal, so C
+//! code can connect and emit it as usual. When emitted any other way, Rust handlers are spawned on
+//! the thread-default main context and the emission receives `Default::default()` from them as a
+//! placeholder.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Loader {}
+//!     impl Loader {
+//!         #[signal(run_last)]
+//!         async fn load(&self, url: String) -> bool {
+//!             glib::timeout_future(std::time::Duration::ZERO).await;
+//!             !url.is_empty()
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let loader: Loader = glib::Object::new(&[]).unwrap();
+//!     loader.connect_load(|_, url| async move { url.ends_with(".txt") });
+//!     let ok = glib::MainContext::default().block_on(loader.emit_load_future("file.txt".into()));
+//!     assert!(ok);
+//! # }
+//! ```
+//!
+//! ### Scoped handlers
+//!
+//! Each `connect_` method generated for signals and property notifications has a `_scoped`
+//! variant returning a [`SignalGuard`], which disconnects the handler when dropped. Guards for
+//! several objects can be kept together in a [`SignalGuards`] to disconnect them all at once.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Counter {
+//!         #[property(get, set)]
+//!         count: std::cell::Cell<u32>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let counter: Counter = glib::Object::new(&[]).unwrap();
+//!     let guards = gobject::SignalGuards::new();
+//!     let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+//!     guards.add(counter.connect_count_notify_scoped({
+//!         let notified = notified.clone();
+//!         move |_| notified.set(notified.get() + 1)
+//!     }));
+//!     counter.set_count(1);
+//!     guards.clear();
+//!     counter.set_count(2);
+//!     assert_eq!(notified.get(), 1);
+//! # }
+//! ```
+//!
+//! ### Signal hooks
+//!
+//! Methods marked with `#[signal_hook("name")]` are installed as emission hooks when the class is
+//! initialized, and run for every emission of the signal on any instance. Hooks take the emitting
+//! object and the signal arguments, optionally preceded by the invocation hint, and can return
+//! `bool` to remove themselves. Hooks for signals of the class itself are checked against the
+//! signal's arguments. A signal on another type can be observed by passing `type = "Path"`. Each
+//! hook gets a `*_hook` method returning an [`EmissionHook`] handle that can remove it early.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     use std::sync::atomic::{AtomicU32, Ordering};
+//!
+//!     pub static NOTIFIES: AtomicU32 = AtomicU32::new(0);
+//!
+//!     #[derive(Default)]
+//!     pub struct Logger {
+//!         #[property(get, set)]
+//!         level: std::cell::Cell<u32>,
+//!     }
+//!     impl Logger {
+//!         #[signal_hook("notify", type = "glib::Object")]
+//!         pub(super) fn log_notify(_obj: glib::Object, _pspec: glib::ParamSpec) {
+//!             NOTIFIES.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let logger: Logger = glib::Object::new(&[]).unwrap();
+//!     logger.set_level(1);
+//!     Logger::log_notify_hook().remove();
+//!     logger.set_level(2);
+//!     assert_eq!(obj::NOTIFIES.load(std::sync::atomic::Ordering::Relaxed), 1);
+//! # }
+//! ```
+//!
+//! ### Signal arguments
+//!
+//! Signal arguments can be borrowed. `&[u8]` is passed as a `GBytes`, `&T` and `Option<&T>` borrow
+//! objects, boxed types and variants, and `Vec<T>` and `&[T]` are stored in a [`BoxedVec`]
+//! registered for `T`. Emitting copies `&str`, `&[u8]` and `&[T]` arguments once into the signal's
+//! values, while `Vec<T>` arguments are moved in. Handlers borrow `&str`, `&[u8]` and `&[T]` from
+//! those values without copying them again, except async handlers, which copy them because they
+//! outlive the emission.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Parser {}
+//!     impl Parser {
+//!         #[signal(run_last)]
+//!         fn chunk(&self, data: &[u8], name: Option<&str>) -> u32 {
+//!             data.len() as u32
+//!         }
+//!         #[signal]
+//!         fn ranges(&self, ranges: &[(u32, u32)]) {}
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let parser: Parser = glib::Object::new(&[]).unwrap();
+//!     let count = std::rc::Rc::new(std::cell::Cell::new(0));
+//!     parser.connect_ranges({
+//!         let count = count.clone();
+//!         move |_, ranges| count.set(ranges.len())
+//!     });
+//!     assert_eq!(parser.emit_chunk(b"hello", None), 5);
+//!     parser.emit_ranges(&[(0, 5), (5, 8)]);
+//!     assert_eq!(count.get(), 2);
+//! # }
+//! ```
+//!
+//! ### Signal forwarding
+//!
+//! `#[signal(forward_from = "self.child")]` re-emits a signal of the same name from the object
+//! stored in a property, with the same arguments and detail. Pass `signal` to forward a differently
+//! named signal. The handler is connected in `constructed` and moved whenever the property changes,
+//! so the property can be a `WeakCell`, a `ConstructOnlyCell` or any other object property. The
+//! handlers aren't `Send`, so `forward_from` is not allowed on `sync` classes.
+//!
+//! ```
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Entry {}
+//!     impl Entry {
+//!         #[signal]
+//!         fn activate(&self) {}
+//!     }
+//! }
+//!
+//! #[gobject::class(final)]
+//! mod form {
+//!     #[derive(Default)]
+//!     pub struct Form {
+//!         #[property(get, set, object)]
+//!         entry: gobject::WeakCell<super::Entry>,
+//!     }
+//!     impl Form {
+//!         #[signal(forward_from = "self.entry", signal = "activate")]
+//!         fn submit(&self) {}
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let entry: Entry = glib::Object::new(&[]).unwrap();
+//!     let form: Form = glib::Object::new(&[("entry", &entry)]).unwrap();
+//!     let submitted = std::rc::Rc::new(std::cell::Cell::new(false));
+//!     form.connect_submit({
+//!         let submitted = submitted.clone();
+//!         move |_| submitted.set(true)
+//!     });
+//!     entry.emit_activate();
+//!     assert!(submitted.get());
+//! # }
+//! ```
+//!
+//! ### Typed signal details
+//!
+//! `#[signal(detailed = "Type")]` checks signal details at compile time. The emit method takes the
+//! detail as a required argument of that type, and the connect methods take an `Option` of it.
+//! Enums defined with [`enum_`] implement [`SignalDetail`] using the nick of each value as the
+//! detail string, so handlers can still be connected from C with `changed::title`.
+//!
+//! ```
+//! #[gobject::enum_]
+//! #[derive(Clone, Copy, Debug)]
+//! pub enum Field {
+//!     Title,
+//!     Body,
+//! }
+//!
+//! #[gobject::class(final)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Document {}
+//!     impl Document {
+//!         #[signal(detailed = "super::Field")]
+//!         fn changed(&self) {}
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     let doc: Document = glib::Object::new(&[]).unwrap();
+//!     let changed = std::rc::Rc::new(std::cell::Cell::new(0));
+//!     doc.connect_changed(Some(Field::Title), {
+//!         let changed = changed.clone();
+//!         move |_| changed.set(changed.get() + 1)
+//!     });
+//!     doc.emit_changed(Field::Title);
+//!     doc.emit_changed(Field::Body);
+//!     assert_eq!(changed.get(), 1);
+//! # }
+//! ```
+//!
+//! ### Mock classes
+//!
+//! `#[gobject::class(mock)]` on a derivable class also generates a final `Mock<Name>` subclass for
+//! tests, when the `mock` feature is enabled. Enable it from `dev-dependencies` so the mocks are
+//! only compiled for tests. Every virtual method and signal class handler of the mocked class can
+//! be replaced with a closure by calling `mock_<method>`, and falls through to the parent
+//! implementation otherwise. Calls are recorded either way, and `<method>_mock` returns the
+//! `MockMethod` to inspect them or set how many calls are expected, which `verify_mocks` checks.
+//!
+//! ```
+//! #[gobject::class(abstract, mock)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct Fetcher {}
+//!     impl Fetcher {
+//!         #[virt]
+//!         fn fetch(&self, url: &str) -> Option<String> {
+//!             None
+//!         }
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "mock")]
+//! # fn main() {
+//!     let fetcher: MockFetcher = glib::Object::new(&[]).unwrap();
+//!     fetcher
+//!         .mock_fetch(|_, url| Some(format!("contents of {}", url)))
+//!         .expect_calls(1);
+//!     assert_eq!(fetcher.fetch("a.txt").unwrap(), "contents of a.txt");
+//!     assert_eq!(fetcher.fetch_mock().calls(), [("a.txt".to_owned(),)]);
+//!     fetcher.verify_mocks();
+//! # }
+//! # #[cfg(not(feature = "mock"))]
+//! # fn main() {}
+//! ```
+//!
+//! ### D-Bus export
+//!
+//! With the `use_gio` feature, `#[gobject::class(dbus(name = "..."))]` exports an object as a D-Bus
+//! interface. Properties, `#[public]` methods and `#[signal]`s marked with `#[dbus]` become D-Bus
+//! properties, methods and signals, named in upper camel case unless renamed with
+//! `#[dbus(name = "...")]`. The class implements [`DBusObject`], which generates the introspection
+//! XML and exports the object on a `gio::DBusConnection` until the returned [`DBusRegistration`]
+//! is dropped. Property notifications are sent as `PropertiesChanged`.
+//!
+//! ```
+//! # #[cfg(feature = "use_gio")]
+//! #[gobject::class(final, dbus(name = "org.example.Counter"))]
+//! mod counter {
+//!     #[derive(Default)]
+//!     pub struct Counter {
+//!         #[property(get, set)]
+//!         #[dbus]
+//!         count: std::cell::Cell<i32>,
+//!     }
+//!     impl Counter {
+//!         #[public]
+//!         #[dbus]
+//!         fn add(&self, amount: i32) -> i32 {
+//!             let count = self.count.get() + amount;
+//!             self.instance().set_count(count);
+//!             count
+//!         }
+//!         #[signal]
+//!         #[dbus]
+//!         fn reset(&self, previous: i32) {}
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "use_gio")]
+//! fn export(connection: &gio::DBusConnection) -> Result<gobject::DBusRegistration, glib::Error> {
+//!     use gobject::DBusObject;
+//!     let counter = glib::Object::new::<Counter>(&[]).unwrap();
+//!     counter.dbus_register(connection, "/org/example/Counter")
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### D-Bus proxies
+//!
+//! `gobject::dbus_proxy!("path/to/file.xml")` reads D-Bus introspection XML, relative to the
+//! crate root, and generates a final `<Name>Proxy` class for each interface in it, wrapping a
+//! `gio::DBusProxy`. Methods become async methods returning `Result`, properties are exposed as
+//! GObject properties read from the proxy cache and notified when they change on the bus, and
+//! signals get the usual typed `connect_<signal>` functions. Property and signal types that
+//! can't be stored in a `glib::Value` are kept as a `glib::Variant`.
+//!
+//! ```
+//! # #[cfg(feature = "use_gio")]
+//! gobject::dbus_proxy!("tests/dbus_proxy/org.example.Player.xml");
+//!
+//! # #[cfg(feature = "use_gio")]
+//! async fn play(connection: &gio::DBusConnection) -> Result<bool, glib::Error> {
+//!     let player =
+//!         PlayerProxy::new(connection, Some("org.example.Player"), "/org/example/Player").await?;
+//!     player.set_volume(50);
+//!     player.play("song.ogg").await
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### Settings bindings
+//!
+//! With the `use_gio` feature, `#[properties(settings_schema = "...")]` binds every property
+//! marked with `#[property(settings)]` to a GSettings key when the object is constructed. The key
+//! defaults to the property name, `settings(get)` and `settings(set)` limit the binding to one
+//! direction, and `settings(with = "path")` stores the value with a `gobject::variant` adapter
+//! module. Without `with`, only booleans, integers, `f64`, strings, enums, flags and
+//! `glib::Variant` can be bound, and `glib::Variant` properties need `settings(default = "...")`.
+//! Stateful actions marked with `#[action(settings = "key")]` keep their state in the key,
+//! converted with the action's `state_to` and `state_from` functions, and changes of the key go
+//! through the action's `change_state` handler. The class implements
+//! [`SettingsObject`], which generates the `.gschema.xml` for the schema from the property types,
+//! defaults and blurbs, and from the action states.
+//!
+//! ```
+//! # #[cfg(feature = "use_gio")]
+//! #[gobject::class(
+//!     final,
+//!     extends(gio::Application),
+//!     parent_trait = "gio::subclass::prelude::ApplicationImpl",
+//!     inherits(gio::ActionGroup, gio::ActionMap)
+//! )]
+//! mod application {
+//!     #[derive(Default)]
+//!     #[properties(settings_schema = "org.example.Application")]
+//!     pub struct Application {
+//!         #[property(get, set, settings, builder(maximum = 100, default_value = 50))]
+//!         volume: std::cell::Cell<i32>,
+//!         #[property(get, set, settings(key = "color-scheme", get))]
+//!         theme: std::cell::RefCell<String>,
+//!     }
+//!     impl Application {
+//!         #[action(change_state, settings = "dark-mode")]
+//!         fn dark_mode(&self, dark: bool) -> Option<bool> {
+//!             Some(dark)
+//!         }
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "use_gio")]
+//! fn write_schema() -> std::io::Result<()> {
+//!     use gobject::SettingsObject;
+//!     std::fs::write("org.example.Application.gschema.xml", Application::settings_schema_xml())
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### Introspection
+//!
+//! Classes and interfaces can describe themselves as GObject Introspection XML. From a build
+//! script, `gobject_build::generate_gir("src/lib.rs", out_dir)` writes one `<TypeName>.gir` file
+//! per type into `out_dir`. Alternatively, opt into writing the files while the macros expand by
+//! setting the `GOBJECT_GIR_DIR` environment variable to a directory, which then receives one file
+//! per type on every build or `cargo check`. The namespace is taken from `ns`, falling back to
+//! the crate name. C symbols, such as `glib:get-type` and method identifiers, are only included for
+//! classes declared with `ffi`, since no other classes export them.
+//!
+//! ### Generic classes
+//!
+//! A class with one type parameter is registered once per type listed in `instantiate`, each under
+//! its own type name and with its own properties and signals. Generic classes are limited to:
+//!
+//! - `final` classes, so they can't be subclassed,
+//! - exactly one type parameter, with no lifetime or const parameters,
+//! - the types listed in `instantiate`, which is required,
+//! - static registration without `ffi`, `dynamic` or GIR output.
+//!
+//! ```
+//! #[gobject::class(final, instantiate(Container<i32> = "ContainerInt", Container<String> = "ContainerString"))]
+//! mod container {
+//!     #[derive(Default)]
+//!     pub struct Container<T: Default + 'static> {
+//!         items: std::cell::RefCell<Vec<T>>,
+//!     }
+//! }
+//!
+//! # fn main() {
+//!     use glib::StaticType;
+//!     assert_eq!(Container::<i32>::static_type().name(), "ContainerInt");
+//!     assert_eq!(Container::<String>::static_type().name(), "ContainerString");
+//! # }
+//! ```
+//!
+//! ### Dynamic types
+//!
+//! Classes, interfaces, enums and flags marked `dynamic` are registered with a [`TypeModule`]
+//! instead of statically, so they can be loaded and unloaded along with a plugin. Each one gets a
+//! `register_type(type_module)` function that should be called from [`TypeModuleImpl::load`] every
+//! time the module is loaded, registering interfaces before the classes implementing them. Dynamic
+//! interfaces can't have signals.
+//!
+//! ```rust,ignore
+//! #[gobject::class(final, dynamic)]
+//! mod obj {
+//!     #[derive(Default)]
+//!     pub struct MyPluginObj {}
+//! }
+//!
+//! impl gobject::TypeModuleImpl for MyPlugin {
+//!     fn load(&self, type_module: &Self::Type) -> bool {
+//!         MyPluginObj::register_type(type_module);
+//!         true
+//!     }
+//!     fn unload(&self, _type_module: &Self::Type) {}
+//! }
+//! ```
+//!
+//! ### C API
+//!
+//! The `ffi` option exports `extern "C"` functions for the type's `get_type` function, its
+//! constructors and any `#[public]` methods whose arguments are integers, floats, `bool`, strings
+//! or the class itself. Other public methods must be marked with `#[ffi(skip)]`. Matching C headers
+//! can be generated from a build script with the `gobject-build` crate:
+//!
+//! ```rust,ignore
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! gobject_build::generate_headers("src/lib.rs", out_dir).unwrap();
+//! ```
+//!
+//! ## `enum_` macro
+//!
+//! ```
+//! #[gobject::enum_(ns = "My")]
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+//! pub enum Animal {
+//!     Dog,
+//!     #[enum_value(name = "Felis catus", nick = "kitty")]
+//!     Cat,
+//! }
+//!
+//! # fn main() {
+//!     use glib::{StaticType, ToValue};
+//!     assert_eq!(Animal::static_type().name(), "MyAnimal");
+//!     assert_eq!(Animal::Cat.to_value().get::<Animal>().unwrap(), Animal::Cat);
+//! # }
+//! ```
+//!
+//! ## `flags` macro
+//!
+//! ```
+//! #[gobject::flags(ns = "My")]
+//! pub enum Permissions {
+//!     Read = 1 << 0,
+//!     Write = 1 << 1,
+//!     #[flags_value(nick = "exec")]
+//!     Execute = 1 << 2,
+//! }
+//!
+//! # fn main() {
+//!     use glib::StaticType;
+//!     assert_eq!(Permissions::static_type().name(), "MyPermissions");
+//!     let perms = Permissions::READ | Permissions::EXECUTE;
+//!     let class = glib::FlagsClass::new(Permissions::static_type()).unwrap();
+//!     assert_eq!(class.to_nick_string(perms.bits()), "read|exec");
+//! # }
+//! ```
+//!
+//! ## `boxed` macro
+//!
+//! ```
+//! #[gobject::boxed(ns = "My")]
+//! #[derive(Clone, Debug, Default, PartialEq)]
+//! pub struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! # fn main() {
+//!     use glib::{StaticType, ToValue};
+//!     assert_eq!(Point::static_type().name(), "MyPoint");
+//!     let point = Point { x: 1.0, y: 2.0 };
+//!     assert_eq!(point.to_value().get::<Point>().unwrap(), point);
+//! # }
+//! ```
+//!
 //! ## `clone_block` macro
 //!
 //! ```
@@ -114,6 +827,8 @@
 
 #[doc(hidden)]
 pub use async_trait;
+#[doc(hidden)]
+pub use bitflags;
 #[cfg(feature = "use_gio")]
 #[doc(hidden)]
 pub use gio;
@@ -129,6 +844,8 @@ pub use gtk4::gio;
 #[cfg(feature = "use_serde")]
 pub use serde;
 
+#[cfg(feature = "use_gio")]
+pub use gobject_macros::dbus_proxy;
 #[cfg(feature = "use_gio")]
 pub use gobject_macros::group_actions;
 #[cfg(feature = "use_gtk4")]
@@ -137,18 +854,62 @@ pub use gobject_macros::gtk4_widget;
 pub use gobject_macros::serde_cast;
 #[cfg(feature = "variant")]
 pub use gobject_macros::variant_cast;
-pub use gobject_macros::{class, clone_block, interface, Properties};
+pub use gobject_macros::{boxed, class, clone_block, enum_, flags, interface, Properties};
+
+/// Log domain for messages logged by this crate.
+pub(crate) const LOG_DOMAIN: &str = "gobject";
 
 #[cfg(feature = "use_gio")]
 mod action;
 #[cfg(feature = "use_gio")]
 pub use action::*;
+mod binding;
+#[doc(hidden)]
+pub use binding::*;
+mod boxed_vec;
+pub use boxed_vec::*;
 mod buildable;
 pub use buildable::*;
 mod cells;
 pub use cells::*;
+#[cfg(feature = "use_gio")]
+mod dbus;
+#[cfg(feature = "use_gio")]
+pub use dbus::*;
+mod dependencies;
+#[doc(hidden)]
+pub use dependencies::*;
+mod generic;
+#[doc(hidden)]
+pub use generic::*;
+mod history;
+pub use history::*;
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::*;
+#[cfg(feature = "use_gio")]
+mod settings;
+#[cfg(feature = "use_gio")]
+pub use settings::*;
+mod signal_detail;
+pub use signal_detail::*;
+mod signal_future;
+#[doc(hidden)]
+pub use signal_future::*;
+mod signal_guard;
+pub use signal_guard::*;
+mod signal_hook;
+pub use signal_hook::*;
 mod store;
 pub use store::*;
+mod transaction;
+#[doc(hidden)]
+pub use transaction::*;
+mod type_module;
+pub use type_module::*;
+mod validation;
+pub use validation::*;
 #[cfg(feature = "use_serde")]
 mod serde_traits;
 #[cfg(feature = "use_serde")]
@@ -0,0 +1,93 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// A replaceable implementation of a virtual method or signal handler in a generated mock class.
+///
+/// `F` is the `dyn Fn` type of the closure and `A` is a tuple of owned copies of the arguments,
+/// recorded on every call. When no closure is set, calls fall through to the parent class.
+pub struct MockMethod<F: ?Sized, A> {
+    name: &'static str,
+    func: RefCell<Option<Rc<F>>>,
+    calls: RefCell<Vec<A>>,
+    expected: Cell<Option<usize>>,
+}
+
+impl<F: ?Sized, A> MockMethod<F, A> {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            func: RefCell::new(None),
+            calls: RefCell::new(Vec::new()),
+            expected: Cell::new(None),
+        }
+    }
+    /// Returns the name of the mocked method or signal.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Replaces the implementation with `func`.
+    pub fn set(&self, func: Box<F>) -> &Self {
+        self.func.replace(Some(func.into()));
+        self
+    }
+    /// Removes the closure, so calls fall through to the parent class again.
+    pub fn unset(&self) -> &Self {
+        self.func.replace(None);
+        self
+    }
+    /// Returns `true` if a closure is set.
+    pub fn is_set(&self) -> bool {
+        self.func.borrow().is_some()
+    }
+    /// Returns the arguments of every call so far, in order.
+    pub fn calls(&self) -> Vec<A>
+    where
+        A: Clone,
+    {
+        self.calls.borrow().clone()
+    }
+    /// Returns the number of calls so far.
+    pub fn call_count(&self) -> usize {
+        self.calls.borrow().len()
+    }
+    /// Forgets all recorded calls.
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear();
+    }
+    /// Expects exactly `count` calls to have been made when [`verify`](Self::verify) is called.
+    pub fn expect_calls(&self, count: usize) -> &Self {
+        self.expected.set(Some(count));
+        self
+    }
+    /// Panics if the number of recorded calls does not match [`expect_calls`](Self::expect_calls).
+    #[track_caller]
+    pub fn verify(&self) {
+        if let Some(expected) = self.expected.get() {
+            let count = self.call_count();
+            if count != expected {
+                panic!(
+                    "Expected `{}` to be called {} time(s), but it was called {} time(s)",
+                    self.name, expected, count
+                );
+            }
+        }
+    }
+    #[doc(hidden)]
+    pub fn record(&self, args: A) -> Option<Rc<F>> {
+        self.calls.borrow_mut().push(args);
+        self.func.borrow().clone()
+    }
+}
+
+impl<F: ?Sized, A: std::fmt::Debug> std::fmt::Debug for MockMethod<F, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockMethod")
+            .field("name", &self.name)
+            .field("is_set", &self.is_set())
+            .field("calls", &self.calls.borrow())
+            .field("expected", &self.expected.get())
+            .finish()
+    }
+}
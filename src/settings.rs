@@ -0,0 +1,244 @@
+use glib::{prelude::*, ParamSpec, Value, Variant, VariantTy, VariantType};
+use std::borrow::Cow;
+
+/// A class with properties bound to GSettings keys.
+///
+/// Implemented by classes with `#[properties(settings_schema = "...")]`. Each property marked
+/// with `#[property(settings = "...")]` is bound to its key when the object is constructed, and
+/// each action marked with `#[action(settings = "...")]` keeps its state in its key.
+pub trait SettingsObject: IsA<glib::Object> {
+    /// The id of the settings schema.
+    const SETTINGS_SCHEMA: &'static str;
+    /// Returns the keys bound to properties and actions of the class.
+    fn settings_keys() -> Vec<SettingsKey>;
+    /// Returns a `.gschema.xml` document declaring the schema.
+    ///
+    /// The type, default value and summary of each key are taken from the property or action it
+    /// is bound to. The schema path is derived from the id, so `org.example.App` uses
+    /// `/org/example/App/`.
+    fn settings_schema_xml() -> String {
+        let id = Self::SETTINGS_SCHEMA;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<schemalist>\n");
+        xml.push_str(&format!(
+            "  <schema id=\"{}\" path=\"/{}/\">\n",
+            id,
+            id.replace('.', "/")
+        ));
+        for key in Self::settings_keys() {
+            xml.push_str(&format!(
+                "    <key name=\"{}\" type=\"{}\">\n",
+                key.name,
+                key.variant_type.as_str()
+            ));
+            xml.push_str(&format!(
+                "      <default>{}</default>\n",
+                glib::markup_escape_text(&key.default.print(false))
+            ));
+            if let Some(summary) = &key.summary {
+                xml.push_str(&format!(
+                    "      <summary>{}</summary>\n",
+                    glib::markup_escape_text(summary)
+                ));
+            }
+            xml.push_str("    </key>\n");
+        }
+        xml.push_str("  </schema>\n</schemalist>\n");
+        xml
+    }
+}
+
+/// A GSettings key bound to a property or action of a [`SettingsObject`].
+#[derive(Clone, Debug)]
+pub struct SettingsKey {
+    /// The name of the key.
+    pub name: &'static str,
+    /// The type the key is stored as.
+    pub variant_type: VariantType,
+    /// The default value of the key.
+    pub default: Variant,
+    /// The blurb of the property, if it has one other than its name.
+    pub summary: Option<String>,
+}
+
+impl SettingsKey {
+    /// Describes a key stored with the same mapping `g_settings_bind` uses for the type of
+    /// `pspec`, with `default` parsed as the default value.
+    #[doc(hidden)]
+    pub fn new<P: SettingsParamSpec>(name: &'static str, pspec: &ParamSpec, default: &str) -> Self {
+        let variant_type = match pspec.downcast_ref::<glib::ParamSpecVariant>() {
+            Some(pspec) => pspec.type_().map(ToOwned::to_owned),
+            None => value_to_variant(pspec.default_value()).map(|v| v.type_().to_owned()),
+        };
+        let default = Variant::parse(variant_type.as_deref(), default)
+            .unwrap_or_else(|e| panic!("Invalid default for settings key `{}`: {}", name, e));
+        Self {
+            name,
+            variant_type: default.type_().to_owned(),
+            default,
+            summary: summary(pspec),
+        }
+    }
+    /// Describes a key stored with the same mapping `g_settings_bind` uses for the type of
+    /// `pspec`, defaulting to the default value of the property.
+    #[doc(hidden)]
+    pub fn from_property<P: SettingsDefault>(name: &'static str, pspec: &ParamSpec) -> Self {
+        let default = value_to_variant(pspec.default_value())
+            .expect("settings default of a supported property type");
+        Self {
+            name,
+            variant_type: default.type_().to_owned(),
+            default,
+            summary: summary(pspec),
+        }
+    }
+    /// Describes a key storing the state of an action.
+    #[doc(hidden)]
+    pub fn with_default(name: &'static str, default: Variant) -> Self {
+        Self {
+            name,
+            variant_type: default.type_().to_owned(),
+            default,
+            summary: None,
+        }
+    }
+    /// Describes a key stored by converting property values with `to_variant`.
+    #[doc(hidden)]
+    pub fn with_mapping(
+        name: &'static str,
+        pspec: &ParamSpec,
+        variant_type: Cow<'static, VariantTy>,
+        default: Option<&str>,
+        to_variant: impl FnOnce(&Value) -> Variant,
+    ) -> Self {
+        let default = match default {
+            Some(text) => Variant::parse(Some(&variant_type), text)
+                .unwrap_or_else(|e| panic!("Invalid default for settings key `{}`: {}", name, e)),
+            None => to_variant(pspec.default_value()),
+        };
+        Self {
+            name,
+            variant_type: variant_type.into_owned(),
+            default,
+            summary: summary(pspec),
+        }
+    }
+}
+
+/// Implemented by the [`ParamSpecBuildable::ParamSpec`](crate::ParamSpecBuildable) of property
+/// types that can be bound to a settings key without `with`.
+pub trait SettingsParamSpec {}
+
+/// Implemented by the [`SettingsParamSpec`] types whose property default can be stored in a
+/// settings key, so binding them does not need `settings(default = "...")`.
+pub trait SettingsDefault: SettingsParamSpec {}
+
+macro_rules! impl_settings_param_spec {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SettingsParamSpec for $ty {}
+            impl SettingsDefault for $ty {}
+        )*
+    };
+}
+
+impl_settings_param_spec!(
+    glib::ParamSpecBoolean,
+    glib::ParamSpecChar,
+    glib::ParamSpecUChar,
+    glib::ParamSpecInt,
+    glib::ParamSpecUInt,
+    glib::ParamSpecInt64,
+    glib::ParamSpecUInt64,
+    glib::ParamSpecDouble,
+    glib::ParamSpecString,
+    glib::ParamSpecEnum,
+    glib::ParamSpecFlags,
+);
+
+impl<T> SettingsParamSpec for crate::ParamSpecEnumType<T> {}
+impl<T> SettingsDefault for crate::ParamSpecEnumType<T> {}
+impl<T> SettingsParamSpec for crate::ParamSpecFlagsType<T> {}
+impl<T> SettingsDefault for crate::ParamSpecFlagsType<T> {}
+impl SettingsParamSpec for glib::ParamSpecVariant {}
+
+fn summary(pspec: &ParamSpec) -> Option<String> {
+    pspec
+        .blurb()
+        .filter(|blurb| *blurb != pspec.name())
+        .map(ToOwned::to_owned)
+}
+
+/// Starts binding `property` of `obj` to `key`, storing `glib::Variant` properties unchanged
+/// since `g_settings_bind` has no default mapping for them.
+#[doc(hidden)]
+pub fn bind_settings<'a>(
+    settings: &'a gio::Settings,
+    key: &'a str,
+    obj: &'a impl IsA<glib::Object>,
+    property: &'a str,
+) -> gio::BindingBuilder<'a> {
+    use gio::prelude::SettingsExtManual;
+
+    let builder = settings.bind(key, obj, property);
+    let is_variant = obj
+        .find_property(property)
+        .map_or(false, |pspec| pspec.value_type() == glib::Type::VARIANT);
+    if !is_variant {
+        return builder;
+    }
+    builder
+        .mapping(|variant, _| Some(variant.to_value()))
+        .set_mapping(|value, _| value.get::<Variant>().ok())
+}
+
+/// Binds the state of `action` to `key`. Changes of the key are requested with
+/// `g_action_change_state`, so they go through the `change-state` handler of the action like any
+/// other state change.
+#[doc(hidden)]
+pub fn bind_action_state(settings: &gio::Settings, key: &str, action: &gio::SimpleAction) {
+    use gio::prelude::{ActionExt, SettingsExt};
+
+    bind_settings(settings, key, action, "state").set().build();
+    let action = action.downgrade();
+    settings.connect_changed(Some(key), move |settings, key| {
+        if let Some(action) = action.upgrade() {
+            action.change_state(&settings.value(key));
+        }
+    });
+}
+
+/// Converts `value` the same way `g_settings_bind` does when writing it to a key.
+fn value_to_variant(value: &Value) -> Option<Variant> {
+    let ty = value.type_();
+    let variant = match ty {
+        glib::Type::BOOL => value.get::<bool>().ok()?.to_variant(),
+        glib::Type::I8 => (value.get::<i8>().ok()? as u8).to_variant(),
+        glib::Type::U8 => value.get::<u8>().ok()?.to_variant(),
+        glib::Type::I32 => value.get::<i32>().ok()?.to_variant(),
+        glib::Type::U32 => value.get::<u32>().ok()?.to_variant(),
+        glib::Type::I64 => value.get::<i64>().ok()?.to_variant(),
+        glib::Type::U64 => value.get::<u64>().ok()?.to_variant(),
+        glib::Type::F64 => value.get::<f64>().ok()?.to_variant(),
+        glib::Type::STRING => value
+            .get::<Option<String>>()
+            .ok()?
+            .unwrap_or_default()
+            .to_variant(),
+        glib::Type::VARIANT => value.get::<Option<Variant>>().ok()??,
+        ty if ty == Vec::<String>::static_type() => value.get::<Vec<String>>().ok()?.to_variant(),
+        ty if ty.is_a(glib::Type::ENUM) => {
+            let (_, enum_value) = glib::EnumValue::from_value(value)?;
+            enum_value.nick().to_variant()
+        }
+        ty if ty.is_a(glib::Type::FLAGS) => {
+            let (_, flags_values) = glib::FlagsValue::from_value(value)?;
+            flags_values
+                .iter()
+                .map(|v| v.nick())
+                .collect::<Vec<_>>()
+                .to_variant()
+        }
+        _ => return None,
+    };
+    Some(variant)
+}
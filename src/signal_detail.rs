@@ -0,0 +1,21 @@
+use glib::Quark;
+
+/// A type that can be used as the detail of a signal with `#[signal(detailed = "Type")]`.
+///
+/// Implemented automatically for enums defined with [`enum_`](crate::enum_), using the nick of
+/// each value as the detail string.
+pub trait SignalDetail: Sized {
+    /// Converts the value into the detail quark passed to the signal.
+    fn to_quark(&self) -> Quark;
+    /// Converts a detail quark back into a value, returning `None` if the quark is unknown.
+    fn from_quark(quark: Quark) -> Option<Self>;
+}
+
+impl SignalDetail for Quark {
+    fn to_quark(&self) -> Quark {
+        *self
+    }
+    fn from_quark(quark: Quark) -> Option<Self> {
+        Some(quark)
+    }
+}
@@ -0,0 +1,93 @@
+use glib::{gobject_ffi, subclass::SignalId, translate::*, ObjectType};
+use std::{any::Any, cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+#[doc(hidden)]
+pub type SignalFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+type SignalFutures<T> = RefCell<Vec<SignalFuture<T>>>;
+
+struct Emission {
+    object: *mut gobject_ffi::GObject,
+    signal_id: u32,
+    futures: Rc<dyn Any>,
+}
+
+thread_local! {
+    static EMISSIONS: RefCell<Vec<Emission>> = RefCell::new(Vec::new());
+}
+
+/// Runs `emit` and collects the futures returned by async handlers of `signal_id` on `object`
+/// during the emission.
+///
+/// The generated `emit_*_future` methods await the collected futures one after another, in the
+/// order the handlers ran, so a handler's future only starts once the previous one finished.
+#[doc(hidden)]
+pub fn collect_signal_futures<O: ObjectType, T: 'static, R>(
+    object: &O,
+    signal_id: SignalId,
+    emit: impl FnOnce() -> R,
+) -> (Vec<SignalFuture<T>>, R) {
+    struct PopGuard;
+    impl Drop for PopGuard {
+        fn drop(&mut self) {
+            EMISSIONS.with(|emissions| {
+                emissions.borrow_mut().pop();
+            });
+        }
+    }
+
+    let futures = Rc::new(SignalFutures::<T>::default());
+    EMISSIONS.with(|emissions| {
+        emissions.borrow_mut().push(Emission {
+            object: object.as_ptr() as *mut _,
+            signal_id: signal_id.into_glib(),
+            futures: futures.clone(),
+        })
+    });
+    let guard = PopGuard;
+    let ret = emit();
+    drop(guard);
+    (futures.take(), ret)
+}
+
+/// Hands the future returned by an async handler to the `emit_*_future` call running the current
+/// emission.
+///
+/// When the signal was emitted any other way, such as `g_signal_emit` from C, nothing waits for
+/// the result: the future is spawned on the thread-default main context and the handler returns
+/// `Default::default()` to the emission as a placeholder.
+#[doc(hidden)]
+pub fn add_signal_future<O: ObjectType, T: 'static>(
+    object: &O,
+    future: impl Future<Output = T> + 'static,
+) {
+    let object = object.as_ptr() as *mut gobject_ffi::GObject;
+    let future: SignalFuture<T> = Box::pin(future);
+    let hint = unsafe { gobject_ffi::g_signal_get_invocation_hint(object as *mut _) };
+    let future = if hint.is_null() {
+        Some(future)
+    } else {
+        let signal_id = unsafe { (*hint).signal_id };
+        EMISSIONS.with(|emissions| {
+            let futures = emissions
+                .borrow()
+                .iter()
+                .rev()
+                .find(|e| e.object == object && e.signal_id == signal_id)
+                .map(|e| e.futures.clone());
+            match futures.map(|f| f.downcast::<SignalFutures<T>>()) {
+                Some(Ok(futures)) => {
+                    futures.borrow_mut().push(future);
+                    None
+                }
+                _ => Some(future),
+            }
+        })
+    };
+    // emitted without `emit_*_future`, so nothing is waiting for the result
+    if let Some(future) = future {
+        glib::MainContext::ref_thread_default().spawn_local(async move {
+            future.await;
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use glib::{prelude::*, Object, SignalHandlerId, WeakRef};
+use std::{cell::RefCell, marker::PhantomData};
+
+/// A signal handler connected to an object, which is disconnected when the guard is dropped.
+///
+/// Returned from the generated `connect_*_scoped` methods. The guard only holds a weak reference
+/// to the emitting object, so it does not keep the object alive.
+#[must_use = "the signal handler is disconnected when the guard is dropped"]
+pub struct SignalGuard<T: ?Sized> {
+    object: WeakRef<Object>,
+    handler_id: Option<SignalHandlerId>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: ObjectType> SignalGuard<T> {
+    /// Creates a guard for the handler `handler_id` connected to `object`.
+    pub fn new(object: &T, handler_id: SignalHandlerId) -> Self {
+        let object = unsafe { object.unsafe_cast_ref::<Object>() };
+        Self {
+            object: object.downgrade(),
+            handler_id: Some(handler_id),
+            phantom: PhantomData,
+        }
+    }
+    /// Returns the object the handler is connected to, or `None` if it was finalized.
+    pub fn object(&self) -> Option<T> {
+        self.object.upgrade().map(|o| unsafe { o.unsafe_cast() })
+    }
+}
+
+impl<T: ?Sized> SignalGuard<T> {
+    /// Returns the ID of the guarded handler.
+    pub fn handler_id(&self) -> &SignalHandlerId {
+        self.handler_id.as_ref().unwrap()
+    }
+    /// Blocks the handler until [`unblock`](Self::unblock) is called.
+    pub fn block(&self) {
+        if let Some(object) = self.object.upgrade() {
+            object.block_signal(self.handler_id());
+        }
+    }
+    /// Unblocks the handler after a call to [`block`](Self::block).
+    pub fn unblock(&self) {
+        if let Some(object) = self.object.upgrade() {
+            object.unblock_signal(self.handler_id());
+        }
+    }
+    /// Disconnects the handler now. Equivalent to dropping the guard.
+    pub fn disconnect(self) {}
+    /// Releases the handler without disconnecting it, leaving it connected for the lifetime of
+    /// the object.
+    pub fn forget(mut self) -> SignalHandlerId {
+        self.handler_id.take().unwrap()
+    }
+    /// Converts into a guard for a `glib::Object`, to store guards for different types together.
+    pub fn upcast(mut self) -> SignalGuard<Object> {
+        SignalGuard {
+            object: self.object.clone(),
+            handler_id: self.handler_id.take(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for SignalGuard<T> {
+    fn drop(&mut self) {
+        if let Some(handler_id) = self.handler_id.take() {
+            if let Some(object) = self.object.upgrade() {
+                object.disconnect(handler_id);
+            }
+        }
+    }
+}
+
+impl<T: ?Sized> std::fmt::Debug for SignalGuard<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignalGuard")
+            .field("object", &self.object.upgrade())
+            .field("handler_id", &self.handler_id)
+            .finish()
+    }
+}
+
+/// A collection of [`SignalGuard`]s for objects of any type, disconnecting all of them when
+/// dropped or cleared.
+///
+/// Useful as a field of an object or widget that connects to signals on other objects and should
+/// stop receiving them once it is disposed.
+#[derive(Debug, Default)]
+pub struct SignalGuards(RefCell<Vec<SignalGuard<Object>>>);
+
+impl SignalGuards {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds `guard`, keeping its handler connected until the collection is cleared or dropped.
+    pub fn add<T: ?Sized>(&self, guard: SignalGuard<T>) {
+        self.0.borrow_mut().push(guard.upcast());
+    }
+    /// Returns the number of guards held.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+    /// Returns `true` if no guards are held.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+    /// Blocks all handlers.
+    pub fn block_all(&self) {
+        for guard in self.0.borrow().iter() {
+            guard.block();
+        }
+    }
+    /// Unblocks all handlers.
+    pub fn unblock_all(&self) {
+        for guard in self.0.borrow().iter() {
+            guard.unblock();
+        }
+    }
+    /// Disconnects all handlers.
+    pub fn clear(&self) {
+        // taken out first so handlers dropped while disconnecting can use the collection
+        let guards = self.0.take();
+        drop(guards);
+    }
+}
+
+impl<T: ?Sized> Extend<SignalGuard<T>> for SignalGuards {
+    fn extend<I: IntoIterator<Item = SignalGuard<T>>>(&mut self, iter: I) {
+        self.0
+            .get_mut()
+            .extend(iter.into_iter().map(SignalGuard::upcast));
+    }
+}
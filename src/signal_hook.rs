@@ -0,0 +1,121 @@
+use glib::{
+    gobject_ffi,
+    subclass::SignalInvocationHint,
+    translate::{from_glib, IntoGlib, ToGlibPtr},
+    Type, Value,
+};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// A handle to an emission hook installed by a `#[signal_hook]` method.
+///
+/// Emission hooks are called for every emission of a signal on any instance of the type that
+/// registered it, including subclasses. Handles are returned from the generated `*_hook` methods,
+/// and the hook can be removed early with [`EmissionHook::remove`]. Hooks returning `bool` are
+/// also removed the first time they return `false`.
+#[derive(Debug)]
+pub struct EmissionHook {
+    signal_id: AtomicU32,
+    hook_id: AtomicU64,
+}
+
+type HookFunc = dyn Fn(&SignalInvocationHint, &[Value]) -> bool + Send + Sync + 'static;
+
+struct HookData {
+    hook: &'static EmissionHook,
+    func: Box<HookFunc>,
+}
+
+impl EmissionHook {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        Self {
+            signal_id: AtomicU32::new(0),
+            hook_id: AtomicU64::new(0),
+        }
+    }
+    #[doc(hidden)]
+    pub fn install<F>(&'static self, type_: Type, signal: &str, ref_class: bool, func: F)
+    where
+        F: Fn(&SignalInvocationHint, &[Value]) -> bool + Send + Sync + 'static,
+    {
+        if self.is_installed() {
+            return;
+        }
+        unsafe {
+            if ref_class {
+                if type_.is_a(Type::INTERFACE) {
+                    gobject_ffi::g_type_default_interface_ref(type_.into_glib());
+                } else {
+                    gobject_ffi::g_type_class_ref(type_.into_glib());
+                }
+            }
+            let mut signal_id = 0;
+            let mut detail = 0;
+            let found: bool = from_glib(gobject_ffi::g_signal_parse_name(
+                signal.to_glib_none().0,
+                type_.into_glib(),
+                &mut signal_id,
+                &mut detail,
+                true.into_glib(),
+            ));
+            if !found {
+                panic!("Signal '{}' not found on type '{}'", signal, type_);
+            }
+            let data = Box::new(HookData {
+                hook: self,
+                func: Box::new(func),
+            });
+            self.signal_id.store(signal_id, Ordering::Release);
+            let hook_id = gobject_ffi::g_signal_add_emission_hook(
+                signal_id,
+                detail,
+                Some(hook_trampoline),
+                Box::into_raw(data) as glib::ffi::gpointer,
+                Some(hook_destroy),
+            );
+            self.hook_id.store(hook_id as u64, Ordering::Release);
+        }
+    }
+    /// Returns `true` if the hook is currently installed.
+    pub fn is_installed(&self) -> bool {
+        self.hook_id.load(Ordering::Acquire) != 0
+    }
+    /// Removes the hook. Returns `false` if the hook was already removed.
+    pub fn remove(&self) -> bool {
+        let hook_id = self.hook_id.swap(0, Ordering::AcqRel);
+        if hook_id == 0 {
+            return false;
+        }
+        let signal_id = self.signal_id.load(Ordering::Acquire);
+        unsafe {
+            gobject_ffi::g_signal_remove_emission_hook(signal_id, hook_id as _);
+        }
+        true
+    }
+}
+
+impl Default for EmissionHook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe extern "C" fn hook_trampoline(
+    ihint: *mut gobject_ffi::GSignalInvocationHint,
+    n_param_values: u32,
+    param_values: *const gobject_ffi::GValue,
+    data: glib::ffi::gpointer,
+) -> glib::ffi::gboolean {
+    let data = &*(data as *const HookData);
+    let hint = &*(ihint as *const SignalInvocationHint);
+    let args = std::slice::from_raw_parts(param_values as *const Value, n_param_values as usize);
+    let keep = (data.func)(hint, args);
+    if !keep {
+        data.hook.hook_id.store(0, Ordering::Release);
+    }
+    keep.into_glib()
+}
+
+unsafe extern "C" fn hook_destroy(data: glib::ffi::gpointer) {
+    drop(Box::from_raw(data as *mut HookData));
+}
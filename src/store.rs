@@ -7,6 +7,18 @@ use glib::{
 };
 use std::{ops::DerefMut, sync::atomic::Ordering};
 
+#[doc(hidden)]
+pub fn param_values_eq(pspec: &glib::ParamSpec, a: &Value, b: &Value) -> bool {
+    use glib::translate::ToGlibPtr;
+    unsafe {
+        glib::gobject_ffi::g_param_values_cmp(
+            pspec.to_glib_none().0,
+            a.to_glib_none().0,
+            b.to_glib_none().0,
+        ) == 0
+    }
+}
+
 pub trait ParamStore {
     type Type: ValueType;
 }
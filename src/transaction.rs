@@ -0,0 +1,138 @@
+use glib::{
+    gobject_ffi, once_cell::sync::Lazy, prelude::*, subclass::types::ClassStruct, translate::*,
+};
+use std::{collections::HashMap, sync::Mutex};
+
+/// Notifications held back while a transaction is open.
+#[derive(Default)]
+struct Pending {
+    depth: usize,
+    pspecs: Vec<glib::ParamSpec>,
+}
+
+fn pending_quark() -> glib::Quark {
+    glib::Quark::from_str("gobject-transaction-pending")
+}
+
+fn pending(obj: &glib::Object) -> Option<&Mutex<Pending>> {
+    unsafe {
+        obj.qdata::<Mutex<Pending>>(pending_quark())
+            .map(|pending| &*pending.as_ptr())
+    }
+}
+
+/// Holds the property notifications of an object until dropped, then emits each one once.
+///
+/// Used by the `batch` and `edit` methods generated with `#[properties(transactions)]`. Unlike
+/// [`ObjectExt::freeze_notify`], notifications can be dropped again with
+/// [`discard`](Self::discard) before they are emitted.
+#[doc(hidden)]
+pub struct Transaction<'o> {
+    obj: &'o glib::Object,
+    start: usize,
+}
+
+impl<'o> Transaction<'o> {
+    pub fn begin(obj: &'o glib::Object) -> Self {
+        static INIT: Lazy<Mutex<()>> = Lazy::new(Default::default);
+        if pending(obj).is_none() {
+            let _init = INIT.lock().unwrap();
+            if pending(obj).is_none() {
+                unsafe { obj.set_qdata(pending_quark(), Mutex::new(Pending::default())) };
+            }
+        }
+        let mut pending = pending(obj).unwrap().lock().unwrap();
+        pending.depth += 1;
+        Self {
+            obj,
+            start: pending.pspecs.len(),
+        }
+    }
+    /// Drops the notification for `pspec` if it was queued after this transaction began.
+    pub fn discard(&self, pspec: &glib::ParamSpec) {
+        let mut pending = pending(self.obj).unwrap().lock().unwrap();
+        let start = self.start.min(pending.pspecs.len());
+        if let Some(index) = pending.pspecs[start..].iter().position(|p| p == pspec) {
+            pending.pspecs.remove(start + index);
+        }
+    }
+}
+
+impl<'o> Drop for Transaction<'o> {
+    fn drop(&mut self) {
+        let pspecs = {
+            let mut pending = pending(self.obj).unwrap().lock().unwrap();
+            pending.depth -= 1;
+            if pending.depth > 0 {
+                return;
+            }
+            std::mem::take(&mut pending.pspecs)
+        };
+        // dispatched together on thaw, so computed properties are only notified once
+        let _guard = self.obj.freeze_notify();
+        for pspec in pspecs {
+            self.obj.notify_by_pspec(&pspec);
+        }
+    }
+}
+
+type DispatchFn =
+    unsafe extern "C" fn(*mut gobject_ffi::GObject, u32, *mut *mut gobject_ffi::GParamSpec);
+
+/// The `dispatch_properties_changed` replaced in each class passed to [`install_transactions`].
+static PARENT_DISPATCH: Lazy<Mutex<HashMap<glib::Type, Option<DispatchFn>>>> =
+    Lazy::new(Default::default);
+
+/// Overrides `dispatch_properties_changed` in `class` so notifications can be held by a
+/// [`Transaction`].
+#[doc(hidden)]
+pub fn install_transactions<C: ClassStruct>(class: &mut C) {
+    unsafe {
+        let class = class as *mut C as *mut gobject_ffi::GObjectClass;
+        let type_: glib::Type = from_glib((*class).g_type_class.g_type);
+        let mut parents = PARENT_DISPATCH.lock().unwrap();
+        let mut ancestor = type_.parent();
+        while let Some(parent) = ancestor {
+            if parents.contains_key(&parent) {
+                // already inherited from the parent class
+                return;
+            }
+            ancestor = parent.parent();
+        }
+        parents.insert(type_, (*class).dispatch_properties_changed);
+        (*class).dispatch_properties_changed = Some(dispatch_properties_changed);
+    }
+}
+
+unsafe extern "C" fn dispatch_properties_changed(
+    obj: *mut gobject_ffi::GObject,
+    n_pspecs: u32,
+    pspecs: *mut *mut gobject_ffi::GParamSpec,
+) {
+    let object: Borrowed<glib::Object> = from_glib_borrow(obj);
+    if let Some(pending) = pending(&object) {
+        let mut pending = pending.lock().unwrap();
+        if pending.depth > 0 {
+            for i in 0..n_pspecs as usize {
+                let pspec: glib::ParamSpec = from_glib_none(*pspecs.add(i));
+                if !pending.pspecs.contains(&pspec) {
+                    pending.pspecs.push(pspec);
+                }
+            }
+            return;
+        }
+    }
+    let parent = {
+        let parents = PARENT_DISPATCH.lock().unwrap();
+        let mut type_ = object.type_();
+        loop {
+            if let Some(parent) = parents.get(&type_) {
+                break *parent;
+            }
+            type_ = type_.parent().unwrap();
+        }
+    };
+    if let Some(parent) = parent {
+        parent(obj, n_pspecs, pspecs);
+    }
+}
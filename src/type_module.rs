@@ -0,0 +1,364 @@
+use glib::{
+    gobject_ffi,
+    prelude::*,
+    subclass::{
+        interface::PrerequisiteList,
+        prelude::*,
+        types::{InitializingObject, InitializingType, InterfaceList},
+    },
+    translate::*,
+    Object,
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    mem, ptr,
+    sync::{Arc, Mutex},
+};
+
+glib::wrapper! {
+    /// A `GTypeModule`, used to register dynamic types that can be loaded and unloaded from a
+    /// plugin.
+    pub struct TypeModule(Object<gobject_ffi::GTypeModule, gobject_ffi::GTypeModuleClass>);
+
+    match fn {
+        type_ => || gobject_ffi::g_type_module_get_type(),
+    }
+}
+
+pub trait TypeModuleExt: IsA<TypeModule> + 'static {
+    /// Increments the use count of the module, loading it if it was not already in use.
+    fn use_(&self) -> bool;
+    /// Decrements the use count of the module, unloading it once it is no longer in use.
+    fn unuse(&self);
+    fn set_name(&self, name: &str);
+}
+
+impl<O: IsA<TypeModule>> TypeModuleExt for O {
+    fn use_(&self) -> bool {
+        unsafe {
+            from_glib(gobject_ffi::g_type_module_use(
+                self.as_ref().to_glib_none().0,
+            ))
+        }
+    }
+    fn unuse(&self) {
+        unsafe {
+            gobject_ffi::g_type_module_unuse(self.as_ref().to_glib_none().0);
+        }
+    }
+    fn set_name(&self, name: &str) {
+        unsafe {
+            gobject_ffi::g_type_module_set_name(
+                self.as_ref().to_glib_none().0,
+                name.to_glib_none().0,
+            );
+        }
+    }
+}
+
+pub trait TypeModuleImpl: ObjectImpl {
+    /// Loads the module and registers its types. Returns `false` if loading failed.
+    fn load(&self, type_module: &Self::Type) -> bool;
+    /// Unloads the module. Types registered by the module stay registered, but their classes
+    /// are finalized until the module is loaded again.
+    fn unload(&self, type_module: &Self::Type);
+}
+
+unsafe impl<T: TypeModuleImpl> IsSubclassable<T> for TypeModule {
+    fn class_init(class: &mut glib::Class<Self>) {
+        Self::parent_class_init::<T>(class);
+        let klass = class.as_mut();
+        klass.load = Some(type_module_load::<T>);
+        klass.unload = Some(type_module_unload::<T>);
+    }
+}
+
+unsafe extern "C" fn type_module_load<T: TypeModuleImpl>(
+    ptr: *mut gobject_ffi::GTypeModule,
+) -> glib::ffi::gboolean {
+    let instance = &*(ptr as *mut T::Instance);
+    let imp = instance.imp();
+    let wrap: Borrowed<TypeModule> = from_glib_borrow(ptr);
+    imp.load(wrap.unsafe_cast_ref()).into_glib()
+}
+
+unsafe extern "C" fn type_module_unload<T: TypeModuleImpl>(ptr: *mut gobject_ffi::GTypeModule) {
+    let instance = &*(ptr as *mut T::Instance);
+    let imp = instance.imp();
+    let wrap: Borrowed<TypeModule> = from_glib_borrow(ptr);
+    imp.unload(wrap.unsafe_cast_ref())
+}
+
+/// Returns the type registered for `T` by [`register_dynamic_type`].
+///
+/// # Panics
+///
+/// Panics if the type has not been registered with a [`TypeModule`] yet.
+pub fn dynamic_type<T: ObjectSubclass>() -> glib::Type {
+    let type_ = unsafe { T::type_data().as_ref().type_() };
+    assert!(
+        type_.is_valid(),
+        "Dynamic type `{}` used before being registered with a TypeModule",
+        T::NAME
+    );
+    type_
+}
+
+/// A value stored in the class data of a dynamic class, returned by [`dynamic_class_static`].
+///
+/// Holds a reference on the class, so the class stays initialized and its module stays in use
+/// until the guard is dropped.
+pub struct DynamicClassStatic<V: 'static> {
+    value: Arc<V>,
+    class: ptr::NonNull<gobject_ffi::GTypeClass>,
+}
+
+impl<V: 'static> DynamicClassStatic<V> {
+    /// Keeps the value alive for the rest of the program and releases the class.
+    ///
+    /// Used for `ObjectImpl::properties` and `ObjectImpl::signals`, which glib requires to be
+    /// `'static` even though it only reads them while initializing the class. Each time the
+    /// class is initialized again after its module was reloaded, one more value is kept.
+    #[doc(hidden)]
+    pub fn leak(this: Self) -> &'static V {
+        let value = Arc::into_raw(this.value.clone());
+        unsafe { &*value }
+    }
+}
+
+impl<V: 'static> std::ops::Deref for DynamicClassStatic<V> {
+    type Target = V;
+    fn deref(&self) -> &V {
+        &self.value
+    }
+}
+
+impl<V: 'static> Drop for DynamicClassStatic<V> {
+    fn drop(&mut self) {
+        unsafe { gobject_ffi::g_type_class_unref(self.class.as_ptr() as *mut _) };
+    }
+}
+
+/// Returns a value of type `V` stored in the class data of `T`, calling `init` to create it the
+/// first time it is accessed after the class is initialized.
+///
+/// Dynamic classes are initialized again each time their module is reloaded, so this is used in
+/// place of statics for values like param specs that can only be installed on one class.
+pub fn dynamic_class_static<T: ObjectSubclass, V: Send + Sync + 'static>(
+    init: impl FnOnce() -> V,
+) -> DynamicClassStatic<V> {
+    let type_ = dynamic_type::<T>();
+    unsafe {
+        // also works while the class is being initialized, since the class pointer is already
+        // set up by then
+        let class =
+            gobject_ffi::g_type_class_ref(type_.into_glib()) as *mut gobject_ffi::GTypeClass;
+        let class = ptr::NonNull::new(class).unwrap();
+        let statics = T::type_data()
+            .as_ref()
+            .class_data::<ClassStatics>(type_)
+            .unwrap();
+        let key = TypeId::of::<V>();
+        let value = statics.lock().unwrap().get(&key).cloned();
+        let value = value.unwrap_or_else(|| {
+            // initialize outside the lock, since `init` might need to access other statics
+            let value: Arc<dyn Any + Send + Sync> = Arc::new(init());
+            statics.lock().unwrap().entry(key).or_insert(value).clone()
+        });
+        DynamicClassStatic {
+            value: value.downcast().unwrap(),
+            class,
+        }
+    }
+}
+
+type ClassStatics = Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>;
+
+/// Registers `T` as a dynamic type belonging to `type_module`, along with any interfaces it
+/// implements.
+///
+/// Should be called from [`TypeModuleImpl::load`] every time the module is loaded. Returns the
+/// same type each time.
+///
+/// The instance private data of dynamic types only holds the implementation struct, so instance
+/// data set with `InitializingObject::set_instance_data` is not supported.
+pub fn register_dynamic_type<T: ObjectSubclass>(type_module: &impl IsA<TypeModule>) -> glib::Type {
+    assert!(
+        mem::align_of::<T>() <= 2 * mem::size_of::<usize>(),
+        "Alignment {} of type not supported, bigger than {}",
+        mem::align_of::<T>(),
+        2 * mem::size_of::<usize>(),
+    );
+    unsafe {
+        let module = type_module.as_ref().to_glib_none().0;
+        let name = std::ffi::CString::new(T::NAME).unwrap();
+        let info = gobject_ffi::GTypeInfo {
+            class_size: mem::size_of::<T::Class>() as u16,
+            base_init: None,
+            base_finalize: None,
+            class_init: Some(dynamic_class_init::<T>),
+            class_finalize: None,
+            class_data: ptr::null(),
+            instance_size: mem::size_of::<T::Instance>() as u16,
+            n_preallocs: 0,
+            instance_init: Some(dynamic_instance_init::<T>),
+            value_table: ptr::null(),
+        };
+        let flags = if T::ABSTRACT {
+            gobject_ffi::G_TYPE_FLAG_ABSTRACT
+        } else {
+            0
+        };
+        let type_: glib::Type = from_glib(gobject_ffi::g_type_module_register_type(
+            module,
+            <T::ParentType as StaticType>::static_type().into_glib(),
+            name.as_ptr(),
+            &info,
+            flags,
+        ));
+        assert!(type_.is_valid(), "Failed to register `{}`", T::NAME);
+
+        for (iface_type, iface_info) in T::Interfaces::iface_infos() {
+            let iface_info = &iface_info as *const _ as *const gobject_ffi::GInterfaceInfo;
+            gobject_ffi::g_type_module_add_interface(
+                module,
+                type_.into_glib(),
+                iface_type,
+                iface_info,
+            );
+        }
+
+        let data = T::type_data().as_mut();
+        if !data.type_().is_valid() {
+            data.type_ = type_;
+            // the private data is the implementation struct itself
+            data.private_imp_offset = 0;
+            let mut init = type_;
+            T::type_init(&mut *(&mut init as *mut glib::Type as *mut InitializingType<T>));
+        }
+
+        type_
+    }
+}
+
+unsafe extern "C" fn dynamic_class_init<T: ObjectSubclass>(
+    klass: glib::ffi::gpointer,
+    _klass_data: glib::ffi::gpointer,
+) {
+    let data = T::type_data().as_mut();
+    // the class is initialized again each time the module is reloaded, so the private data has
+    // to be added again from its size rather than the previous offset
+    let mut private_offset = mem::size_of::<T>() as i32;
+    gobject_ffi::g_type_class_adjust_private_offset(klass, &mut private_offset);
+    data.private_offset = private_offset as isize;
+    data.class_data = None;
+    data.set_class_data(data.type_(), ClassStatics::default());
+
+    let gobject_klass = &mut *(klass as *mut gobject_ffi::GObjectClass);
+    gobject_klass.finalize = Some(dynamic_finalize::<T>);
+
+    let klass = &mut *(klass as *mut T::Class);
+    let parent_class =
+        gobject_ffi::g_type_class_peek_parent(klass as *mut _ as glib::ffi::gpointer);
+    assert!(!parent_class.is_null());
+    data.parent_class = parent_class;
+    klass.class_init();
+    T::class_init(klass);
+}
+
+unsafe extern "C" fn dynamic_instance_init<T: ObjectSubclass>(
+    obj: *mut gobject_ffi::GTypeInstance,
+    klass: glib::ffi::gpointer,
+) {
+    let data = T::type_data();
+    let private_offset = data.as_ref().private_offset;
+    let imp = (obj as *mut u8).offset(private_offset) as *mut T;
+    let klass = &*(klass as *const T::Class);
+    ptr::write(imp, T::with_class(klass));
+
+    T::Instance::instance_init(&mut *(obj as *mut T::Instance));
+
+    let obj = from_glib_borrow::<_, Object>(obj as *mut gobject_ffi::GObject);
+    let obj = Borrowed::new(obj.into_inner().unsafe_cast::<T::Type>());
+    let obj = &*(&obj as *const Borrowed<T::Type> as *const InitializingObject<T>);
+    T::instance_init(obj);
+}
+
+unsafe extern "C" fn dynamic_finalize<T: ObjectSubclass>(obj: *mut gobject_ffi::GObject) {
+    let data = T::type_data();
+    let private_offset = data.as_ref().private_offset;
+    let imp = (obj as *mut u8).offset(private_offset) as *mut T;
+    ptr::drop_in_place(imp);
+
+    let parent_class = &*(data.as_ref().parent_class() as *const gobject_ffi::GObjectClass);
+    if let Some(func) = parent_class.finalize {
+        func(obj);
+    }
+}
+
+/// Registers `T` as a dynamic interface belonging to `type_module`.
+///
+/// Should be called from [`TypeModuleImpl::load`] every time the module is loaded, before
+/// registering any dynamic types implementing it. Returns the same type each time.
+pub fn register_dynamic_interface<T: ObjectInterface>(
+    type_module: &impl IsA<TypeModule>,
+) -> glib::Type {
+    unsafe {
+        let module = type_module.as_ref().to_glib_none().0;
+        let name = std::ffi::CString::new(T::NAME).unwrap();
+        let registered =
+            gobject_ffi::g_type_from_name(name.as_ptr()) != gobject_ffi::G_TYPE_INVALID;
+        let info = gobject_ffi::GTypeInfo {
+            class_size: mem::size_of::<T>() as u16,
+            base_init: None,
+            base_finalize: None,
+            class_init: Some(dynamic_interface_init::<T>),
+            class_finalize: None,
+            class_data: ptr::null(),
+            instance_size: 0,
+            n_preallocs: 0,
+            instance_init: None,
+            value_table: ptr::null(),
+        };
+        let type_: glib::Type = from_glib(gobject_ffi::g_type_module_register_type(
+            module,
+            gobject_ffi::G_TYPE_INTERFACE,
+            name.as_ptr(),
+            &info,
+            0,
+        ));
+        assert!(type_.is_valid(), "Failed to register `{}`", T::NAME);
+
+        if !registered {
+            for prerequisite in T::Prerequisites::types() {
+                gobject_ffi::g_type_interface_add_prerequisite(type_.into_glib(), prerequisite);
+            }
+            let mut init = type_;
+            T::type_init(&mut *(&mut init as *mut glib::Type as *mut InitializingType<T>));
+        }
+
+        type_
+    }
+}
+
+unsafe extern "C" fn dynamic_interface_init<T: ObjectInterface>(
+    iface: glib::ffi::gpointer,
+    _iface_data: glib::ffi::gpointer,
+) {
+    assert!(
+        T::signals().is_empty(),
+        "Signals not supported on dynamic interface `{}`",
+        T::NAME
+    );
+    // properties stay installed when the interface is unloaded, so they are only installed the
+    // first time the interface is initialized
+    for pspec in T::properties() {
+        let installed =
+            gobject_ffi::g_object_interface_find_property(iface, pspec.name().to_glib_none().0);
+        if installed.is_null() {
+            gobject_ffi::g_object_interface_install_property(iface, pspec.to_glib_none().0);
+        }
+    }
+    (*(iface as *mut T)).interface_init();
+}
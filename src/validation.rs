@@ -0,0 +1,71 @@
+use glib::{prelude::*, translate::*};
+use std::cell::RefCell;
+
+/// Error returned from the `try_set_*` methods generated for properties with a `validate`
+/// function.
+pub type ValidationError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A `try_set_*` call waiting for the result of validation in `set_property`.
+struct PendingSet {
+    object: *mut glib::gobject_ffi::GObject,
+    name: &'static str,
+    error: Option<ValidationError>,
+}
+
+thread_local! {
+    static PENDING_SETS: RefCell<Vec<PendingSet>> = RefCell::new(Vec::new());
+}
+
+/// Runs `set`, returning the error from the validation function if `set_property` rejects the
+/// value of property `name` on `obj`.
+#[doc(hidden)]
+pub fn try_set_property(
+    obj: &impl ObjectType,
+    name: &'static str,
+    set: impl FnOnce(),
+) -> Result<(), ValidationError> {
+    struct PopGuard;
+    impl Drop for PopGuard {
+        fn drop(&mut self) {
+            PENDING_SETS.with(|pending| {
+                pending.borrow_mut().pop();
+            });
+        }
+    }
+
+    PENDING_SETS.with(|pending| {
+        pending.borrow_mut().push(PendingSet {
+            object: obj.as_object_ref().to_glib_none().0,
+            name,
+            error: None,
+        })
+    });
+    let guard = PopGuard;
+    set();
+    let error = PENDING_SETS.with(|pending| pending.borrow_mut().last_mut().unwrap().error.take());
+    drop(guard);
+    error.map_or(Ok(()), Err)
+}
+
+/// Reports a value rejected by the validation function of property `name`, either to the
+/// `try_set_*` call that set it or as a warning.
+#[doc(hidden)]
+pub fn reject_property_value(obj: &impl ObjectType, name: &str, error: ValidationError) {
+    let object: *mut glib::gobject_ffi::GObject = obj.as_object_ref().to_glib_none().0;
+    let error = PENDING_SETS.with(|pending| match pending.borrow_mut().last_mut() {
+        Some(set) if set.object == object && set.name == name => {
+            set.error = Some(error);
+            None
+        }
+        _ => Some(error),
+    });
+    if let Some(error) = error {
+        glib::g_warning!(
+            crate::LOG_DOMAIN,
+            "value for property '{}' of type '{}' rejected: {}",
+            name,
+            obj.type_().name(),
+            error
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use glib::prelude::*;
+
+#[gobject::boxed(ns = "Test")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Inner {
+    label: String,
+}
+
+#[gobject::boxed(shared)]
+#[derive(Clone, Debug)]
+pub struct SharedLabel(std::sync::Arc<Inner>);
+
+#[gobject::class(final)]
+mod obj_boxed {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct ObjBoxed {
+        #[property(get, set)]
+        point: RefCell<super::Point>,
+        #[property(get, set)]
+        label: RefCell<Option<super::SharedLabel>>,
+    }
+}
+
+#[test]
+fn boxed_type() {
+    assert_eq!(Point::static_type().name(), "TestPoint");
+    assert!(Point::static_type().is_a(glib::Type::BOXED));
+    let point = Point { x: 3, y: 4 };
+    let value = point.to_value();
+    assert_eq!(value.type_(), Point::static_type());
+    assert_eq!(value.get::<Point>().unwrap(), point);
+    assert_eq!(value.get::<&Point>().unwrap(), &point);
+    assert!(None::<Point>
+        .to_value()
+        .get::<Option<Point>>()
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn shared_boxed_type() {
+    assert_eq!(SharedLabel::static_type().name(), "SharedLabel");
+    let label = SharedLabel(std::sync::Arc::new(Inner {
+        label: String::from("hello"),
+    }));
+    let value = label.to_value();
+    assert_eq!(std::sync::Arc::strong_count(&label.0), 2);
+    let other = value.get::<SharedLabel>().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&label.0, &other.0));
+    drop(value);
+    assert_eq!(std::sync::Arc::strong_count(&label.0), 2);
+    drop(other);
+    assert_eq!(std::sync::Arc::strong_count(&label.0), 1);
+}
+
+#[test]
+fn boxed_property() {
+    let obj = glib::Object::new::<ObjBoxed>(&[]).unwrap();
+    let pspec = obj.find_property("point").unwrap();
+    assert_eq!(pspec.value_type(), Point::static_type());
+    assert!(pspec.downcast_ref::<glib::ParamSpecBoxed>().is_some());
+    assert_eq!(obj.point(), Point::default());
+    obj.set_point(Point { x: 1, y: 2 });
+    assert_eq!(obj.property::<Point>("point"), Point { x: 1, y: 2 });
+
+    assert!(obj.label().is_none());
+    obj.set_label(Some(SharedLabel(std::sync::Arc::new(Inner {
+        label: String::from("abc"),
+    }))));
+    assert_eq!(obj.label().unwrap().0.label, "abc");
+}
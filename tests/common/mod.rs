@@ -0,0 +1,32 @@
+use gio::prelude::*;
+
+/// Connects two ends of a socket pair as peer-to-peer D-Bus connections, returning the
+/// server and client side.
+pub fn connect_peers() -> (gio::DBusConnection, gio::DBusConnection) {
+    fn stream(socket: std::os::unix::net::UnixStream) -> gio::SocketConnection {
+        unsafe { gio::Socket::from_fd(socket) }
+            .unwrap()
+            .connection_factory_create_connection()
+    }
+    let (server, client) = std::os::unix::net::UnixStream::pair().unwrap();
+    let (server, client) = (stream(server), stream(client));
+    let guid = gio::dbus_generate_guid();
+    glib::MainContext::default().block_on(async move {
+        let (server, client) = futures_util::future::join(
+            gio::DBusConnection::new_future(
+                &server,
+                Some(guid.as_str()),
+                gio::DBusConnectionFlags::AUTHENTICATION_SERVER,
+                None,
+            ),
+            gio::DBusConnection::new_future(
+                &client,
+                None,
+                gio::DBusConnectionFlags::AUTHENTICATION_CLIENT,
+                None,
+            ),
+        )
+        .await;
+        (server.unwrap(), client.unwrap())
+    })
+}
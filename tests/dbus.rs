@@ -0,0 +1,145 @@
+#![cfg(feature = "use_gio")]
+
+use futures_util::StreamExt;
+use gio::prelude::*;
+use gobject::DBusObject;
+
+mod common;
+
+#[gobject::class(final, dbus(name = "org.example.Counter"))]
+mod counter {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct Counter {
+        #[property(get, set)]
+        #[dbus]
+        count: Cell<i32>,
+        #[property(get, set)]
+        #[dbus(name = "Title")]
+        label: RefCell<String>,
+        #[property(get, set)]
+        hidden: Cell<bool>,
+    }
+    impl Counter {
+        #[public]
+        #[dbus]
+        fn add(&self, amount: i32) -> i32 {
+            let count = self.count.get() + amount;
+            self.instance().set_count(count);
+            count
+        }
+        #[public]
+        #[dbus]
+        fn rename(&self, label: &str) -> Result<(), glib::Error> {
+            if label.is_empty() {
+                return Err(glib::Error::new(
+                    gio::IOErrorEnum::InvalidArgument,
+                    "empty label",
+                ));
+            }
+            self.instance().set_label(label.to_owned());
+            Ok(())
+        }
+        #[public]
+        fn local_only(&self) {}
+        #[signal]
+        #[dbus]
+        fn reset(&self, previous: i32) {}
+    }
+}
+
+#[test]
+fn dbus_export() {
+    let node = gio::DBusNodeInfo::for_xml(&Counter::dbus_introspection_xml()).unwrap();
+    let info = node.lookup_interface("org.example.Counter").unwrap();
+    assert!(info.lookup_method("Add").is_some());
+    assert!(info.lookup_method("Rename").is_some());
+    assert!(info.lookup_method("LocalOnly").is_none());
+    assert!(info.lookup_property("Count").is_some());
+    assert!(info.lookup_property("Title").is_some());
+    assert!(info.lookup_property("Hidden").is_none());
+    assert!(info.lookup_signal("Reset").is_some());
+
+    let (server, client) = common::connect_peers();
+    let obj = glib::Object::new::<Counter>(&[]).unwrap();
+    let registration = obj.dbus_register(&server, "/org/example/Counter").unwrap();
+
+    let (tx, mut rx) = futures_channel::mpsc::unbounded();
+    let _filter = client.add_filter(move |_, message, incoming| {
+        if incoming && message.message_type() == gio::DBusMessageType::Signal {
+            let name = format!(
+                "{}.{}",
+                message.interface().unwrap(),
+                message.member().unwrap()
+            );
+            tx.unbounded_send((name, message.body().unwrap())).unwrap();
+        }
+        Some(message.clone())
+    });
+
+    let call = |method: &'static str, parameters: glib::Variant| {
+        client.call_future(
+            None,
+            "/org/example/Counter",
+            "org.example.Counter",
+            method,
+            Some(&parameters),
+            None,
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+    };
+    let get_property = |name: &'static str| {
+        client.call_future(
+            None,
+            "/org/example/Counter",
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            Some(&("org.example.Counter", name).to_variant()),
+            None,
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+    };
+    glib::MainContext::default().block_on(async {
+        let ret = call("Add", (5i32,).to_variant()).await.unwrap();
+        assert_eq!(ret.get::<(i32,)>(), Some((5,)));
+        assert_eq!(obj.count(), 5);
+        let (name, parameters) = rx.next().await.unwrap();
+        assert_eq!(name, "org.freedesktop.DBus.Properties.PropertiesChanged");
+        let (interface, changed, _) = parameters
+            .get::<(
+                String,
+                std::collections::HashMap<String, glib::Variant>,
+                Vec<String>,
+            )>()
+            .unwrap();
+        assert_eq!(interface, "org.example.Counter");
+        assert_eq!(changed["Count"].get::<i32>(), Some(5));
+
+        let ret = call("Rename", ("hello",).to_variant()).await.unwrap();
+        assert_eq!(ret.n_children(), 0);
+        assert_eq!(obj.label(), "hello");
+        rx.next().await.unwrap();
+        let err = call("Rename", ("",).to_variant()).await.unwrap_err();
+        assert!(err.message().contains("empty label"));
+        assert!(call("Add", ("wrong",).to_variant()).await.is_err());
+        assert!(call("LocalOnly", ().to_variant()).await.is_err());
+
+        let ret = get_property("Title").await.unwrap();
+        let (value,) = ret.get::<(glib::Variant,)>().unwrap();
+        assert_eq!(value.get::<String>().as_deref(), Some("hello"));
+        assert!(get_property("Hidden").await.is_err());
+
+        obj.emit_reset(7);
+        let (name, parameters) = rx.next().await.unwrap();
+        assert_eq!(name, "org.example.Counter.Reset");
+        assert_eq!(parameters.get::<(i32,)>(), Some((7,)));
+    });
+
+    registration.unregister();
+    glib::MainContext::default().block_on(async {
+        assert!(call("Add", (1i32,).to_variant()).await.is_err());
+    });
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "use_gio")]
+
+use futures_util::StreamExt;
+use gobject::DBusObject;
+
+mod common;
+
+#[gobject::class(final, dbus(name = "org.example.Player"))]
+mod player {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct Player {
+        #[property(get, set)]
+        #[dbus]
+        volume: Cell<u32>,
+        #[property(get)]
+        #[dbus]
+        title: RefCell<String>,
+        #[property(get)]
+        position: Cell<i64>,
+    }
+    impl Player {
+        #[public]
+        #[dbus]
+        fn play(&self, uri: &str) -> bool {
+            self.title.replace(uri.to_owned());
+            self.instance().notify_title();
+            true
+        }
+        #[public]
+        #[dbus]
+        fn seek(&self, offset: i64) {
+            self.position.set(self.position.get() + offset);
+        }
+        #[signal]
+        #[dbus]
+        fn finished(&self, uri: String) {}
+    }
+}
+
+gobject::dbus_proxy!("tests/dbus_proxy/org.example.Player.xml");
+
+#[test]
+fn dbus_proxy() {
+    assert_eq!(PlayerProxy::INTERFACE, "org.example.Player");
+    assert!(PlayerProxy::interface_info()
+        .lookup_method("Seek")
+        .is_some());
+
+    let (server, client) = common::connect_peers();
+    let player = glib::Object::new::<Player>(&[]).unwrap();
+    player.set_volume(5);
+    let _registration = player
+        .dbus_register(&server, "/org/example/Player")
+        .unwrap();
+
+    glib::MainContext::default().block_on(async {
+        let proxy = PlayerProxy::new(&client, None, "/org/example/Player")
+            .await
+            .unwrap();
+        assert_eq!(proxy.volume(), 5);
+        assert_eq!(proxy.title(), "");
+
+        let (tx, mut rx) = futures_channel::mpsc::unbounded();
+        proxy.connect_title_notify({
+            let tx = tx.clone();
+            move |proxy| {
+                tx.unbounded_send(format!("title {}", proxy.title()))
+                    .unwrap()
+            }
+        });
+        proxy.connect_volume_notify({
+            let tx = tx.clone();
+            move |proxy| {
+                tx.unbounded_send(format!("volume {}", proxy.volume()))
+                    .unwrap()
+            }
+        });
+        proxy.connect_finished(move |_, uri| {
+            tx.unbounded_send(format!("finished {}", uri)).unwrap()
+        });
+
+        assert!(proxy.play("song.ogg").await.unwrap());
+        assert_eq!(rx.next().await.unwrap(), "title song.ogg");
+        proxy.seek(10).await.unwrap();
+        assert_eq!(player.position(), 10);
+
+        player.set_volume(8);
+        assert_eq!(rx.next().await.unwrap(), "volume 8");
+        player.emit_finished("song.ogg".into());
+        assert_eq!(rx.next().await.unwrap(), "finished song.ogg");
+
+        let (server_tx, mut server_rx) = futures_channel::mpsc::unbounded();
+        player.connect_volume_notify(move |player| {
+            server_tx.unbounded_send(player.volume()).unwrap()
+        });
+        proxy.set_volume(3);
+        assert_eq!(server_rx.next().await.unwrap(), 3);
+        assert_eq!(rx.next().await.unwrap(), "volume 3");
+    });
+}
@@ -0,0 +1,119 @@
+use glib::{prelude::*, subclass::prelude::*};
+use gobject::TypeModuleExt;
+
+#[gobject::enum_(ns = "Test", dynamic)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynMode {
+    Off,
+    On,
+}
+
+#[gobject::flags(ns = "Test", dynamic)]
+pub enum DynFlags {
+    Left = 1 << 0,
+    Right = 1 << 1,
+}
+
+#[gobject::interface(ns = "Test", dynamic)]
+mod dyn_iface {
+    use std::marker::PhantomData;
+
+    #[derive(Copy, Clone)]
+    pub struct DynIface {
+        #[property(get, set)]
+        _label: PhantomData<String>,
+    }
+    impl DynIface {
+        #[virt]
+        fn describe(iface: &super::DynIface) -> String {
+            format!("<{}>", iface.label())
+        }
+    }
+}
+
+#[gobject::class(ns = "Test", final, dynamic, implements(DynIface))]
+mod dyn_obj {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct DynObj {
+        #[property(get, set)]
+        count: Cell<u32>,
+        #[property(get, set, override_iface = "super::DynIface")]
+        label: RefCell<String>,
+    }
+    impl DynObj {
+        #[signal]
+        fn changed(&self) {}
+    }
+    impl super::DynIfaceImpl for DynObj {}
+}
+
+#[gobject::class(final, extends(gobject::TypeModule))]
+mod plugin {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct TestPlugin {
+        pub(super) loads: Cell<u32>,
+        pub(super) unloads: Cell<u32>,
+    }
+    impl gobject::TypeModuleImpl for TestPlugin {
+        fn load(&self, type_module: &super::TestPlugin) -> bool {
+            self.loads.set(self.loads.get() + 1);
+            super::DynMode::register_type(type_module);
+            super::DynFlags::register_type(type_module);
+            super::DynIface::register_type(type_module);
+            super::DynObj::register_type(type_module);
+            true
+        }
+        fn unload(&self, _type_module: &super::TestPlugin) {
+            self.unloads.set(self.unloads.get() + 1);
+        }
+    }
+}
+
+#[test]
+fn dynamic_types() {
+    let module = glib::Object::new::<TestPlugin>(&[]).unwrap();
+    module.set_name("test-plugin");
+
+    assert!(module.use_());
+    assert_eq!(module.imp().loads.get(), 1);
+    let type_ = DynObj::static_type();
+    assert_eq!(type_.name(), "TestDynObj");
+    assert_eq!(DynMode::static_type().name(), "TestDynMode");
+    assert_eq!(DynFlags::static_type().name(), "TestDynFlags");
+    assert_eq!(DynIface::static_type().name(), "TestDynIface");
+    assert!(type_.is_a(DynIface::static_type()));
+    module.unuse();
+    assert_eq!(module.imp().unloads.get(), 1);
+
+    // types are registered again with the same ids when the module is reloaded
+    assert!(module.use_());
+    assert_eq!(module.imp().loads.get(), 2);
+    assert_eq!(DynObj::static_type(), type_);
+    assert_eq!(
+        DynMode::On.to_value().get::<DynMode>().unwrap(),
+        DynMode::On
+    );
+    let flags = DynFlags::LEFT | DynFlags::RIGHT;
+    assert_eq!(flags.to_value().get::<DynFlags>().unwrap(), flags);
+
+    let obj = glib::Object::new::<DynObj>(&[("count", &7u32)]).unwrap();
+    assert_eq!(obj.count(), 7);
+    obj.set_count(8);
+    assert_eq!(obj.property::<u32>("count"), 8);
+    let changed = std::rc::Rc::new(std::cell::Cell::new(false));
+    obj.connect_changed({
+        let changed = changed.clone();
+        move |_| changed.set(true)
+    });
+    obj.emit_changed();
+    assert!(changed.get());
+    obj.set_label("dyn".into());
+    assert_eq!(obj.upcast_ref::<DynIface>().label(), "dyn");
+    assert_eq!(obj.describe(), "<dyn>");
+    drop(obj);
+    module.unuse();
+}
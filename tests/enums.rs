@@ -0,0 +1,84 @@
+use glib::prelude::*;
+
+#[gobject::enum_(ns = "Test")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Animal {
+    Goat,
+    #[enum_value(name = "Canis familiaris", nick = "doggo")]
+    Dog,
+    BigCat = 10,
+}
+
+impl Default for Animal {
+    fn default() -> Self {
+        Self::Goat
+    }
+}
+
+#[gobject::class(final)]
+mod obj_enum {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ObjEnum {
+        #[property(get, set)]
+        animal: Cell<super::Animal>,
+        #[property(get, set, enum)]
+        animal2: Cell<super::Animal>,
+    }
+}
+
+#[test]
+fn enum_type() {
+    assert_eq!(Animal::static_type().name(), "TestAnimal");
+    let class = glib::EnumClass::new(Animal::static_type()).unwrap();
+    assert_eq!(class.n_values(), 3);
+    let goat = class.value(Animal::Goat as i32).unwrap();
+    assert_eq!(goat.name(), "Goat");
+    assert_eq!(goat.nick(), "goat");
+    let dog = class.value_by_nick("doggo").unwrap();
+    assert_eq!(dog.name(), "Canis familiaris");
+    assert_eq!(dog.value(), Animal::Dog as i32);
+    let cat = class.value_by_nick("big-cat").unwrap();
+    assert_eq!(cat.value(), 10);
+
+    let value = Animal::BigCat.to_value();
+    assert_eq!(value.type_(), Animal::static_type());
+    assert_eq!(value.get::<Animal>().unwrap(), Animal::BigCat);
+}
+
+#[test]
+fn enum_property() {
+    let obj = glib::Object::new::<ObjEnum>(&[]).unwrap();
+    let pspec = obj.find_property("animal").unwrap();
+    assert_eq!(pspec.value_type(), Animal::static_type());
+    assert!(pspec.downcast_ref::<glib::ParamSpecEnum>().is_some());
+    assert_eq!(obj.animal(), Animal::Goat);
+    obj.set_animal(Animal::Dog);
+    assert_eq!(obj.property::<Animal>("animal"), Animal::Dog);
+    obj.set_property("animal2", Animal::BigCat);
+    assert_eq!(obj.animal2(), Animal::BigCat);
+}
+
+#[cfg(feature = "variant")]
+#[test]
+fn enum_variant() {
+    use glib::{FromVariant, StaticVariantType, ToVariant};
+
+    assert_eq!(&*Animal::static_variant_type(), glib::VariantTy::STRING);
+    let variant = Animal::Dog.to_variant();
+    assert_eq!(variant.str(), Some("doggo"));
+    assert_eq!(Animal::from_variant(&variant), Some(Animal::Dog));
+    assert_eq!(Animal::from_variant(&"cow".to_variant()), None);
+}
+
+#[cfg(feature = "use_serde")]
+#[test]
+fn enum_serde() {
+    assert_eq!(serde_json::to_string(&Animal::Dog).unwrap(), "\"doggo\"");
+    assert_eq!(
+        serde_json::from_str::<Animal>("\"big-cat\"").unwrap(),
+        Animal::BigCat
+    );
+    assert!(serde_json::from_str::<Animal>("\"Goat\"").is_err());
+}
@@ -0,0 +1,79 @@
+use glib::translate::{from_glib, from_glib_full, IntoGlib, ToGlibPtr};
+use obj_ffi::*;
+
+#[gobject::class(ns = "TestApp", final, ffi)]
+mod obj_ffi {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct FfiObj {
+        #[property(get, set)]
+        count: Cell<i32>,
+        #[property(get, set)]
+        label: RefCell<String>,
+    }
+    impl FfiObj {
+        #[public]
+        fn add(&self, amount: i32) -> i32 {
+            self.count.set(self.count.get() + amount);
+            self.count.get()
+        }
+        #[public]
+        fn greet(&self, name: &str, shout: bool) -> String {
+            let msg = format!("{} {}", self.label.borrow(), name);
+            if shout {
+                msg.to_uppercase()
+            } else {
+                msg
+            }
+        }
+        #[public]
+        fn is_empty(&self) -> bool {
+            self.label.borrow().is_empty()
+        }
+        #[public]
+        fn square(value: f64) -> f64 {
+            value * value
+        }
+        #[public]
+        #[ffi(skip)]
+        fn skipped(&self, _values: &[u8]) {}
+    }
+    impl super::FfiObj {
+        #[constructor(infallible)]
+        pub fn new(count: i32, label: &str) -> Self {}
+    }
+}
+
+#[test]
+fn ffi_shims() {
+    unsafe {
+        let type_: glib::Type = from_glib(test_app_ffi_obj_get_type());
+        assert_eq!(type_, <FfiObj as glib::StaticType>::static_type());
+
+        let label = std::ffi::CString::new("hello").unwrap();
+        let obj: FfiObj = from_glib_full(test_app_ffi_obj_new(5, label.as_ptr()));
+        assert_eq!(obj.count(), 5);
+        assert_eq!(obj.label(), "hello");
+
+        let ptr = obj.to_glib_none().0;
+        assert_eq!(test_app_ffi_obj_add(ptr, 3), 8);
+        assert_eq!(obj.count(), 8);
+
+        let name = std::ffi::CString::new("world").unwrap();
+        let greeting = test_app_ffi_obj_greet(ptr, name.as_ptr(), true.into_glib());
+        let greeting: glib::GString = from_glib_full(greeting);
+        assert_eq!(greeting, "HELLO WORLD");
+        assert_eq!(test_app_ffi_obj_is_empty(ptr), false.into_glib());
+        assert_eq!(test_app_ffi_obj_square(1.5), 2.25);
+    }
+}
+
+#[test]
+fn ffi_header() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ffi.rs");
+    let classes = gobject_build::parse_classes(path.as_ref()).unwrap();
+    assert_eq!(classes.len(), 1);
+    assert_eq!(classes[0].0, "test_app_ffi_obj");
+    assert_eq!(classes[0].1, include_str!("ffi/test_app_ffi_obj.h"));
+}
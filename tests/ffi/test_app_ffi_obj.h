@@ -0,0 +1,35 @@
+/* Generated by gobject. Do not edit. */
+
+#ifndef __TEST_APP_FFI_OBJ_H__
+#define __TEST_APP_FFI_OBJ_H__
+
+#include <glib-object.h>
+
+G_BEGIN_DECLS
+
+#define TEST_APP_TYPE_FFI_OBJ (test_app_ffi_obj_get_type ())
+
+typedef struct _TestAppFfiObj TestAppFfiObj;
+typedef struct _TestAppFfiObjClass TestAppFfiObjClass;
+
+struct _TestAppFfiObj
+{
+  GObject parent_instance;
+};
+
+struct _TestAppFfiObjClass
+{
+  GObjectClass parent_class;
+};
+
+GType test_app_ffi_obj_get_type (void);
+
+gint test_app_ffi_obj_add (TestAppFfiObj *self, gint amount);
+char *test_app_ffi_obj_greet (TestAppFfiObj *self, const char *name, gboolean shout);
+gboolean test_app_ffi_obj_is_empty (TestAppFfiObj *self);
+gdouble test_app_ffi_obj_square (gdouble value);
+TestAppFfiObj *test_app_ffi_obj_new (gint count, const char *label);
+
+G_END_DECLS
+
+#endif /* __TEST_APP_FFI_OBJ_H__ */
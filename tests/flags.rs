@@ -0,0 +1,113 @@
+use glib::prelude::*;
+
+#[gobject::flags(ns = "Test")]
+#[derive(Default)]
+pub enum Permissions {
+    Read = 1 << 0,
+    Write = 1 << 1,
+    #[flags_value(name = "Execute permission", nick = "exec")]
+    Execute = 1 << 2,
+    ReadWrite = Self::READ.bits | Self::WRITE.bits,
+}
+
+#[gobject::flags(ns = "Test", numeric)]
+#[derive(Default)]
+pub enum Features {
+    Fast = 1,
+    Cheap = 2,
+}
+
+#[gobject::class(final)]
+mod obj_flags {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    pub struct ObjFlags {
+        #[property(get, set)]
+        permissions: Cell<super::Permissions>,
+        #[property(get, set, flags)]
+        features: Cell<super::Features>,
+    }
+}
+
+#[test]
+fn flags_type() {
+    assert_eq!(Permissions::static_type().name(), "TestPermissions");
+    let class = glib::FlagsClass::new(Permissions::static_type()).unwrap();
+    let exec = class.value_by_nick("exec").unwrap();
+    assert_eq!(exec.name(), "Execute permission");
+    assert_eq!(exec.value(), Permissions::EXECUTE.bits());
+    assert_eq!(
+        class.value_by_nick("read-write").unwrap().value(),
+        (Permissions::READ | Permissions::WRITE).bits()
+    );
+    assert_eq!(
+        class.to_nick_string((Permissions::READ | Permissions::EXECUTE).bits()),
+        "read|exec"
+    );
+
+    let value = (Permissions::WRITE | Permissions::EXECUTE).to_value();
+    assert_eq!(value.type_(), Permissions::static_type());
+    assert_eq!(
+        value.get::<Permissions>().unwrap(),
+        Permissions::WRITE | Permissions::EXECUTE
+    );
+}
+
+#[test]
+fn flags_property() {
+    let obj = glib::Object::new::<ObjFlags>(&[]).unwrap();
+    let pspec = obj.find_property("permissions").unwrap();
+    assert_eq!(pspec.value_type(), Permissions::static_type());
+    assert!(pspec.downcast_ref::<glib::ParamSpecFlags>().is_some());
+    assert_eq!(obj.permissions(), Permissions::empty());
+    obj.set_permissions(Permissions::READ_WRITE);
+    assert_eq!(
+        obj.property::<Permissions>("permissions"),
+        Permissions::READ | Permissions::WRITE
+    );
+    obj.set_property("features", Features::FAST | Features::CHEAP);
+    assert_eq!(obj.features(), Features::all());
+}
+
+#[cfg(feature = "variant")]
+#[test]
+fn flags_variant() {
+    use glib::{FromVariant, StaticVariantType, ToVariant};
+
+    assert_eq!(
+        &*Permissions::static_variant_type(),
+        glib::VariantTy::STRING
+    );
+    let variant = (Permissions::READ | Permissions::EXECUTE).to_variant();
+    assert_eq!(variant.str(), Some("read|exec"));
+    assert_eq!(
+        Permissions::from_variant(&variant),
+        Some(Permissions::READ | Permissions::EXECUTE)
+    );
+    assert_eq!(Permissions::from_variant(&"bogus".to_variant()), None);
+
+    assert_eq!(&*Features::static_variant_type(), glib::VariantTy::UINT32);
+    let variant = Features::CHEAP.to_variant();
+    assert_eq!(variant.get::<u32>(), Some(2));
+    assert_eq!(Features::from_variant(&variant), Some(Features::CHEAP));
+}
+
+#[cfg(feature = "use_serde")]
+#[test]
+fn flags_serde() {
+    let perms = Permissions::WRITE | Permissions::EXECUTE;
+    assert_eq!(serde_json::to_string(&perms).unwrap(), "\"write|exec\"");
+    assert_eq!(
+        serde_json::from_str::<Permissions>("\"read|write\"").unwrap(),
+        Permissions::READ_WRITE
+    );
+    assert!(serde_json::from_str::<Permissions>("\"bogus\"").is_err());
+
+    assert_eq!(serde_json::to_string(&Features::all()).unwrap(), "3");
+    assert_eq!(
+        serde_json::from_str::<Features>("1").unwrap(),
+        Features::FAST
+    );
+    assert!(serde_json::from_str::<Features>("4").is_err());
+}
@@ -0,0 +1,67 @@
+use glib::prelude::*;
+
+#[gobject::class(
+    final,
+    instantiate(Container<i32> = "ContainerInt", Container<String> = "ContainerString")
+)]
+mod container {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct Container<T: Default + 'static> {
+        items: RefCell<Vec<T>>,
+        #[property(get, set)]
+        capacity: Cell<u32>,
+    }
+    impl<T: Default + 'static> Container<T> {
+        #[signal]
+        fn added(&self, len: u32) {}
+        #[public]
+        fn push(&self, item: T) {
+            let len = {
+                let mut items = self.items.borrow_mut();
+                items.push(item);
+                items.len() as u32
+            };
+            self.instance().emit_added(len);
+        }
+        #[public]
+        fn len(&self) -> u32 {
+            self.items.borrow().len() as u32
+        }
+    }
+}
+
+#[test]
+fn generic_types() {
+    let ints = Container::<i32>::static_type();
+    let strings = Container::<String>::static_type();
+    assert_ne!(ints, strings);
+    assert_eq!(ints.name(), "ContainerInt");
+    assert_eq!(strings.name(), "ContainerString");
+    assert!(ints.is_a(glib::Object::static_type()));
+}
+
+#[test]
+fn generic_members() {
+    let ints = glib::Object::new::<Container<i32>>(&[("capacity", &4u32)]).unwrap();
+    let strings = glib::Object::new::<Container<String>>(&[]).unwrap();
+    assert_eq!(ints.capacity(), 4);
+    assert_eq!(strings.capacity(), 0);
+    assert!(ints.find_property("capacity").is_some());
+    assert!(strings.find_property("capacity").is_some());
+
+    let added = std::rc::Rc::new(std::cell::Cell::new(0));
+    ints.connect_added({
+        let added = added.clone();
+        move |_, len| added.set(len)
+    });
+    ints.push(1);
+    ints.push(2);
+    assert_eq!(ints.len(), 2);
+    assert_eq!(added.get(), 2);
+
+    strings.push("hello".into());
+    assert_eq!(strings.len(), 1);
+    assert_eq!(added.get(), 2);
+}
@@ -0,0 +1,99 @@
+#[gobject::interface(ns = "Test")]
+mod iface_gir {
+    #[derive(Default)]
+    pub struct GirIface {
+        #[property(get, set, blurb = "A <name> & label")]
+        label: std::marker::PhantomData<String>,
+    }
+    impl super::GirIface {
+        #[virt]
+        fn frobnicate(&self, amount: u32) -> bool {
+            amount > 0
+        }
+    }
+}
+
+#[gobject::class(ns = "Test", final, ffi, implements(GirIface))]
+mod obj_gir {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct GirObj {
+        #[property(get, set, construct)]
+        count: Cell<i32>,
+        #[property(get)]
+        name: RefCell<String>,
+        #[property(set, construct_only)]
+        ratio: Cell<f64>,
+        #[property(get, set, override_iface = "super::GirIface")]
+        label: RefCell<String>,
+    }
+    impl GirObj {
+        #[public]
+        fn double(&self, factor: u8) -> i64 {
+            self.count.get() as i64 * factor as i64
+        }
+        #[signal(run_last, detailed)]
+        fn changed(&self, what: &str, flag: bool) {}
+        #[signal(run_first, action)]
+        fn compute(&self, input: u64) -> f32 {
+            input as f32
+        }
+    }
+    impl super::GirObj {
+        #[constructor(infallible)]
+        pub fn with_count(count: i32) -> Self {}
+    }
+    impl super::GirIfaceImpl for GirObj {}
+}
+
+#[gobject::class(ns = "Test", final)]
+mod plain_gir {
+    #[derive(Default)]
+    pub struct GirPlain {}
+    impl GirPlain {
+        #[public]
+        fn answer(&self) -> u32 {
+            42
+        }
+    }
+}
+
+const SOURCE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/gir.rs");
+
+fn gir(name: &str) -> String {
+    let types = gobject_build::parse_gir(SOURCE.as_ref()).unwrap();
+    types.into_iter().find(|t| t.0 == name).unwrap().1
+}
+
+#[test]
+fn gir_class() {
+    assert_eq!(gir("TestGirObj"), include_str!("gir/TestGirObj.gir"));
+}
+
+#[test]
+fn gir_interface() {
+    assert_eq!(gir("TestGirIface"), include_str!("gir/TestGirIface.gir"));
+}
+
+#[test]
+fn gir_without_ffi() {
+    let gir = gir("TestGirPlain");
+    assert!(gir.contains("<class name=\"GirPlain\" c:type=\"TestGirPlain\""));
+    assert!(!gir.contains("glib:get-type"));
+    assert!(!gir.contains("<method"));
+}
+
+#[test]
+fn gir_files() {
+    let out_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("gir");
+    let files = gobject_build::generate_gir(SOURCE, &out_dir).unwrap();
+    assert_eq!(
+        files,
+        ["TestGirIface.gir", "TestGirObj.gir", "TestGirPlain.gir"].map(|f| out_dir.join(f))
+    );
+    assert_eq!(
+        std::fs::read_to_string(&files[1]).unwrap(),
+        include_str!("gir/TestGirObj.gir")
+    );
+}
@@ -0,0 +1,104 @@
+use glib::prelude::*;
+use gobject::History;
+
+#[gobject::class(final)]
+mod doc {
+    use std::cell::{Cell, RefCell};
+
+    #[properties(history)]
+    #[derive(Default)]
+    pub struct Document {
+        #[property(get, set)]
+        title: RefCell<String>,
+        #[property(get, set, explicit_notify, lax_validation)]
+        zoom: Cell<u32>,
+        #[property(get)]
+        revision: Cell<u32>,
+    }
+}
+
+#[test]
+fn undo_redo() {
+    let history = History::new();
+    let doc = glib::Object::new::<Document>(&[]).unwrap();
+    doc.set_title("unrecorded".into());
+    history.attach(&doc);
+    assert!(!history.can_undo());
+
+    doc.set_title("First".into());
+    doc.set_title("Second".into());
+    doc.set_zoom(2);
+    doc.set_zoom(2);
+    assert!(history.can_undo());
+    assert!(!history.can_redo());
+
+    assert!(history.undo());
+    assert_eq!(doc.zoom(), 0);
+    assert!(history.can_redo());
+    assert!(history.undo());
+    assert_eq!(doc.title(), "First");
+    assert!(history.undo());
+    assert_eq!(doc.title(), "unrecorded");
+    assert!(!history.undo());
+    assert!(!history.can_undo());
+
+    assert!(history.redo());
+    assert_eq!(doc.title(), "First");
+    doc.set_title("Branch".into());
+    assert!(!history.can_redo());
+    assert!(!history.redo());
+
+    history.detach(&doc);
+    doc.set_title("Detached".into());
+    assert!(history.undo());
+    assert_eq!(doc.title(), "First");
+}
+
+#[test]
+fn groups_and_merging() {
+    let history = History::new();
+    let doc = glib::Object::new::<Document>(&[]).unwrap();
+    history.attach(&doc);
+
+    history.group(|| {
+        doc.set_title("Grouped".into());
+        doc.set_zoom(3);
+        doc.set_zoom(4);
+    });
+    assert!(history.undo());
+    assert_eq!(doc.title(), "");
+    assert_eq!(doc.zoom(), 0);
+    assert!(!history.can_undo());
+    assert!(history.redo());
+    assert_eq!(doc.zoom(), 4);
+
+    history.clear();
+    history.set_merge_window(std::time::Duration::from_secs(60));
+    doc.set_title("a".into());
+    doc.set_title("ab".into());
+    doc.set_title("abc".into());
+    assert!(history.undo());
+    assert_eq!(doc.title(), "Grouped");
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn can_undo_notify() {
+    let history = History::new();
+    let doc = glib::Object::new::<Document>(&[]).unwrap();
+    history.attach(&doc);
+
+    let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+    history.connect_notify_local(Some("can-undo"), {
+        let notified = notified.clone();
+        move |_, _| notified.set(notified.get() + 1)
+    });
+    doc.set_zoom(1);
+    doc.set_zoom(2);
+    assert_eq!(notified.get(), 1);
+    assert!(history.property::<bool>("can-undo"));
+    history.undo();
+    history.undo();
+    assert_eq!(notified.get(), 2);
+    assert!(!history.property::<bool>("can-undo"));
+}
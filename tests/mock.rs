@@ -0,0 +1,127 @@
+#![cfg(feature = "mock")]
+
+use glib::prelude::*;
+
+#[gobject::class(abstract, mock)]
+mod storage {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct Storage {
+        #[property(get, set)]
+        prefix: RefCell<String>,
+    }
+    impl Storage {
+        #[virt]
+        fn load(&self, key: &str) -> Option<String> {
+            Some(format!("{}{}", self.prefix.borrow(), key))
+        }
+        #[virt]
+        fn store(&self, key: &str, value: String) -> bool {
+            let _ = (key, value);
+            false
+        }
+        #[virt]
+        fn contains(&self, key: Option<&str>, tags: Option<&[String]>) -> bool {
+            key.is_some() && tags.map(|t| !t.is_empty()).unwrap_or(true)
+        }
+        #[signal]
+        fn saved(&self, key: &str, count: u32) -> u32 {
+            count
+        }
+        #[signal(run_last)]
+        fn cleared(&self, keys: Vec<String>) {
+            self.prefix.borrow_mut().clear();
+            let _ = keys;
+        }
+    }
+}
+
+fn load_all(storage: &impl IsA<Storage>, keys: &[&str]) -> Vec<String> {
+    keys.iter().filter_map(|key| storage.load(key)).collect()
+}
+
+#[test]
+fn mock_virtual_methods() {
+    let mock = glib::Object::new::<MockStorage>(&[("prefix", &"default:")]).unwrap();
+    assert_eq!(load_all(&mock, &["a"]), ["default:a"]);
+    assert_eq!(mock.load_mock().calls(), [("a".to_owned(),)]);
+
+    mock.mock_load(|_, key| (key != "b").then(|| key.to_uppercase()))
+        .expect_calls(3);
+    assert_eq!(load_all(&mock, &["a", "b"]), ["A"]);
+    assert_eq!(mock.load_mock().call_count(), 3);
+    mock.verify_mocks();
+
+    mock.mock_store(|obj, key, value| {
+        obj.set_prefix(format!("{}={}", key, value));
+        true
+    });
+    assert!(mock.store("x", "1".into()));
+    assert_eq!(mock.prefix(), "x=1");
+    assert_eq!(
+        mock.store_mock().calls(),
+        [("x".to_owned(), "1".to_owned())]
+    );
+
+    mock.load_mock().unset();
+    mock.load_mock().clear_calls();
+    assert_eq!(mock.load("y"), Some("x=1y".into()));
+    assert_eq!(mock.load_mock().call_count(), 1);
+}
+
+#[test]
+fn mock_optional_references() {
+    let mock = glib::Object::new::<MockStorage>(&[]).unwrap();
+    let tags = vec!["a".to_owned()];
+    assert!(mock.contains(Some("x"), Some(&tags)));
+    mock.mock_contains(|_, key, _| key == Some("y"));
+    assert!(!mock.contains(None, None));
+    assert!(mock.contains(Some("y"), Some(&[])));
+    assert_eq!(
+        mock.contains_mock().calls(),
+        [
+            (Some("x".to_owned()), Some(tags)),
+            (None, None),
+            (Some("y".to_owned()), Some(Vec::new())),
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected `store` to be called 1 time(s), but it was called 0 time(s)")]
+fn mock_expectations() {
+    let mock = glib::Object::new::<MockStorage>(&[]).unwrap();
+    mock.store_mock().expect_calls(1);
+    mock.verify_mocks();
+}
+
+#[test]
+fn mock_signal_handlers() {
+    let mock = glib::Object::new::<MockStorage>(&[]).unwrap();
+    assert_eq!(mock.emit_saved("a", 1), 1);
+
+    mock.mock_saved(|_, key, count| key.len() as u32 + count);
+    assert_eq!(mock.emit_saved("abc", 2), 5);
+    assert_eq!(
+        mock.saved_mock().calls(),
+        [("a".to_owned(), 1), ("abc".to_owned(), 2)]
+    );
+
+    mock.set_prefix("p".into());
+    mock.cleared_mock().expect_calls(2);
+    mock.mock_cleared(|_, _| {});
+    mock.emit_cleared(vec!["a".into()]);
+    assert_eq!(mock.prefix(), "p");
+    mock.cleared_mock().unset();
+    mock.emit_cleared(vec!["b".into(), "c".into()]);
+    assert_eq!(mock.prefix(), "");
+    assert_eq!(
+        mock.cleared_mock().calls(),
+        [
+            (vec!["a".to_owned()],),
+            (vec!["b".to_owned(), "c".to_owned()],)
+        ]
+    );
+    mock.verify_mocks();
+}
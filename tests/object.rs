@@ -164,6 +164,51 @@ fn concurrency() {
     assert!(flag.load(Ordering::Acquire));
 }
 
+#[gobject::class(final, sync)]
+mod obj_sync_cells {
+    use gobject::{ArcCell, AtomicCell, SyncConstructCell, SyncWeakCell};
+
+    #[derive(Default)]
+    struct ObjSyncCells {
+        #[property(get, set)]
+        ratio: AtomicCell<f64>,
+        #[property(get, set)]
+        label: ArcCell<String>,
+        #[property(get, set, object, construct)]
+        owner: SyncConstructCell<super::ObjThreadSafe>,
+        #[property(get, set, construct_only, object)]
+        creator: SyncConstructCell<super::ObjThreadSafe>,
+        #[property(get, set, object)]
+        peer: SyncWeakCell<super::ObjThreadSafe>,
+    }
+}
+
+#[test]
+fn sync_cells() {
+    let peer = glib::Object::new::<ObjThreadSafe>(&[]).unwrap();
+    let obj = glib::Object::new::<ObjSyncCells>(&[("owner", &peer), ("creator", &peer)]).unwrap();
+    assert_eq!(obj.ratio(), 0.0);
+    assert_eq!(obj.owner(), peer);
+    assert_eq!(obj.creator(), peer);
+    let o = obj.clone();
+    let p = peer.clone();
+    std::thread::spawn(move || {
+        assert_eq!(o.property::<ObjThreadSafe>("creator"), p);
+        o.set_ratio(0.5);
+        o.set_label("Hello".into());
+        o.set_peer(p);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(obj.ratio(), 0.5);
+    assert_eq!(obj.label(), "Hello");
+    assert_eq!(obj.peer(), peer);
+    let other = glib::Object::new::<ObjThreadSafe>(&[]).unwrap();
+    obj.set_peer(other.clone());
+    drop(other);
+    assert!(obj.property::<Option<ObjThreadSafe>>("peer").is_none());
+}
+
 #[allow(unused_imports)]
 mod objects {
     #[gobject::class(final)]
@@ -341,3 +341,282 @@ fn optional_props() {
     obj.set_point(None);
     assert!(obj.point().is_none());
 }
+
+#[gobject::class(final)]
+mod validated {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct Validated {
+        #[property(get, set, validate = "clamp_percent")]
+        percent: Cell<u32>,
+        #[property(get, set, explicit_notify, lax_validation, validate = "trim_label")]
+        label: RefCell<String>,
+    }
+
+    pub static PERCENT_CHECKS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+    fn clamp_percent(_obj: &super::Validated, value: u32) -> Result<u32, String> {
+        PERCENT_CHECKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if value > 1000 {
+            return Err(format!("{} is out of range", value));
+        }
+        Ok(value.min(100))
+    }
+
+    fn trim_label(_obj: &super::Validated, value: String) -> Result<String, &'static str> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err("label must not be empty");
+        }
+        Ok(value.to_owned())
+    }
+}
+
+#[test]
+fn validated_properties() {
+    let obj = glib::Object::new::<Validated>(&[]).unwrap();
+    obj.set_percent(150);
+    assert_eq!(obj.percent(), 100);
+    obj.set_property("percent", 40u32);
+    assert_eq!(obj.percent(), 40);
+    obj.set_property("percent", 5000u32);
+    assert_eq!(obj.percent(), 40);
+    let checks = || validated::PERCENT_CHECKS.load(std::sync::atomic::Ordering::Relaxed);
+    let before = checks();
+    let err = obj.try_set_percent(5000).unwrap_err();
+    assert_eq!(err.to_string(), "5000 is out of range");
+    assert_eq!(obj.percent(), 40);
+    obj.try_set_percent(75).unwrap();
+    assert_eq!(obj.percent(), 75);
+    assert_eq!(checks() - before, 2);
+
+    let notified = std::rc::Rc::new(std::cell::Cell::new(0));
+    obj.connect_label_notify({
+        let notified = notified.clone();
+        move |_| notified.set(notified.get() + 1)
+    });
+    obj.set_label("  hello ".into());
+    assert_eq!(obj.label(), "hello");
+    assert_eq!(notified.get(), 1);
+    obj.set_label("   ".into());
+    assert_eq!(obj.label(), "hello");
+    assert_eq!(notified.get(), 1);
+    let err = obj.try_set_label(String::new()).unwrap_err();
+    assert_eq!(err.to_string(), "label must not be empty");
+    obj.try_set_label("world".into()).unwrap();
+    assert_eq!(obj.label(), "world");
+    assert_eq!(notified.get(), 2);
+}
+
+#[gobject::class(final)]
+mod bound {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct BoundLabel {
+        #[property(get, set, object)]
+        model: RefCell<Option<super::Pod>>,
+        #[property(
+            get,
+            set,
+            bind(source = "self.model", property = "string-prop", bidirectional)
+        )]
+        text: RefCell<String>,
+        #[property(get, set, bind(source = "model", property = "int-prop"))]
+        count: Cell<i32>,
+    }
+}
+
+#[test]
+fn bound_properties() {
+    let first = glib::Object::new::<Pod>(&[]).unwrap();
+    first.set_string_prop("first".into());
+    first.set_int_prop(1);
+    let second = glib::Object::new::<Pod>(&[]).unwrap();
+    second.set_string_prop("second".into());
+    second.set_int_prop(2);
+
+    let label = glib::Object::new::<BoundLabel>(&[]).unwrap();
+    assert_eq!(label.text(), "");
+    label.set_model(Some(first.clone()));
+    assert_eq!(label.text(), "first");
+    assert_eq!(label.count(), 1);
+    first.set_int_prop(10);
+    assert_eq!(label.count(), 10);
+    label.set_text("changed".into());
+    assert_eq!(first.string_prop(), "changed");
+
+    label.set_model(Some(second.clone()));
+    assert_eq!(label.text(), "second");
+    assert_eq!(label.count(), 2);
+    first.set_string_prop("ignored".into());
+    first.set_int_prop(100);
+    assert_eq!(label.text(), "second");
+    assert_eq!(label.count(), 2);
+
+    label.set_model(None);
+    second.set_int_prop(3);
+    assert_eq!(label.count(), 2);
+}
+
+#[gobject::class(final)]
+mod rect {
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+
+    #[derive(Default)]
+    pub struct Rect {
+        #[property(get, set)]
+        width: Cell<u32>,
+        #[property(get, set, explicit_notify, lax_validation)]
+        height: Cell<u32>,
+        #[property(computed, get, depends_on("width", "height"))]
+        area: PhantomData<u32>,
+        #[property(computed, get, depends_on("area"))]
+        double_area: PhantomData<u32>,
+    }
+    impl Rect {
+        #[public]
+        fn area(&self) -> u32 {
+            self.width.get() * self.height.get()
+        }
+        #[public]
+        fn double_area(&self) -> u32 {
+            self.area() * 2
+        }
+    }
+}
+
+#[test]
+fn computed_dependencies() {
+    use std::{cell::Cell, rc::Rc};
+
+    let rect = glib::Object::new::<Rect>(&[]).unwrap();
+    let area_count = Rc::new(Cell::new(0));
+    let double_count = Rc::new(Cell::new(0));
+    rect.connect_area_notify({
+        let area_count = area_count.clone();
+        move |_| area_count.set(area_count.get() + 1)
+    });
+    rect.connect_double_area_notify({
+        let double_count = double_count.clone();
+        move |_| double_count.set(double_count.get() + 1)
+    });
+
+    rect.set_width(2);
+    assert_eq!(area_count.get(), 1);
+    assert_eq!(double_count.get(), 1);
+    rect.set_height(3);
+    assert_eq!(area_count.get(), 2);
+    assert_eq!(rect.area(), 6);
+    assert_eq!(rect.property::<u32>("double-area"), 12);
+    rect.set_height(3);
+    assert_eq!(area_count.get(), 2);
+
+    {
+        let _guard = rect.freeze_notify();
+        rect.set_width(4);
+        rect.set_height(5);
+        assert_eq!(area_count.get(), 2);
+    }
+    assert_eq!(area_count.get(), 3);
+    assert_eq!(double_count.get(), 3);
+    rect.set_properties(&[("width", &6u32), ("height", &7u32)]);
+    assert_eq!(area_count.get(), 4);
+    assert_eq!(rect.area(), 42);
+
+    rect.notify_width();
+    assert_eq!(area_count.get(), 5);
+    assert_eq!(double_count.get(), 5);
+}
+
+#[gobject::class(final)]
+mod form {
+    use std::cell::{Cell, RefCell};
+
+    #[properties(transactions)]
+    #[derive(Default)]
+    pub struct Form {
+        #[property(get, set)]
+        name: RefCell<String>,
+        #[property(get, set)]
+        age: Cell<u32>,
+    }
+}
+
+#[test]
+fn transactions() {
+    use std::{cell::Cell, rc::Rc};
+
+    let form = glib::Object::new::<Form>(&[]).unwrap();
+    let name_count = Rc::new(Cell::new(0));
+    form.connect_name_notify({
+        let name_count = name_count.clone();
+        move |_| name_count.set(name_count.get() + 1)
+    });
+
+    form.batch(|form| {
+        form.set_name("a".into());
+        form.set_name("b".into());
+        form.set_age(5);
+        assert_eq!(name_count.get(), 0);
+    });
+    assert_eq!(name_count.get(), 1);
+    assert_eq!(form.name(), "b");
+
+    let res = form.edit(|form| {
+        form.set_name("c".into());
+        form.set_age(6);
+        Err::<(), _>("invalid")
+    });
+    assert_eq!(res, Err("invalid"));
+    assert_eq!(form.name(), "b");
+    assert_eq!(form.age(), 5);
+
+    assert_eq!(name_count.get(), 1);
+
+    let res = form.edit(|form| {
+        form.set_age(7);
+        Ok::<_, ()>(form.age())
+    });
+    assert_eq!(res, Ok(7));
+    assert_eq!(form.age(), 7);
+    assert_eq!(name_count.get(), 1);
+
+    let age_count = Rc::new(Cell::new(0));
+    form.connect_age_notify({
+        let age_count = age_count.clone();
+        move |_| age_count.set(age_count.get() + 1)
+    });
+    form.batch(|form| {
+        form.set_age(10);
+        let res = form.edit(|form| {
+            form.set_age(11);
+            form.set_name("d".into());
+            Err::<(), _>(())
+        });
+        assert!(res.is_err());
+        assert_eq!(form.age(), 10);
+    });
+    assert_eq!(age_count.get(), 1);
+    assert_eq!(name_count.get(), 1);
+    assert_eq!(form.name(), "b");
+}
+
+#[test]
+fn scoped_notify() {
+    use std::{cell::Cell, rc::Rc};
+
+    let form = glib::Object::new::<Form>(&[]).unwrap();
+    let count = Rc::new(Cell::new(0));
+    let guard = form.connect_age_notify_scoped({
+        let count = count.clone();
+        move |_| count.set(count.get() + 1)
+    });
+    form.set_age(1);
+    assert_eq!(count.get(), 1);
+    drop(guard);
+    form.set_age(2);
+    assert_eq!(count.get(), 1);
+}
@@ -109,3 +109,204 @@ mod obj_final3 {
     }
     impl super::ObjDerivableImpl for ObjFinal3 {}
 }
+
+#[gobject::boxed]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[gobject_serde(serialize, deserialize)]
+pub struct BoxedPoint {
+    x: i32,
+    #[serde(rename = "why")]
+    y: i32,
+}
+
+#[test]
+fn boxed_json() {
+    let point = BoxedPoint { x: 1, y: 2 };
+    let result = serde_json::to_string(&point).unwrap();
+    assert_eq!(result, r#"{"x":1,"why":2}"#);
+    let other: BoxedPoint = serde_json::from_str(&result).unwrap();
+    assert_eq!(other, point);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Quality {
+    Low,
+    High,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Source {
+    None,
+    File(String),
+    Camera { index: u32, quality: Quality },
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Recording {
+    name: String,
+    length: u64,
+    volume: Option<f64>,
+    tags: Vec<String>,
+    markers: std::collections::BTreeMap<String, (i32, bool)>,
+    quality: Quality,
+    source: Source,
+}
+
+impl gobject_serde::SerdeVariantType for Quality {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        gobject_serde::trace_variant_type::<Self>()
+    }
+}
+
+impl gobject_serde::SerdeVariantType for Source {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        gobject_serde::trace_variant_type::<Self>()
+    }
+}
+
+impl gobject_serde::SerdeVariantType for Recording {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        gobject_serde::trace_variant_type::<Self>()
+    }
+}
+
+#[test]
+fn serde_variant() {
+    use glib::ToVariant;
+    use gobject_serde::SerdeVariantType;
+
+    assert_eq!(
+        Recording::serde_variant_type().unwrap().as_str(),
+        "(stmdasa{s(ib)}s(sv))"
+    );
+    assert_eq!(Quality::serde_variant_type().unwrap().as_str(), "s");
+
+    let recording = Recording {
+        name: "take 1".into(),
+        length: 90,
+        volume: None,
+        tags: Vec::new(),
+        markers: [("intro".to_owned(), (5, true))].into_iter().collect(),
+        quality: Quality::High,
+        source: Source::Camera {
+            index: 2,
+            quality: Quality::Low,
+        },
+    };
+    let variant = gobject_serde::to_variant(&recording).unwrap();
+    assert_eq!(variant.type_().as_str(), "(stmdasa{s(ib)}s(sv))");
+    assert_eq!(variant.child_value(5).str(), Some("High"));
+    assert_eq!(variant.child_value(6).child_value(0).str(), Some("Camera"));
+    let other: Recording = gobject_serde::from_variant(&variant).unwrap();
+    assert_eq!(other, recording);
+
+    for source in [Source::None, Source::File("a.mkv".into())] {
+        let variant = gobject_serde::to_variant(&source).unwrap();
+        assert_eq!(
+            gobject_serde::from_variant::<Source>(&variant).unwrap(),
+            source
+        );
+    }
+    let variant = ("File", 1u32.to_variant().to_variant()).to_variant();
+    assert!(gobject_serde::from_variant::<Source>(&variant).is_err());
+}
+
+#[derive(serde::Serialize)]
+struct Report {
+    code: u16,
+    details: Vec<String>,
+    source: Option<Source>,
+}
+
+impl gobject_serde::SerdeVariantType for Report {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        Ok(glib::VariantType::new("(qasm(sv))").unwrap())
+    }
+}
+
+#[test]
+fn serde_variant_serialize_only() {
+    let report = Report {
+        code: 404,
+        details: Vec::new(),
+        source: Some(Source::File("a.mkv".into())),
+    };
+    let variant = gobject_serde::to_variant(&report).unwrap();
+    assert_eq!(variant.type_().as_str(), "(qasm(sv))");
+    assert_eq!(variant.child_value(0).get::<u16>(), Some(404));
+    let source = variant.child_value(2).as_maybe().unwrap();
+    assert_eq!(
+        gobject_serde::from_variant::<Source>(&source).unwrap(),
+        Source::File("a.mkv".into())
+    );
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String")]
+struct Tag(String);
+
+impl TryFrom<String> for Tag {
+    type Error = &'static str;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            return Err("empty tag");
+        }
+        Ok(Self(s))
+    }
+}
+
+impl gobject_serde::SerdeVariantType for Tag {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        Ok(glib::VariantType::new("s").unwrap())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Label {
+    Plain,
+    Tagged(Tag),
+    Pair {
+        tags: Vec<Tag>,
+        primary: Option<Tag>,
+    },
+}
+
+impl gobject_serde::SerdeVariantType for Label {
+    fn serde_variant_type() -> Result<glib::VariantType, gobject_serde::Error> {
+        Ok(glib::VariantType::new("(sv)").unwrap())
+    }
+}
+
+#[test]
+fn serde_variant_validating() {
+    use glib::ToVariant;
+
+    assert!(gobject_serde::trace_variant_type::<Tag>().is_err());
+
+    let tag = Tag("rust".into());
+    let variant = gobject_serde::to_variant(&tag).unwrap();
+    assert_eq!(variant.str(), Some("rust"));
+    assert_eq!(gobject_serde::from_variant::<Tag>(&variant).unwrap(), tag);
+    assert!(gobject_serde::from_variant::<Tag>(&"".to_variant()).is_err());
+
+    for label in [
+        Label::Plain,
+        Label::Tagged(tag.clone()),
+        Label::Pair {
+            tags: Vec::new(),
+            primary: None,
+        },
+        Label::Pair {
+            tags: vec![tag.clone(), Tag("glib".into())],
+            primary: Some(tag.clone()),
+        },
+    ] {
+        let variant = gobject_serde::to_variant(&label).unwrap();
+        assert_eq!(variant.type_().as_str(), "(sv)");
+        assert_eq!(
+            gobject_serde::from_variant::<Label>(&variant).unwrap(),
+            label
+        );
+    }
+}
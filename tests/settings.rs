@@ -0,0 +1,201 @@
+#![cfg(all(feature = "use_gio", feature = "variant"))]
+
+use gio::prelude::*;
+use gobject::SettingsObject;
+
+#[gobject::enum_(ns = "Test")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::Light
+    }
+}
+
+#[gobject::class(final)]
+mod preferences {
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    #[properties(settings_schema = "org.example.Preferences")]
+    pub struct Preferences {
+        #[property(
+            get,
+            set,
+            settings = "volume",
+            blurb = "Playback volume",
+            builder(minimum = 0, maximum = 100, default_value = 50)
+        )]
+        volume: Cell<i32>,
+        #[property(get, set, settings)]
+        title: RefCell<String>,
+        #[property(get, set, settings(key = "color-scheme", get))]
+        theme: Cell<super::Theme>,
+        #[property(
+            get,
+            set,
+            settings(with = "gobject::variant::glib::date_time::optional")
+        )]
+        last_opened: RefCell<Option<glib::DateTime>>,
+        #[property(
+            get,
+            set,
+            settings(default = "(0, 0)"),
+            builder_defaults = "[glib::VariantTy::new(\"(ii)\").unwrap()]"
+        )]
+        window_size: RefCell<Option<glib::Variant>>,
+        #[property(get, set)]
+        unbound: Cell<bool>,
+    }
+}
+
+#[gobject::class(
+    final,
+    extends(gio::Application),
+    parent_trait = "gio::subclass::prelude::ApplicationImpl",
+    inherits(gio::ActionGroup, gio::ActionMap)
+)]
+mod app {
+    #[derive(Default)]
+    #[properties(settings_schema = "org.example.App")]
+    pub struct App {}
+    impl App {
+        #[action(change_state, settings = "dark-mode")]
+        fn dark_mode(&self, dark: bool) -> Option<bool> {
+            Some(dark)
+        }
+        #[action(
+            change_state,
+            settings = "accent",
+            with = "super::accent",
+            default = "'b'"
+        )]
+        fn accent(&self, accent: char) -> Option<char> {
+            Some(accent).filter(char::is_ascii_lowercase)
+        }
+    }
+}
+
+mod accent {
+    pub fn static_variant_type() -> std::borrow::Cow<'static, glib::VariantTy> {
+        std::borrow::Cow::Borrowed(glib::VariantTy::STRING)
+    }
+    pub fn to_variant(accent: &char) -> glib::Variant {
+        glib::ToVariant::to_variant(&accent.to_string())
+    }
+    pub fn from_variant(variant: &glib::Variant) -> Option<char> {
+        variant.str()?.chars().next()
+    }
+}
+
+fn install_schema<O: SettingsObject>() {
+    let dir = std::env::temp_dir().join(format!("gobject-settings-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(format!("{}.gschema.xml", O::SETTINGS_SCHEMA));
+    std::fs::write(file, O::settings_schema_xml()).unwrap();
+    let status = std::process::Command::new("glib-compile-schemas")
+        .arg(&dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    std::env::set_var("GSETTINGS_SCHEMA_DIR", &dir);
+    std::env::set_var("GSETTINGS_BACKEND", "memory");
+}
+
+#[test]
+fn settings_schema() {
+    let xml = Preferences::settings_schema_xml();
+    assert!(
+        xml.contains("<schema id=\"org.example.Preferences\" path=\"/org/example/Preferences/\">")
+    );
+
+    let keys = Preferences::settings_keys();
+    let names = keys.iter().map(|k| k.name).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "volume",
+            "title",
+            "color-scheme",
+            "last-opened",
+            "window-size"
+        ]
+    );
+    assert_eq!(keys[0].variant_type.as_str(), "i");
+    assert_eq!(keys[0].default.get::<i32>(), Some(50));
+    assert_eq!(keys[0].summary.as_deref(), Some("Playback volume"));
+    assert_eq!(keys[1].variant_type.as_str(), "s");
+    assert_eq!(keys[1].default.str(), Some(""));
+    assert_eq!(keys[1].summary, None);
+    assert_eq!(keys[2].variant_type.as_str(), "s");
+    assert_eq!(keys[2].default.str(), Some("light"));
+    assert_eq!(keys[3].variant_type.as_str(), "ms");
+    assert!(keys[3].default.as_maybe().is_none());
+    assert_eq!(keys[4].variant_type.as_str(), "(ii)");
+    assert_eq!(keys[4].default.get::<(i32, i32)>(), Some((0, 0)));
+
+    let keys = App::settings_keys();
+    let names = keys.iter().map(|k| k.name).collect::<Vec<_>>();
+    assert_eq!(names, ["dark-mode", "accent"]);
+    assert_eq!(keys[0].variant_type.as_str(), "b");
+    assert_eq!(keys[0].default.get::<bool>(), Some(false));
+    assert_eq!(keys[1].variant_type.as_str(), "s");
+    assert_eq!(keys[1].default.str(), Some("b"));
+
+    install_schema::<Preferences>();
+    install_schema::<App>();
+    let settings = gio::Settings::new(Preferences::SETTINGS_SCHEMA);
+    settings.set_string("title", "Hello").unwrap();
+
+    let prefs = glib::Object::new::<Preferences>(&[]).unwrap();
+    assert_eq!(prefs.volume(), 50);
+    assert_eq!(prefs.title(), "Hello");
+    prefs.set_volume(80);
+    assert_eq!(settings.int("volume"), 80);
+    settings.set_int("volume", 20).unwrap();
+    assert_eq!(prefs.volume(), 20);
+
+    settings.set_string("color-scheme", "dark").unwrap();
+    assert_eq!(prefs.theme(), Theme::Dark);
+    prefs.set_theme(Theme::Light);
+    assert_eq!(settings.string("color-scheme"), "dark");
+
+    let date = glib::DateTime::from_utc(2022, 5, 1, 12, 0, 0.).unwrap();
+    prefs.set_last_opened(Some(date.clone()));
+    let stored = settings.value("last-opened").as_maybe().unwrap();
+    assert_eq!(stored.str(), Some(date.format_iso8601().unwrap().as_str()));
+    settings
+        .set_value("last-opened", &None::<String>.to_variant())
+        .unwrap();
+    assert_eq!(prefs.last_opened(), None);
+
+    assert_eq!(prefs.window_size(), Some((0i32, 0i32).to_variant()));
+    prefs.set_window_size(Some((800i32, 600i32).to_variant()));
+    let stored = settings.value("window-size");
+    assert_eq!(stored.get::<(i32, i32)>(), Some((800, 600)));
+
+    let settings = gio::Settings::new(App::SETTINGS_SCHEMA);
+    settings.set_boolean("dark-mode", true).unwrap();
+    let app = glib::Object::new::<App>(&[]).unwrap();
+    let dark_mode = app.lookup_action("dark-mode").unwrap();
+    assert_eq!(dark_mode.state().unwrap().get::<bool>(), Some(true));
+    dark_mode.activate(None);
+    assert!(!settings.boolean("dark-mode"));
+    settings.set_boolean("dark-mode", true).unwrap();
+    assert_eq!(dark_mode.state().unwrap().get::<bool>(), Some(true));
+
+    let accent = app.lookup_action("accent").unwrap();
+    assert_eq!(accent.state().unwrap().str(), Some("b"));
+    accent.change_state(&"g".to_variant());
+    assert_eq!(settings.string("accent"), "g");
+    accent.change_state(&"G".to_variant());
+    assert_eq!(settings.string("accent"), "g");
+    settings.set_string("accent", "r").unwrap();
+    assert_eq!(accent.state().unwrap().str(), Some("r"));
+    settings.set_string("accent", "R").unwrap();
+    assert_eq!(accent.state().unwrap().str(), Some("r"));
+}
@@ -0,0 +1,124 @@
+use glib::prelude::*;
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+fn log(msg: String) {
+    LOG.with(|log| log.borrow_mut().push(msg));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.take())
+}
+
+#[gobject::class(final)]
+mod hooked {
+    use glib::subclass::SignalInvocationHint;
+    use std::ops::ControlFlow;
+
+    #[derive(Default)]
+    pub struct Hooked {}
+    impl Hooked {
+        #[signal(detailed)]
+        fn changed(&self, val: u32) {}
+        #[signal(run_last)]
+        fn compute(&self, val: u32) -> u32 {
+            val * 2
+        }
+        #[accumulator(signal = "compute")]
+        fn compute_acc(accu: u32, val: u32) -> ControlFlow<Option<u32>, Option<u32>> {
+            ControlFlow::Continue(Some(accu + val))
+        }
+        #[signal_hook("changed")]
+        fn log_changed(hint: &SignalInvocationHint, _obj: super::Hooked, val: u32) {
+            let detail = hint.detail().map(|q| q.as_str().to_owned());
+            super::log(format!("changed {:?} {}", detail, val));
+        }
+        #[signal_hook("changed::size")]
+        pub(super) fn log_size(_obj: super::Hooked, val: u32) -> bool {
+            super::log(format!("size {}", val));
+            val < 10
+        }
+        #[signal_hook("compute")]
+        pub(super) fn log_compute(_obj: super::Hooked, val: u32) {
+            super::log(format!("compute {}", val));
+        }
+    }
+}
+
+#[gobject::class(final)]
+mod labelled {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct Labelled {
+        #[property(get, set)]
+        label: RefCell<String>,
+    }
+}
+
+#[gobject::class(final)]
+mod watcher {
+    #[derive(Default)]
+    pub struct Watcher {}
+    impl Watcher {
+        #[signal_hook("notify::label", type = "glib::Object")]
+        pub(super) fn label_changed(obj: glib::Object, _pspec: glib::ParamSpec) {
+            if let Some(obj) = obj.downcast_ref::<super::Labelled>() {
+                super::log(format!("label {}", obj.label()));
+            }
+        }
+    }
+}
+
+#[test]
+fn detailed_hooks() {
+    let obj = glib::Object::new::<Hooked>(&[]).unwrap();
+    take_log();
+
+    obj.emit_changed(None, 1);
+    assert_eq!(take_log(), ["changed None 1"]);
+
+    obj.emit_changed(Some("size".into()), 5);
+    assert_eq!(take_log(), ["changed Some(\"size\") 5", "size 5"]);
+
+    obj.emit_changed(Some("color".into()), 6);
+    assert_eq!(take_log(), ["changed Some(\"color\") 6"]);
+
+    assert!(Hooked::log_size_hook().is_installed());
+    obj.emit_changed(Some("size".into()), 12);
+    assert_eq!(take_log(), ["changed Some(\"size\") 12", "size 12"]);
+    assert!(!Hooked::log_size_hook().is_installed());
+
+    obj.emit_changed(Some("size".into()), 3);
+    assert_eq!(take_log(), ["changed Some(\"size\") 3"]);
+}
+
+#[test]
+fn accumulator_hooks() {
+    let obj = glib::Object::new::<Hooked>(&[]).unwrap();
+    take_log();
+
+    obj.connect_compute(|_, val| val + 1);
+    assert_eq!(obj.emit_compute(3), 10);
+    assert_eq!(take_log(), ["compute 3"]);
+
+    assert!(Hooked::log_compute_hook().remove());
+    assert!(!Hooked::log_compute_hook().remove());
+    assert_eq!(obj.emit_compute(4), 13);
+    assert!(take_log().is_empty());
+}
+
+#[test]
+fn foreign_hooks() {
+    let _watcher = glib::Object::new::<Watcher>(&[]).unwrap();
+    let obj = glib::Object::new::<Labelled>(&[]).unwrap();
+    take_log();
+
+    obj.set_label("Hello".into());
+    assert_eq!(take_log(), ["label Hello"]);
+    obj.notify("label");
+    assert_eq!(take_log(), ["label Hello"]);
+}
@@ -112,3 +112,312 @@ fn signals() {
         "first(class(b)), add(closure(b))"
     );
 }
+
+#[gobject::class(final)]
+mod obj_async_signals {
+    use std::ops::ControlFlow;
+
+    #[derive(Default)]
+    pub struct AsyncSignals {}
+    impl AsyncSignals {
+        #[signal(run_last)]
+        async fn load(&self, url: String) -> u32 {
+            glib::timeout_future(std::time::Duration::ZERO).await;
+            url.len() as u32
+        }
+        #[signal(run_last)]
+        async fn total(&self, val: u32) -> u32 {
+            val
+        }
+        #[accumulator(signal = "total")]
+        fn total_acc(accu: u32, val: u32) -> ControlFlow<Option<u32>, Option<u32>> {
+            ControlFlow::Continue(Some(accu + val))
+        }
+    }
+}
+
+#[test]
+fn async_signals() {
+    let ctx = glib::MainContext::default();
+    let obj = glib::Object::new::<AsyncSignals>(&[]).unwrap();
+
+    assert_eq!(ctx.block_on(obj.emit_load_future("abc".into())), 3);
+    obj.connect_load(|_, url| async move { url.len() as u32 * 2 });
+    assert_eq!(ctx.block_on(obj.emit_load_future("abcd".into())), 4);
+    assert_eq!(obj.emit_load("abcde".into()), 0);
+
+    assert_eq!(ctx.block_on(obj.emit_total_future(2)), 2);
+    obj.connect_total(|_, val| async move { val * 10 });
+    assert_eq!(ctx.block_on(obj.emit_total_future(2)), 22);
+}
+
+#[test]
+fn scoped_handlers() {
+    use std::{cell::Cell, rc::Rc};
+
+    let signals = glib::Object::new::<Signals>(&[]).unwrap();
+    let count = Rc::new(Cell::new(0));
+
+    let guard = signals.connect_noparam_scoped({
+        let count = count.clone();
+        move |_| count.set(count.get() + 1)
+    });
+    signals.emit_noparam();
+    assert_eq!(count.get(), 1);
+    guard.block();
+    signals.emit_noparam();
+    assert_eq!(count.get(), 1);
+    guard.unblock();
+    assert_eq!(guard.object().as_ref(), Some(&signals));
+    drop(guard);
+    signals.emit_noparam();
+    assert_eq!(count.get(), 1);
+
+    let guards = gobject::SignalGuards::new();
+    guards.add(signals.connect_param_scoped({
+        let count = count.clone();
+        move |_, hello| count.set(count.get() + hello)
+    }));
+    guards.add(signals.connect_has_detail_scoped(None, |_, val| val));
+    assert_eq!(guards.len(), 2);
+    signals.emit_param(10);
+    assert_eq!(count.get(), 11);
+    guards.clear();
+    assert!(guards.is_empty());
+    signals.emit_param(10);
+    assert_eq!(count.get(), 11);
+
+    let guard = signals.connect_noparam_scoped(|_| {});
+    drop(signals);
+    drop(guard);
+}
+
+#[gobject::class(final)]
+mod obj_arg_signals {
+    #[derive(Default)]
+    pub struct ArgSignals {}
+    impl ArgSignals {
+        #[signal(run_first)]
+        fn bytes(&self, data: &[u8]) -> u32 {
+            data.iter().map(|b| *b as u32).sum()
+        }
+        #[signal(run_first)]
+        fn points(&self, points: &[(i32, i32)]) -> i32 {
+            points.iter().map(|(x, y)| x * y).sum()
+        }
+        #[signal(run_first)]
+        fn owned(&self, items: Vec<u32>) -> u32 {
+            items.len() as u32
+        }
+        #[signal(run_first)]
+        fn peer(&self, other: Option<&super::ArgSignals>) -> bool {
+            other.is_some()
+        }
+        #[signal(run_first)]
+        fn variant(&self, v: &glib::Variant) -> i32 {
+            v.get::<i32>().unwrap_or_default()
+        }
+        #[signal(run_last)]
+        async fn measure(&self, data: &[u8], label: Option<&str>) -> u32 {
+            (data.len() + label.map(str::len).unwrap_or(0)) as u32
+        }
+    }
+}
+
+#[test]
+fn borrowed_args() {
+    use glib::prelude::*;
+
+    let obj = glib::Object::new::<ArgSignals>(&[]).unwrap();
+
+    assert_eq!(obj.emit_bytes(&[1, 2, 3]), 6);
+    let bytes = glib::Bytes::from(&[4u8, 5][..]);
+    assert_eq!(obj.emit_by_name::<u32>("bytes", &[&bytes]), 9);
+    obj.connect_bytes(|_, data| data.len() as u32);
+    assert_eq!(obj.emit_bytes(&[1, 2, 3]), 3);
+
+    assert_eq!(obj.emit_points(&[(1, 2), (3, 4)]), 14);
+    obj.connect_points(|_, points| points.len() as i32);
+    assert_eq!(obj.emit_points(&[(1, 2), (3, 4)]), 2);
+
+    assert_eq!(obj.emit_owned(vec![1, 2, 3]), 3);
+    obj.connect_owned(|_, items| items.into_iter().sum());
+    assert_eq!(obj.emit_owned(vec![1, 2, 3]), 6);
+
+    let other = glib::Object::new::<ArgSignals>(&[]).unwrap();
+    assert!(!obj.emit_peer(None));
+    assert!(obj.emit_peer(Some(&other)));
+    obj.connect_peer(|obj, other| other == Some(obj));
+    assert!(obj.emit_peer(Some(&obj)));
+    assert!(!obj.emit_peer(Some(&other)));
+
+    assert_eq!(obj.emit_variant(&5i32.to_variant()), 5);
+
+    let ctx = glib::MainContext::default();
+    assert_eq!(
+        ctx.block_on(obj.emit_measure_future(&[1, 2], Some("abc"))),
+        5
+    );
+    obj.connect_measure(|_, data, label| {
+        let len = (data.len() * 10 + label.map(str::len).unwrap_or(0)) as u32;
+        async move { len }
+    });
+    assert_eq!(ctx.block_on(obj.emit_measure_future(&[1, 2], None)), 20);
+}
+
+#[gobject::class(final)]
+mod obj_forward_child {
+    #[derive(Default)]
+    pub struct ForwardChild {}
+    impl ForwardChild {
+        #[signal]
+        fn changed(&self, val: u32) {}
+        #[signal(detailed)]
+        fn item(&self, name: String) {}
+    }
+}
+
+#[gobject::class(final)]
+mod obj_forward_parent {
+    #[derive(Default)]
+    pub struct ForwardParent {
+        #[property(get, set, object)]
+        child: gobject::WeakCell<super::ForwardChild>,
+        #[property(get, set, object, construct_only)]
+        fixed: gobject::ConstructOnlyCell<super::ForwardChild>,
+    }
+    impl ForwardParent {
+        #[signal(forward_from = "self.child")]
+        fn changed(&self, val: u32) {}
+        #[signal(forward_from = "self.fixed", signal = "changed")]
+        fn fixed_changed(&self, val: u32) {}
+        #[signal(detailed, forward_from = "self.child")]
+        fn item(&self, name: String) {}
+    }
+}
+
+#[test]
+fn forwarding() {
+    use glib::prelude::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    let fixed = glib::Object::new::<ForwardChild>(&[]).unwrap();
+    let parent = glib::Object::new::<ForwardParent>(&[("fixed", &fixed)]).unwrap();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    parent.connect_changed({
+        let log = log.clone();
+        move |_, val| log.borrow_mut().push(format!("changed {}", val))
+    });
+    parent.connect_fixed_changed({
+        let log = log.clone();
+        move |_, val| log.borrow_mut().push(format!("fixed {}", val))
+    });
+    parent.connect_item(Some("a".into()), {
+        let log = log.clone();
+        move |_, name| log.borrow_mut().push(format!("item a {}", name))
+    });
+
+    fixed.emit_changed(1);
+    assert_eq!(log.take(), ["fixed 1"]);
+
+    let first = glib::Object::new::<ForwardChild>(&[]).unwrap();
+    first.emit_changed(2);
+    assert!(log.borrow().is_empty());
+    parent.set_property("child", &first);
+    first.emit_changed(3);
+    first.emit_item(Some("a".into()), "x".into());
+    first.emit_item(Some("b".into()), "y".into());
+    assert_eq!(log.take(), ["changed 3", "item a x"]);
+
+    let second = glib::Object::new::<ForwardChild>(&[]).unwrap();
+    parent.set_property("child", &second);
+    first.emit_changed(4);
+    second.emit_changed(5);
+    assert_eq!(log.take(), ["changed 5"]);
+
+    parent.set_property("child", None::<ForwardChild>);
+    second.emit_changed(6);
+    assert!(log.borrow().is_empty());
+}
+
+#[gobject::class(final)]
+mod obj_forward_mismatch {
+    #[derive(Default)]
+    pub struct ForwardMismatch {
+        #[property(get, set, object)]
+        child: gobject::WeakCell<super::ForwardChild>,
+    }
+    impl ForwardMismatch {
+        #[signal(forward_from = "self.child", signal = "item")]
+        fn count(&self, val: u32) {}
+    }
+}
+
+#[test]
+fn forwarding_mismatch() {
+    use std::{cell::Cell, rc::Rc};
+
+    let parent = glib::Object::new::<ForwardMismatch>(&[]).unwrap();
+    let count = Rc::new(Cell::new(0));
+    parent.connect_count({
+        let count = count.clone();
+        move |_, _| count.set(count.get() + 1)
+    });
+    let child = glib::Object::new::<ForwardChild>(&[]).unwrap();
+    parent.set_property("child", &child);
+    child.emit_item(None, "x".into());
+    assert_eq!(count.get(), 0);
+}
+
+#[gobject::enum_]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    #[enum_value(nick = "body-text")]
+    Body,
+}
+
+#[gobject::class(final)]
+mod obj_typed_detail {
+    #[derive(Default)]
+    pub struct TypedDetail {}
+    impl TypedDetail {
+        #[signal(detailed = "super::Field")]
+        fn changed(&self, val: u32) {}
+    }
+}
+
+#[test]
+fn typed_details() {
+    use glib::prelude::*;
+    use gobject::SignalDetail;
+    use std::{cell::RefCell, rc::Rc};
+
+    assert_eq!(Field::Body.to_quark().as_str(), "body-text");
+    assert_eq!(Field::from_quark("title".into()), Some(Field::Title));
+    assert_eq!(Field::from_quark("color".into()), None);
+
+    let obj = glib::Object::new::<TypedDetail>(&[]).unwrap();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    obj.connect_changed(Some(Field::Title), {
+        let log = log.clone();
+        move |_, val| log.borrow_mut().push(format!("title {}", val))
+    });
+    obj.connect_changed(None, {
+        let log = log.clone();
+        move |_, val| log.borrow_mut().push(format!("any {}", val))
+    });
+    obj.connect_local("changed::body-text", false, {
+        let log = log.clone();
+        move |args| {
+            let val = args[1].get::<u32>().unwrap();
+            log.borrow_mut().push(format!("body {}", val));
+            None
+        }
+    });
+
+    obj.emit_changed(Field::Title, 1);
+    assert_eq!(log.take(), ["title 1", "any 1"]);
+    obj.emit_changed(Field::Body, 2);
+    assert_eq!(log.take(), ["any 2", "body 2"]);
+}
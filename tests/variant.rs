@@ -241,3 +241,53 @@ fn convert_paths() {
     let other: Conversions = result.get().unwrap();
     assert_eq!(other.datetime().unwrap().year(), 1980);
 }
+
+#[gobject::boxed]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[variant(to, from)]
+pub struct BoxedTuple {
+    name: String,
+    #[variant(skip)]
+    cached: Option<u64>,
+    #[variant(variant_type_str = "x")]
+    count: i64,
+}
+
+#[gobject::boxed]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[variant(to, from, dict)]
+pub struct BoxedDict {
+    my_name: String,
+    #[variant(skip_from)]
+    flag: bool,
+}
+
+#[test]
+fn boxed_variant() {
+    assert_eq!("(sx)", BoxedTuple::static_variant_type().as_str());
+    let boxed = BoxedTuple {
+        name: String::from("hello"),
+        cached: Some(5),
+        count: -3,
+    };
+    let result = boxed.to_variant();
+    assert_eq!(result.to_string(), "('hello', int64 -3)");
+    let other: BoxedTuple = result.get().unwrap();
+    assert_eq!(other.name, "hello");
+    assert_eq!(other.cached, None);
+    assert_eq!(other.count, -3);
+    assert!("hello".to_variant().get::<BoxedTuple>().is_none());
+
+    assert_eq!("a{sv}", BoxedDict::static_variant_type().as_str());
+    let boxed = BoxedDict {
+        my_name: String::from("world"),
+        flag: true,
+    };
+    let result = boxed.to_variant();
+    let dict = glib::VariantDict::new(Some(&result));
+    assert_eq!(dict.lookup::<String>("my-name").unwrap().unwrap(), "world");
+    assert_eq!(dict.lookup::<bool>("flag").unwrap(), Some(true));
+    let other: BoxedDict = result.get().unwrap();
+    assert_eq!(other.my_name, "world");
+    assert!(!other.flag);
+}